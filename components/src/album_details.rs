@@ -28,6 +28,9 @@ pub fn AlbumDetails(
 
     let mut is_selection_mode = use_signal(|| false);
     let mut selected_tracks = use_signal(|| HashSet::<PathBuf>::new());
+    let mut show_tag_editor = use_signal(|| false);
+    let favorites_store = use_context::<Signal<reader::FavoritesStore>>();
+    let config = use_context::<Signal<config::AppConfig>>();
 
     let lib = library.read();
     let album = match lib.albums.iter().find(|a| a.id == album_id) {
@@ -38,16 +41,19 @@ pub fn AlbumDetails(
     let mut tracks: Vec<_> = lib
         .tracks
         .iter()
-        .filter(|t| t.album == album.title)
+        .filter(|t| t.album_id == album.id)
         .cloned()
         .collect();
     tracks.sort_by(|a, b| {
-        a.track_number
-            .cmp(&b.track_number)
+        a.disc_number
+            .unwrap_or(1)
+            .cmp(&b.disc_number.unwrap_or(1))
+            .then_with(|| a.track_number.cmp(&b.track_number))
             .then_with(|| a.title.cmp(&b.title))
     });
 
     let album_cover = utils::format_artwork_url(album.cover_path.as_ref());
+    let album_rating = favorites_store.read().album_rating(&album.id);
 
     rsx! {
         div {
@@ -60,6 +66,19 @@ pub fn AlbumDetails(
                     i { class: "fa-solid fa-arrow-left" }
                     "{i18n::t(\"back_to_albums\")}"
                 }
+                div {
+                    title: i18n::t("album_rating"),
+                    crate::star_rating::StarRating {
+                        rating: album_rating,
+                        size: "text-base",
+                        on_rate: {
+                            let album_id = album.id.clone();
+                            move |stars: u8| {
+                                crate::star_rating::rate_album(album_id.clone(), stars, favorites_store, config);
+                            }
+                        },
+                    }
+                }
             }
 
             crate::showcase::Showcase {
@@ -68,6 +87,7 @@ pub fn AlbumDetails(
                 cover_url: album_cover,
                 tracks: tracks.clone(),
                 library: library,
+                group_by_disc: true,
                 is_selection_mode: is_selection_mode(),
                 selected_tracks: selected_tracks.read().clone(),
                 on_long_press: {
@@ -210,7 +230,19 @@ pub fn AlbumDetails(
                     on_cancel: move |_| {
                         is_selection_mode.set(false);
                         selected_tracks.write().clear();
-                    }
+                    },
+                    on_edit_tags: move |_| show_tag_editor.set(true),
+                }
+            }
+
+            if show_tag_editor() {
+                crate::tag_editor::TagEditor {
+                    paths: selected_tracks.read().iter().cloned().collect::<Vec<_>>(),
+                    on_close: move |_| {
+                        show_tag_editor.set(false);
+                        is_selection_mode.set(false);
+                        selected_tracks.write().clear();
+                    },
                 }
             }
 
@@ -262,6 +294,7 @@ pub fn AlbumDetails(
                                 name,
                                 tracks: selected_paths,
                                 cover_path: None,
+                                collage_path: None,
                             });
                         }
                         show_playlist_modal.set(false);
@@ -81,6 +81,11 @@ pub fn Bottombar(
         }
     };
 
+    let current_rating = queue
+        .read()
+        .get(*current_queue_index.read())
+        .map(|track| favorites_store.read().track_rating(track));
+
     let heart_class = if is_favorite {
         "ml-2 text-red-400 hover:text-red-300 transition-colors"
     } else {
@@ -118,6 +123,18 @@ pub fn Bottombar(
                     class: "flex flex-col min-w-0",
                     span { class: "text-sm font-bold text-white/90 truncate hover:underline cursor-pointer", "{current_song_title}" }
                     span { class: "text-xs text-slate-400 truncate hover:text-white/70 cursor-pointer", "{current_song_artist}" }
+                    if let Some(rating) = current_rating {
+                        crate::star_rating::StarRating {
+                            rating,
+                            size: "text-[10px]",
+                            on_rate: move |stars: u8| {
+                                let path = queue.peek().get(*current_queue_index.peek()).map(|t| t.path.clone());
+                                if let Some(path) = path {
+                                    crate::star_rating::rate_track(path, stars, favorites_store, queue, config);
+                                }
+                            },
+                        }
+                    }
                 }
                 button {
                     class: "{heart_class}",
@@ -0,0 +1,409 @@
+use config::AppConfig;
+use dioxus::prelude::*;
+use reader::{
+    BookmarkStore, FavoritesStore, HistoryStore, Library, PlaylistStore, SavedQueueStore,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::track_info::{format_quality, format_size};
+#[cfg(not(target_arch = "wasm32"))]
+use reader::duplicates::{DuplicateGroup, find_duplicates};
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::HashSet;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+/// Copies scoring below this against the kept copy are treated as a different
+/// recording that only shares its tags.
+#[cfg(not(target_arch = "wasm32"))]
+const FINGERPRINT_THRESHOLD: f32 = 0.9;
+
+/// The copy kept in each group and the others to hide or trash.
+#[cfg(not(target_arch = "wasm32"))]
+type Plan = Vec<(PathBuf, Vec<PathBuf>)>;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn format_label(track: &reader::models::Track) -> String {
+    track
+        .path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_uppercase())
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[component]
+pub fn DuplicatesReport(
+    mut library: Signal<Library>,
+    mut config: Signal<AppConfig>,
+    mut playlist_store: Signal<PlaylistStore>,
+    mut favorites_store: Signal<FavoritesStore>,
+    on_close: EventHandler,
+) -> Element {
+    let mut history = use_context::<Signal<HistoryStore>>();
+    let mut bookmarks = use_context::<Signal<BookmarkStore>>();
+    let mut saved_queues = use_context::<Signal<SavedQueueStore>>();
+    let mut groups = use_signal(|| find_duplicates(&library.peek().tracks));
+    // the copy to keep in each group, best quality unless the user picks another
+    let mut keep = use_signal(|| {
+        groups
+            .peek()
+            .iter()
+            .map(|g| g.best().path.clone())
+            .collect::<Vec<PathBuf>>()
+    });
+    // copies the fingerprint check says are a different recording
+    let mut mismatched = use_signal(HashSet::<PathBuf>::new);
+    let mut checking = use_signal(|| false);
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+    // the trash plan waiting for the user to confirm it
+    let mut confirm_trash = use_signal(|| None::<Plan>);
+
+    let check_fingerprints = move |_| {
+        let pending: Vec<(PathBuf, Vec<PathBuf>)> = groups
+            .peek()
+            .iter()
+            .zip(keep.peek().iter())
+            .map(|(group, kept)| {
+                let others = group
+                    .tracks
+                    .iter()
+                    .map(|t| t.path.clone())
+                    .filter(|p| p != kept)
+                    .collect();
+                (kept.clone(), others)
+            })
+            .collect();
+        checking.set(true);
+        spawn(async move {
+            let result = tokio::task::spawn_blocking(move || {
+                let mut different = Vec::new();
+                for (kept, others) in pending {
+                    // an undecodable kept copy proves nothing, so leave the group alone
+                    let Some(reference) = player::fingerprint::fingerprint(&kept) else {
+                        continue;
+                    };
+                    for other in others {
+                        // a copy we can't fingerprint can't be shown to match, so keep it
+                        let same = player::fingerprint::fingerprint(&other)
+                            .is_some_and(|f| f.similarity(&reference) >= FINGERPRINT_THRESHOLD);
+                        if !same {
+                            different.push((kept.clone(), other));
+                        }
+                    }
+                }
+                different
+            })
+            .await;
+            checking.set(false);
+            if let Ok(different) = result {
+                // skip groups whose kept copy was changed while checking
+                let kept = keep.peek();
+                mismatched.set(
+                    different
+                        .into_iter()
+                        .filter(|(reference, _)| kept.contains(reference))
+                        .map(|(_, other)| other)
+                        .collect(),
+                );
+            }
+        });
+    };
+
+    let plan = move || -> Plan {
+        groups
+            .peek()
+            .iter()
+            .zip(keep.peek().iter())
+            .map(|(group, kept)| {
+                let others = group
+                    .tracks
+                    .iter()
+                    .map(|t| t.path.clone())
+                    .filter(|p| p != kept && !mismatched.peek().contains(p))
+                    .collect();
+                (kept.clone(), others)
+            })
+            .collect()
+    };
+
+    // moves the references of the removed copies onto the kept ones and drops
+    // them from the library
+    let mut apply = move |done: Plan, failures: Vec<String>| {
+        let mut resolved = HashSet::new();
+        for (kept, removed) in done {
+            if removed.is_empty() {
+                continue;
+            }
+
+            reader::duplicates::merge_references(
+                &kept,
+                &removed,
+                &mut favorites_store.write(),
+                &mut playlist_store.write(),
+                &mut history.write(),
+                &mut bookmarks.write(),
+                &mut saved_queues.write(),
+            );
+            let mut lib = library.write();
+            for path in &removed {
+                lib.remove_track(path);
+            }
+            resolved.extend(removed);
+        }
+
+        {
+            let mut lib = library.write();
+            lib.prune_albums();
+            lib.prune_artists();
+        }
+
+        // keep groups that still have more than one copy, e.g. after a failed delete
+        let mut remaining: Vec<(DuplicateGroup, PathBuf)> = Vec::new();
+        for (mut group, kept) in groups
+            .peek()
+            .iter()
+            .cloned()
+            .zip(keep.peek().iter().cloned())
+        {
+            group.tracks.retain(|t| !resolved.contains(&t.path));
+            let others = group
+                .tracks
+                .iter()
+                .filter(|t| t.path != kept && !mismatched.peek().contains(&t.path))
+                .count();
+            if others > 0 {
+                remaining.push((group, kept));
+            }
+        }
+        let (next_groups, next_keep): (Vec<_>, Vec<_>) = remaining.into_iter().unzip();
+        groups.set(next_groups);
+        keep.set(next_keep);
+
+        busy.set(false);
+        if !failures.is_empty() {
+            error.set(Some(i18n::t_with(
+                "duplicates_trash_failed",
+                &[("error", failures.join("\n"))],
+            )));
+        }
+    };
+
+    let hide = move |_| {
+        let done = plan();
+        config
+            .write()
+            .scan_rules
+            .hidden_files
+            .extend(done.iter().flat_map(|(_, others)| others.iter().cloned()));
+        error.set(None);
+        apply(done, Vec::new());
+    };
+
+    let trash = move |_| {
+        let Some(pending) = confirm_trash.write().take() else {
+            return;
+        };
+        busy.set(true);
+        error.set(None);
+        spawn(async move {
+            let result = tokio::task::spawn_blocking(move || {
+                let mut failures = Vec::new();
+                let done: Plan = pending
+                    .into_iter()
+                    .map(|(kept, others)| {
+                        let removed = others
+                            .into_iter()
+                            .filter(|path| match trash::delete(path) {
+                                Ok(()) => true,
+                                Err(e) => {
+                                    failures.push(format!("{}: {e}", path.display()));
+                                    false
+                                }
+                            })
+                            .collect();
+                        (kept, removed)
+                    })
+                    .collect();
+                (done, failures)
+            })
+            .await;
+            match result {
+                Ok((done, failures)) => apply(done, failures),
+                Err(e) => {
+                    busy.set(false);
+                    error.set(Some(i18n::t_with(
+                        "duplicates_trash_failed",
+                        &[("error", e.to_string())],
+                    )));
+                }
+            }
+        });
+    };
+
+    let count = groups.read().len();
+    let trash_paths: Vec<PathBuf> = confirm_trash
+        .read()
+        .iter()
+        .flatten()
+        .flat_map(|(_, others)| others.iter().cloned())
+        .collect();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/80 flex items-center justify-center z-50",
+            onclick: move |_| on_close.call(()),
+            div {
+                class: "bg-neutral-900 rounded-xl border border-white/10 w-full max-w-2xl p-6 max-h-[85vh] overflow-y-auto",
+                onclick: move |e| e.stop_propagation(),
+                h2 { class: "text-xl font-bold text-white mb-1", "{i18n::t(\"duplicates\")}" }
+                p {
+                    class: "text-sm text-slate-400 mb-4",
+                    if count == 0 {
+                        "{i18n::t(\"duplicates_none\")}"
+                    } else {
+                        "{i18n::t_with(\"duplicates_found\", &[(\"count\", count.to_string())])}"
+                    }
+                }
+
+                if let Some(err) = error() {
+                    p { class: "text-sm text-red-400 mb-4 whitespace-pre-wrap", "{err}" }
+                }
+
+                div { class: "space-y-4",
+                    for (i, group) in groups.read().iter().enumerate() {
+                        div {
+                            key: "{group.best().path.display()}",
+                            class: "rounded-lg border border-white/10 p-3",
+                            div { class: "text-white font-medium truncate", "{group.best().title}" }
+                            div { class: "text-xs text-slate-400 mb-2 truncate", "{group.best().artist}" }
+                            for track in group.tracks.iter() {
+                                {
+                                    let path = track.path.clone();
+                                    let group_paths: Vec<PathBuf> = group.tracks.iter().map(|t| t.path.clone()).collect();
+                                    let checked = keep.read().get(i) == Some(&track.path);
+                                    let different = mismatched.read().contains(&track.path);
+                                    let quality = format_quality(track.khz, track.bit_depth, track.bitrate);
+                                    let size = track.file_size.map(format_size).unwrap_or_default();
+                                    rsx! {
+                                        label {
+                                            key: "{track.path.display()}",
+                                            class: "flex items-start gap-3 py-1.5 cursor-pointer",
+                                            input {
+                                                r#type: "radio",
+                                                class: "mt-1",
+                                                name: "duplicate-{i}",
+                                                checked,
+                                                onchange: move |_| {
+                                                    if let Some(slot) = keep.write().get_mut(i) {
+                                                        *slot = path.clone();
+                                                    }
+                                                    // the check compared against the copy kept before
+                                                    mismatched.with_mut(|m| {
+                                                        for p in &group_paths {
+                                                            m.remove(p);
+                                                        }
+                                                    });
+                                                },
+                                            }
+                                            div { class: "min-w-0 flex-1",
+                                                div { class: "flex items-center gap-2 text-sm text-white",
+                                                    span { class: "text-xs font-mono bg-white/10 rounded px-1.5", "{format_label(track)}" }
+                                                    span { "{quality}" }
+                                                    if !size.is_empty() {
+                                                        span { class: "text-slate-500", "· {size}" }
+                                                    }
+                                                    if different {
+                                                        span { class: "text-xs text-amber-400", "{i18n::t(\"duplicates_different_recording\")}" }
+                                                    }
+                                                }
+                                                div { class: "text-xs text-slate-500 truncate", "{track.path.display()}" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div { class: "flex items-center justify-between mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm text-slate-400 hover:text-white transition-colors disabled:opacity-50",
+                        disabled: count == 0 || checking() || busy(),
+                        title: i18n::t("duplicates_fingerprint_hint").to_string(),
+                        onclick: check_fingerprints,
+                        i { class: if checking() { "fa-solid fa-spinner fa-spin mr-2" } else { "fa-solid fa-wave-square mr-2" } }
+                        "{i18n::t(\"duplicates_fingerprint\")}"
+                    }
+                    div { class: "flex gap-2",
+                        button {
+                            class: "px-4 py-2 text-sm text-slate-400 hover:text-white transition-colors",
+                            onclick: move |_| on_close.call(()),
+                            "{i18n::t(\"close\")}"
+                        }
+                        button {
+                            class: "px-4 py-2 text-sm bg-white/10 text-white rounded-lg hover:bg-white/20 transition-colors disabled:opacity-50",
+                            disabled: count == 0 || checking() || busy(),
+                            title: i18n::t("duplicates_hide_hint").to_string(),
+                            onclick: hide,
+                            "{i18n::t(\"duplicates_hide\")}"
+                        }
+                        button {
+                            class: "px-4 py-2 text-sm bg-white text-black rounded-lg font-medium hover:bg-white/90 transition-colors disabled:opacity-50",
+                            disabled: count == 0 || checking() || busy(),
+                            onclick: move |_| {
+                                let pending = plan();
+                                if pending.iter().any(|(_, others)| !others.is_empty()) {
+                                    confirm_trash.set(Some(pending));
+                                }
+                            },
+                            "{i18n::t(\"duplicates_trash\")}"
+                        }
+                    }
+                }
+
+                if !trash_paths.is_empty() {
+                    div { class: "mt-4 rounded-lg border border-red-500/30 bg-red-500/5 p-4",
+                        p {
+                            class: "text-sm text-white mb-2",
+                            "{i18n::t_with(\"duplicates_trash_confirm\", &[(\"count\", trash_paths.len().to_string())])}"
+                        }
+                        div { class: "max-h-40 overflow-y-auto mb-3",
+                            for path in trash_paths.iter() {
+                                div { key: "{path.display()}", class: "text-xs text-slate-400 truncate", "{path.display()}" }
+                            }
+                        }
+                        div { class: "flex justify-end gap-2",
+                            button {
+                                class: "px-4 py-2 text-sm text-slate-400 hover:text-white transition-colors",
+                                onclick: move |_| confirm_trash.set(None),
+                                "{i18n::t(\"cancel\")}"
+                            }
+                            button {
+                                class: "px-4 py-2 text-sm bg-red-500 text-white rounded-lg font-medium hover:bg-red-400 transition-colors",
+                                onclick: trash,
+                                "{i18n::t(\"duplicates_trash\")}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Needs local files to compare and remove.
+#[cfg(target_arch = "wasm32")]
+#[component]
+pub fn DuplicatesReport(
+    library: Signal<Library>,
+    config: Signal<AppConfig>,
+    playlist_store: Signal<PlaylistStore>,
+    favorites_store: Signal<FavoritesStore>,
+    on_close: EventHandler,
+) -> Element {
+    let _ = (library, config, playlist_store, favorites_store, on_close);
+    rsx! {}
+}
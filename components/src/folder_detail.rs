@@ -114,6 +114,7 @@ pub fn FolderDetail(
                                 name,
                                 tracks: vec![path],
                                 cover_path: None,
+                                collage_path: None,
                             });
                         }
                         show_playlist_modal.set(false);
@@ -3,6 +3,7 @@ use crate::titlebar::Titlebar;
 use config::AppConfig;
 use dioxus::document::eval;
 use dioxus::prelude::*;
+use hooks::use_lyrics::use_lyrics;
 use hooks::use_player_controller::{LoopMode, PlayerController};
 use player::player::Player;
 use reader::Library;
@@ -20,7 +21,7 @@ pub fn Fullscreen(
     mut current_song_title: Signal<String>,
     mut current_song_artist: Signal<String>,
     mut current_song_khz: Signal<u32>,
-    mut current_song_bitrate: Signal<u8>,
+    mut current_song_bitrate: Signal<u32>,
     mut current_song_cover_url: Signal<String>,
     mut current_song_album: Signal<String>,
     mut volume: Signal<f32>,
@@ -50,6 +51,15 @@ pub fn Fullscreen(
         }
     });
 
+    let quality = crate::track_info::format_quality(
+        *current_song_khz.read(),
+        queue
+            .read()
+            .get(*current_queue_index.read())
+            .and_then(|t| t.bit_depth),
+        *current_song_bitrate.read(),
+    );
+
     let format_time = |seconds: u64| {
         let minutes = seconds / 60;
         let seconds = seconds % 60;
@@ -83,26 +93,12 @@ pub fn Fullscreen(
 
     let mut config = use_context::<Signal<AppConfig>>();
 
-    let lyrics = use_resource(move || {
-        let title = current_song_title.read().clone();
-        let artist = current_song_artist.read().clone();
-        let album = current_song_album.read().clone();
-        let duration = *current_song_duration.read();
-
-        async move {
-            if !title.is_empty() {
-                if let Some(l) =
-                    utils::lyrics::fetch_lyrics(&artist, &title, &album, duration).await
-                {
-                    Some(l)
-                } else {
-                    Some(utils::lyrics::Lyrics::Plain(i18n::t("lyrics_not_found").to_string()))
-                }
-            } else {
-                None
-            }
-        }
-    });
+    let lyrics = use_lyrics(
+        current_song_title,
+        current_song_artist,
+        current_song_album,
+        current_song_duration,
+    );
 
     let active_lyric_index = use_memo(move || {
         if *active_tab.read() == 2 {
@@ -248,7 +244,7 @@ pub fn Fullscreen(
                 div {
                     class: "flex items-center gap-4 text-xs text-white/50 mb-6 w-full",
                     style: "max-width: 420px;",
-                    span { style: "font-size: 10px;", "{current_song_khz} / {current_song_bitrate}" }
+                    span { style: "font-size: 10px;", "{quality}" }
                 }
 
                 div {
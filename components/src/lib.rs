@@ -3,12 +3,19 @@ pub mod folder_detail;
 pub mod folder_picker;
 pub mod bottombar;
 pub mod dots_menu;
+pub mod duplicates;
 pub mod fullscreen;
+pub mod library_toolbar;
+pub mod listen_summary;
+pub mod offline_storage;
+pub mod playlist_convert;
 pub mod playlist_detail;
+pub mod playlist_files;
 pub mod playlist_modal;
 pub mod playlist_popups;
 pub mod reorder_buttons;
 pub mod rightbar;
+pub mod saved_queues;
 pub mod search_bar;
 pub mod search_genre_detail;
 pub mod search_genres;
@@ -18,6 +25,10 @@ pub mod settings_items;
 pub mod settings_popups;
 pub mod showcase;
 pub mod sidebar;
+pub mod smart_playlist;
 pub mod titlebar;
+pub mod star_rating;
 pub mod stat_card;
+pub mod tag_editor;
+pub mod track_info;
 pub mod track_row;
@@ -0,0 +1,45 @@
+use config::SortOrder;
+use dioxus::prelude::*;
+use hooks::use_library_items::{SORT_ORDERS, sort_order_label};
+
+#[component]
+pub fn LibraryToolbar(
+    mut sort_order: Signal<SortOrder>,
+    mut filter_query: Signal<String>,
+) -> Element {
+    let selected = SORT_ORDERS
+        .iter()
+        .position(|o| *o == *sort_order.read())
+        .unwrap_or(0);
+
+    rsx! {
+        div { class: "flex items-center gap-3",
+            div { class: "relative",
+                i { class: "fa-solid fa-filter absolute left-3 top-1/2 -translate-y-1/2 text-white/30 text-xs" }
+                input {
+                    class: "w-72 bg-white/5 border border-white/5 rounded-lg pl-8 pr-3 py-1.5 text-xs text-white placeholder:text-white/30 focus:outline-none focus:border-white/20",
+                    r#type: "text",
+                    placeholder: "{i18n::t(\"filter_tracks\")}",
+                    value: "{filter_query}",
+                    oninput: move |evt| filter_query.set(evt.value()),
+                }
+            }
+            select {
+                class: "bg-white/5 border border-white/5 rounded-lg px-3 py-1.5 text-xs text-white focus:outline-none",
+                value: "{selected}",
+                onchange: move |evt| {
+                    if let Some(order) = evt.value().parse::<usize>().ok().and_then(|i| SORT_ORDERS.get(i)) {
+                        sort_order.set(order.clone());
+                    }
+                },
+                for (idx , order) in SORT_ORDERS.iter().enumerate() {
+                    option {
+                        value: "{idx}",
+                        selected: idx == selected,
+                        "{i18n::t(sort_order_label(order))}"
+                    }
+                }
+            }
+        }
+    }
+}
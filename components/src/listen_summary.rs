@@ -0,0 +1,125 @@
+use crate::stat_card::StatCard;
+use dioxus::prelude::*;
+use reader::HistoryStore;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Period {
+    Week,
+    Month,
+    AllTime,
+}
+
+impl Period {
+    fn since(self, now: u64) -> u64 {
+        match self {
+            Period::Week => now.saturating_sub(7 * 86_400),
+            Period::Month => now.saturating_sub(30 * 86_400),
+            Period::AllTime => 0,
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn format_listened(secs: u64) -> String {
+    let hours = secs / 3600;
+    let minutes = (secs % 3600) / 60;
+    if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+fn format_ago(secs: u64) -> String {
+    if secs < 3600 {
+        i18n::t_with("minutes_ago", &[("count", (secs / 60).to_string())])
+    } else if secs < 86_400 {
+        i18n::t_with("hours_ago", &[("count", (secs / 3600).to_string())])
+    } else {
+        i18n::t_with("days_ago", &[("count", (secs / 86_400).to_string())])
+    }
+}
+
+/// Plays, skips and listening time for a period, and the tracks heard last.
+/// `server` picks server listens over local ones.
+#[component]
+pub fn ListenSummary(server: bool) -> Element {
+    let history = use_context::<Signal<HistoryStore>>();
+    let mut period = use_signal(|| Period::Week);
+
+    let now = unix_now();
+    let store = history.read();
+    let stats = store.stats(period().since(now), u64::MAX, |l| {
+        l.source.is_server() == server
+    });
+    let recent: Vec<_> = store
+        .recently_played(8, |l| l.source.is_server() == server)
+        .into_iter()
+        .cloned()
+        .collect();
+    drop(store);
+
+    let top_artist = stats
+        .top_artists
+        .first()
+        .map(|(artist, _)| artist.clone())
+        .unwrap_or_else(|| "-".to_string());
+    let skip_rate = format!("{:.0}%", stats.skip_rate() * 100.0);
+
+    let btn_active =
+        "px-3 py-1 text-xs rounded-md bg-white/10 text-white font-medium transition-all";
+    let btn_inactive =
+        "px-3 py-1 text-xs rounded-md text-white/40 hover:text-white/80 transition-all";
+
+    rsx! {
+        div { class: "mb-10",
+            div { class: "flex items-center justify-end mb-4",
+                div { class: "flex items-center gap-1 p-1 rounded-lg bg-white/5",
+                    for (value, label) in [
+                        (Period::Week, "last_7_days"),
+                        (Period::Month, "last_30_days"),
+                        (Period::AllTime, "all_time"),
+                    ] {
+                        button {
+                            class: if period() == value { btn_active } else { btn_inactive },
+                            onclick: move |_| period.set(value),
+                            "{i18n::t(label)}"
+                        }
+                    }
+                }
+            }
+            div { class: "grid grid-cols-2 lg:grid-cols-4 gap-4 mb-6",
+                StatCard { label: i18n::t("plays").to_string(), value: "{stats.plays}", icon: "fa-play" }
+                StatCard { label: i18n::t("skip_rate").to_string(), value: skip_rate, icon: "fa-forward" }
+                StatCard { label: i18n::t("time_listened").to_string(), value: format_listened(stats.listened_secs), icon: "fa-clock" }
+                StatCard { label: i18n::t("top_artist").to_string(), value: top_artist, icon: "fa-user" }
+            }
+            if !recent.is_empty() {
+                h2 { class: "text-lg font-semibold text-white mb-3", "{i18n::t(\"recently_played\")}" }
+                div { class: "flex flex-col space-y-1",
+                    for (idx, listen) in recent.into_iter().enumerate() {
+                        div {
+                            key: "{idx}",
+                            class: "flex items-center gap-3 px-4 py-2 rounded-xl bg-white/[0.02] text-sm",
+                            i {
+                                class: if listen.completed { "fa-solid fa-check text-emerald-400/70 text-xs w-4" } else { "fa-solid fa-forward text-slate-500 text-xs w-4" },
+                                title: if listen.completed { i18n::t("listen_completed") } else { i18n::t("listen_skipped") },
+                            }
+                            span { class: "text-white truncate", "{listen.title}" }
+                            span { class: "text-slate-400 truncate flex-1", "{listen.artist}" }
+                            span { class: "text-slate-500 tabular-nums shrink-0",
+                                "{format_ago(now.saturating_sub(listen.started_at))}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
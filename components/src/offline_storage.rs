@@ -0,0 +1,150 @@
+use dioxus::prelude::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::track_info::format_size;
+#[cfg(not(target_arch = "wasm32"))]
+use config::AppConfig;
+#[cfg(not(target_arch = "wasm32"))]
+use hooks::DownloadProgress;
+#[cfg(not(target_arch = "wasm32"))]
+use hooks::use_player_controller::PlayerController;
+#[cfg(not(target_arch = "wasm32"))]
+use reader::OfflineStore;
+#[cfg(not(target_arch = "wasm32"))]
+use reader::models::server_item_id;
+#[cfg(not(target_arch = "wasm32"))]
+use reader::offline::{CollectionKind, OfflineCollection};
+
+/// How much space the offline downloads take, what is downloading and which
+/// albums and playlists are kept offline.
+#[cfg(not(target_arch = "wasm32"))]
+#[component]
+pub fn OfflineStorage() -> Element {
+    let ctrl = use_context::<PlayerController>();
+    let config = use_context::<Signal<AppConfig>>();
+    let mut store = use_context::<Signal<OfflineStore>>();
+    let progress = use_context::<Signal<Option<DownloadProgress>>>();
+
+    let (used, pinned, waiting, collections) = {
+        let store = store.read();
+        let collections: Vec<(OfflineCollection, usize)> = store
+            .collections
+            .iter()
+            .map(|c| (c.clone(), store.downloaded(c)))
+            .collect();
+        (
+            store.used_bytes(),
+            store.pinned_bytes(),
+            store.queue.len(),
+            collections,
+        )
+    };
+    let limit = config.read().offline_cache_limit_mb * 1024 * 1024;
+    let bar_width = if limit == 0 {
+        "100%".to_string()
+    } else {
+        format!("{:.1}%", (used as f64 / limit as f64 * 100.0).min(100.0))
+    };
+    let usage = i18n::t_with(
+        "offline_usage",
+        &[
+            ("used", format_size(used)),
+            ("limit", format_size(limit)),
+            ("pinned", format_size(pinned)),
+        ],
+    );
+    let status = progress().map(|p| {
+        let title = ctrl
+            .library
+            .read()
+            .jellyfin_tracks
+            .iter()
+            .find(|t| server_item_id(&t.path.to_string_lossy()) == Some(p.track.as_str()))
+            .map(|t| t.title.clone())
+            .unwrap_or(p.track);
+        if p.no_room {
+            let size = format_size(p.total.unwrap_or(p.bytes));
+            return i18n::t_with("offline_no_room", &[("title", title), ("size", size)]);
+        }
+        let done = match p.total {
+            Some(total) => format!("{} / {}", format_size(p.bytes), format_size(total)),
+            None => format_size(p.bytes),
+        };
+        i18n::t_with("offline_downloading", &[("title", title), ("progress", done)])
+    });
+
+    rsx! {
+        div { class: "space-y-3 py-2",
+            div {
+                div { class: "h-1.5 rounded-full bg-white/10 overflow-hidden",
+                    div { class: "h-full bg-indigo-500", style: "width: {bar_width}" }
+                }
+                p { class: "text-xs text-slate-400 mt-2", "{usage}" }
+            }
+            if let Some(status) = status {
+                p { class: "text-xs text-slate-300", "{status}" }
+            }
+            if waiting > 0 {
+                p {
+                    class: "text-xs text-slate-400",
+                    "{i18n::t_with(\"offline_waiting\", &[(\"count\", waiting.to_string())])}"
+                }
+            }
+            if collections.is_empty() {
+                p { class: "text-sm text-white/30", "{i18n::t(\"no_offline_collections\")}" }
+            }
+            for (collection, downloaded) in collections {
+                {
+                    let kind = collection.kind;
+                    let pin_id = collection.id.clone();
+                    let remove_id = collection.id.clone();
+                    let is_pinned = collection.pinned;
+                    let icon = match kind {
+                        CollectionKind::Album => "fa-solid fa-compact-disc",
+                        CollectionKind::Playlist => "fa-solid fa-list",
+                    };
+                    let count = format!("{downloaded} / {}", collection.tracks.len());
+                    rsx! {
+                        div {
+                            key: "{collection.id}",
+                            class: "flex items-center gap-3 px-3 py-2 rounded-lg bg-white/5",
+                            i { class: "{icon} text-white/40" }
+                            span { class: "flex-1 min-w-0 text-sm text-white truncate", "{collection.name}" }
+                            span { class: "text-xs text-slate-400", "{count}" }
+                            button {
+                                class: if is_pinned { "p-1 text-indigo-400 hover:text-indigo-300" } else { "p-1 text-slate-500 hover:text-white" },
+                                title: if is_pinned { i18n::t("unpin_offline").to_string() } else { i18n::t("pin_offline").to_string() },
+                                onclick: move |_| store.write().set_pinned(kind, &pin_id, !is_pinned),
+                                i { class: "fa-solid fa-thumbtack text-xs" }
+                            }
+                            button {
+                                class: "p-1 text-slate-500 hover:text-red-400",
+                                title: i18n::t("remove_download"),
+                                onclick: move |_| {
+                                    store.write().remove_collection(kind, &remove_id);
+                                },
+                                i { class: "fa-solid fa-trash text-xs" }
+                            }
+                        }
+                    }
+                }
+            }
+            button {
+                class: "px-3 py-1.5 text-xs rounded-lg bg-white/10 text-white hover:bg-white/20 transition-colors disabled:opacity-40",
+                disabled: used == pinned,
+                onclick: move |_| {
+                    let removed = store.write().clear_unpinned();
+                    hooks::delete_offline_files(removed);
+                },
+                "{i18n::t(\"clear_unpinned_downloads\")}"
+            }
+        }
+    }
+}
+
+// Downloads need a disk to go to.
+#[cfg(target_arch = "wasm32")]
+#[component]
+pub fn OfflineStorage() -> Element {
+    rsx! {}
+}
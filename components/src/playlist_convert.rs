@@ -0,0 +1,217 @@
+use config::{AppConfig, MusicService};
+use dioxus::prelude::*;
+use reader::models::{Playlist, Track};
+use reader::track_match::{MatchStatus, TrackMatch, match_tracks};
+use reader::{Library, PlaylistStore};
+
+fn track_label(track: &Track) -> String {
+    if track.album.is_empty() {
+        format!("{} - {}", track.artist, track.title)
+    } else {
+        format!("{} - {} ({})", track.artist, track.title, track.album)
+    }
+}
+
+/// Server item id from a `jellyfin:<id>[:tag]` path.
+fn server_item_id(track: &Track) -> Option<String> {
+    let path = track.path.to_string_lossy();
+    path.split(':').nth(1).map(str::to_string)
+}
+
+/// Copies a playlist to the other side: local tracks are looked up in the
+/// server library, server tracks in the local one. Confident matches go
+/// straight through; ambiguous and missing ones are listed for review.
+#[component]
+pub fn ConvertPlaylist(
+    name: String,
+    tracks: Vec<Track>,
+    to_server: bool,
+    library: Signal<Library>,
+    config: Signal<AppConfig>,
+    mut playlist_store: Signal<PlaylistStore>,
+    on_close: EventHandler,
+) -> Element {
+    let mut matches = use_signal(|| {
+        let lib = library.peek();
+        let targets = if to_server {
+            &lib.jellyfin_tracks
+        } else {
+            &lib.tracks
+        };
+        match_tracks(&tracks, targets)
+    });
+    let mut target_name = use_signal(|| name.clone());
+    let mut saving = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let matched = matches
+        .read()
+        .iter()
+        .filter(|m| m.status == MatchStatus::Matched)
+        .count();
+    let total = matches.read().len();
+    let review: Vec<(usize, TrackMatch)> = matches
+        .read()
+        .iter()
+        .cloned()
+        .enumerate()
+        .filter(|(_, m)| m.status != MatchStatus::Matched)
+        .collect();
+
+    let create = move |_| {
+        if saving() {
+            return;
+        }
+        let chosen: Vec<Track> = matches
+            .peek()
+            .iter()
+            .filter_map(|m| m.chosen_track().cloned())
+            .collect();
+        let name = target_name.peek().trim().to_string();
+
+        if !to_server {
+            playlist_store.write().playlists.push(Playlist {
+                id: uuid::Uuid::new_v4().to_string(),
+                name,
+                tracks: chosen.into_iter().map(|t| t.path).collect(),
+                cover_path: None,
+                collage_path: None,
+            });
+            on_close.call(());
+            return;
+        }
+
+        let server_vals = {
+            let conf = config.peek();
+            conf.server
+                .as_ref()
+                .and_then(|s| match (&s.access_token, &s.user_id) {
+                    (Some(token), Some(user_id)) => Some((
+                        s.service,
+                        s.url.clone(),
+                        token.clone(),
+                        user_id.clone(),
+                        conf.device_id.clone(),
+                    )),
+                    _ => None,
+                })
+        };
+        let Some((service, url, token, user_id, device_id)) = server_vals else {
+            error.set(Some(i18n::t("error_server_not_configured").to_string()));
+            return;
+        };
+
+        let ids: Vec<String> = chosen.iter().filter_map(server_item_id).collect();
+        error.set(None);
+        saving.set(true);
+        spawn(async move {
+            let id_refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+            let result = match service {
+                MusicService::Jellyfin => {
+                    let remote = server::jellyfin::JellyfinClient::new(
+                        &url,
+                        Some(&token),
+                        &device_id,
+                        Some(&user_id),
+                    );
+                    remote.create_playlist(&name, &id_refs).await
+                }
+                MusicService::Subsonic | MusicService::Custom => {
+                    let remote = server::subsonic::SubsonicClient::new(&url, &user_id, &token);
+                    remote.create_playlist(&name, &id_refs).await
+                }
+            };
+            saving.set(false);
+            match result {
+                Ok(_) => on_close.call(()),
+                Err(e) => error.set(Some(e)),
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/80 flex items-center justify-center z-50",
+            onclick: move |_| on_close.call(()),
+            div {
+                class: "bg-neutral-900 rounded-xl border border-white/10 w-full max-w-2xl p-6 max-h-[85vh] overflow-y-auto",
+                onclick: move |e| e.stop_propagation(),
+                h2 {
+                    class: "text-xl font-bold text-white mb-1",
+                    if to_server { "{i18n::t(\"copy_to_server\")}" } else { "{i18n::t(\"copy_to_local\")}" }
+                }
+                p {
+                    class: "text-sm text-slate-400 mb-4",
+                    "{i18n::t_with(\"convert_matched\", &[(\"matched\", matched.to_string()), (\"total\", total.to_string())])}"
+                }
+
+                input {
+                    class: "w-full bg-white/5 border border-white/10 rounded-lg px-4 py-2 text-white mb-4 focus:outline-none focus:border-white/20",
+                    value: "{target_name}",
+                    oninput: move |e| target_name.set(e.value()),
+                }
+
+                if let Some(err) = error() {
+                    p { class: "text-sm text-red-400 mb-4", "{err}" }
+                }
+
+                if !review.is_empty() {
+                    p { class: "text-sm text-slate-400 mb-2", "{i18n::t(\"convert_review\")}" }
+                }
+                div { class: "space-y-3",
+                    for (i, m) in review {
+                        div {
+                            key: "{i}",
+                            class: "rounded-lg border border-white/10 p-3",
+                            div { class: "flex items-center gap-2 text-sm text-white mb-2 min-w-0",
+                                if m.status == MatchStatus::Missing {
+                                    span { class: "text-xs text-red-400 shrink-0", "{i18n::t(\"convert_missing\")}" }
+                                } else {
+                                    span { class: "text-xs text-amber-400 shrink-0", "{i18n::t(\"convert_ambiguous\")}" }
+                                }
+                                span { class: "truncate", "{track_label(&m.source)}" }
+                            }
+                            if !m.candidates.is_empty() {
+                                select {
+                                    class: "w-full bg-stone-800 text-white rounded-lg px-3 py-2 text-sm border border-white/10 focus:outline-none focus:border-indigo-500",
+                                    onchange: move |e| {
+                                        let choice = e.value().parse::<usize>().ok();
+                                        if let Some(slot) = matches.write().get_mut(i) {
+                                            slot.chosen = choice;
+                                        }
+                                    },
+                                    for (c, (candidate, _)) in m.candidates.iter().enumerate() {
+                                        option {
+                                            value: "{c}",
+                                            selected: m.chosen == Some(c),
+                                            "{track_label(candidate)}"
+                                        }
+                                    }
+                                    option {
+                                        value: "skip",
+                                        selected: m.chosen.is_none(),
+                                        "{i18n::t(\"convert_skip\")}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div { class: "flex justify-end gap-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm text-slate-400 hover:text-white transition-colors",
+                        onclick: move |_| on_close.call(()),
+                        "{i18n::t(\"cancel\")}"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm bg-white text-black rounded-lg font-medium hover:bg-white/90 transition-colors disabled:opacity-50",
+                        disabled: saving() || target_name.read().trim().is_empty(),
+                        onclick: create,
+                        "{i18n::t(\"create\")}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use config::MusicService;
 use dioxus::prelude::*;
 use player::player;
-use reader::{Library, PlaylistStore};
+use reader::models::server_item_id;
+use reader::offline::{CollectionKind, OfflineCollection};
+use reader::{Library, OfflineStore, PlaylistStore};
 use std::collections::HashSet;
 use std::path::PathBuf;
 #[cfg(not(target_arch = "wasm32"))]
@@ -32,16 +34,18 @@ pub fn PlaylistDetail(
 
     let mut is_selection_mode = use_signal(|| false);
     let mut selected_tracks = use_signal(|| HashSet::<PathBuf>::new());
+    let mut file_report = use_signal(|| None::<crate::playlist_files::PlaylistFileReport>);
+    let mut show_convert = use_signal(|| false);
 
     let (playlist_name, local_tracks_paths, is_jellyfin, playlist_custom_cover, playlist_image_tag) =
         if let Some(p) = store.playlists.iter().find(|p| p.id == playlist_id) {
-            (p.name.clone(), p.tracks.clone(), false, p.cover_path.clone(), None::<String>)
+            (p.name.clone(), p.tracks.clone(), false, p.cover_path.clone().or_else(|| p.collage_path.clone()), None::<String>)
         } else if let Some(p) = store
             .jellyfin_playlists
             .iter()
             .find(|p| p.id == playlist_id)
         {
-            (p.name.clone(), vec![], true, p.cover_path.clone(), p.image_tag.clone())
+            (p.name.clone(), vec![], true, p.cover_path.clone().or_else(|| p.collage_path.clone()), p.image_tag.clone())
         } else {
             return rsx! { div { "{i18n::t(\"playlist_not_found\")}" } };
         };
@@ -90,13 +94,6 @@ pub fn PlaylistDetail(
                                                     path_str.push_str(&format!(":{}", tag));
                                                 }
                                             }
-                                            let bitrate_kbps = item.bitrate.unwrap_or(0) / 1000;
-                                            let bitrate_u8 = if bitrate_kbps > 255 {
-                                                255
-                                            } else {
-                                                bitrate_kbps as u8
-                                            };
-
                                             let artist_str = item
                                                 .album_artist
                                                 .clone()
@@ -111,16 +108,23 @@ pub fn PlaylistDetail(
                                                     .map(|id| format!("jellyfin:{}", id))
                                                     .unwrap_or_default(),
                                                 title: item.name,
+                                                album_artist: item.album_artist.clone(),
                                                 artist: artist_str,
                                                 album: item.album.unwrap_or_default(),
                                                 duration: duration_secs,
                                                 khz: item.sample_rate.unwrap_or(0),
-                                                bitrate: bitrate_u8,
+                                                bitrate: item.bitrate.unwrap_or(0) / 1000,
                                                 track_number: item.index_number,
                                                 disc_number: item.parent_index_number,
                                                 musicbrainz_release_id: None,
                                                 playlist_item_id: item.playlist_item_id,
                                                 artists: item.artists.unwrap_or_default(),
+                                                date: item
+                                                    .production_year
+                                                    .map(|y| y.to_string()),
+                                                genres: item.genres.unwrap_or_default(),
+                                                codec: item.container.map(|c| c.to_uppercase()),
+                                                ..Default::default()
                                             });
                                         }
                                         tracks.set(new_tracks);
@@ -185,12 +189,23 @@ pub fn PlaylistDetail(
                                                 album: item.album.unwrap_or_default(),
                                                 duration: item.duration.unwrap_or(0),
                                                 khz: item.sampling_rate.unwrap_or(0),
-                                                bitrate: item.bit_rate.unwrap_or(0).min(255) as u8,
+                                                bitrate: item.bit_rate.unwrap_or(0),
+                                                bit_depth: item.bit_depth,
                                                 track_number: item.track,
                                                 disc_number: item.disc_number,
                                                 musicbrainz_release_id: None,
                                                 playlist_item_id: None,
                                                 artists: vec![item.artist.unwrap_or_default()],
+                                                album_artist: None,
+                                                date: item.year.map(|y| y.to_string()),
+                                                genres: item
+                                                    .genre
+                                                    .into_iter()
+                                                    .filter(|g| !g.is_empty())
+                                                    .collect(),
+                                                file_size: item.size,
+                                                codec: item.suffix.map(|s| s.to_uppercase()),
+                                                ..Default::default()
                                             });
                                         }
                                         tracks.set(new_tracks);
@@ -246,6 +261,13 @@ pub fn PlaylistDetail(
         None
     };
 
+    let mut offline = use_context::<Signal<OfflineStore>>();
+    let is_offline = is_jellyfin
+        && offline
+            .read()
+            .collection(CollectionKind::Playlist, &playlist_id)
+            .is_some();
+
     let pid_for_remove = playlist_id.clone();
     let pid_for_move_up = playlist_id.clone();
     let pid_for_move_down = playlist_id.clone();
@@ -261,6 +283,88 @@ pub fn PlaylistDetail(
                     i { class: "fa-solid fa-arrow-left" }
                     "{i18n::t(\"back_to_playlists\")}"
                 }
+                div { class: "flex items-center gap-1",
+                    if is_jellyfin || config.read().server.is_some() {
+                        button {
+                            class: "text-white/60 flex items-center hover:text-white transition-colors p-3 rounded-full hover:bg-white/10",
+                            title: if is_jellyfin { i18n::t("copy_to_local").to_string() } else { i18n::t("copy_to_server").to_string() },
+                            onclick: move |_| show_convert.set(true),
+                            i { class: if is_jellyfin { "fa-solid fa-download" } else { "fa-solid fa-cloud-arrow-up" } }
+                        }
+                    }
+                    if is_jellyfin && !cfg!(target_arch = "wasm32") {
+                        button {
+                            class: if is_offline {
+                                "text-white flex items-center transition-colors p-3 rounded-full bg-white/10 hover:bg-white/20"
+                            } else {
+                                "text-white/60 flex items-center hover:text-white transition-colors p-3 rounded-full hover:bg-white/10"
+                            },
+                            title: if is_offline { i18n::t("remove_download").to_string() } else { i18n::t("available_offline").to_string() },
+                            onclick: {
+                                let pid = playlist_id.clone();
+                                let name = playlist_name.clone();
+                                move |_| {
+                                    if offline.write().remove_collection(CollectionKind::Playlist, &pid) {
+                                        return;
+                                    }
+                                    let track_ids = tracks
+                                        .peek()
+                                        .iter()
+                                        .filter_map(|t| server_item_id(&t.path.to_string_lossy()).map(str::to_string))
+                                        .collect();
+                                    offline.write().add_collection(OfflineCollection {
+                                        kind: CollectionKind::Playlist,
+                                        id: pid.clone(),
+                                        name: name.clone(),
+                                        tracks: track_ids,
+                                        pinned: true,
+                                    });
+                                }
+                            },
+                            i { class: "fa-solid fa-circle-down" }
+                        }
+                    }
+                    button {
+                        class: "text-white/60 flex items-center hover:text-white transition-colors p-3 rounded-full hover:bg-white/10",
+                        title: i18n::t("export_playlist").to_string(),
+                        onclick: {
+                            let name = playlist_name.clone();
+                            move |_| {
+                                let _ = &name;
+                                #[cfg(not(target_arch = "wasm32"))]
+                                {
+                                    let name = name.clone();
+                                    let list = tracks.peek().clone();
+                                    spawn(async move {
+                                        if let Some(report) = crate::playlist_files::export_playlist_file(name, list, library).await {
+                                            file_report.set(Some(report));
+                                        }
+                                    });
+                                }
+                            }
+                        },
+                        i { class: "fa-solid fa-file-export" }
+                    }
+                }
+            }
+
+            if show_convert() {
+                crate::playlist_convert::ConvertPlaylist {
+                    name: playlist_name.clone(),
+                    tracks: tracks.peek().clone(),
+                    to_server: !is_jellyfin,
+                    library,
+                    config,
+                    playlist_store,
+                    on_close: move |_| show_convert.set(false),
+                }
+            }
+
+            if let Some(report) = file_report.read().clone() {
+                crate::playlist_files::PlaylistFileReportModal {
+                    report,
+                    on_close: move |_| file_report.set(None),
+                }
             }
 
             crate::showcase::Showcase {
@@ -639,6 +743,7 @@ pub fn PlaylistDetail(
                                     name,
                                     tracks: selected_paths,
                                     cover_path: None,
+                                    collage_path: None,
                                 });
                             } else {
                                 let playlist_name = name.clone();
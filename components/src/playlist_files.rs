@@ -0,0 +1,180 @@
+use dioxus::prelude::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+use reader::models::{Playlist, Track};
+#[cfg(not(target_arch = "wasm32"))]
+use reader::playlist_io::{self, PlaylistEntry, PlaylistFormat, TrackIndex};
+#[cfg(not(target_arch = "wasm32"))]
+use reader::{Library, PlaylistStore};
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::AsyncFileDialog;
+
+/// The outcome of an import or export, including every entry that could not
+/// be matched so nothing disappears without the user knowing.
+#[derive(Clone, PartialEq)]
+pub struct PlaylistFileReport {
+    pub summary: String,
+    pub unmatched: Vec<String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PlaylistFileReport {
+    fn error(summary: String) -> Self {
+        Self {
+            summary,
+            unmatched: Vec::new(),
+        }
+    }
+}
+
+/// Asks for an `.m3u8`, `.pls` or `.xspf` file and adds it as a local playlist.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn import_playlist_file(
+    library: Signal<Library>,
+    mut playlist_store: Signal<PlaylistStore>,
+) -> Option<PlaylistFileReport> {
+    let file = AsyncFileDialog::new()
+        .add_filter(i18n::t("playlists"), &["m3u", "m3u8", "pls", "xspf"])
+        .pick_file()
+        .await?;
+    let path = file.path().to_path_buf();
+    let file_name = path.display().to_string();
+
+    let format = PlaylistFormat::from_path(&path).unwrap_or(PlaylistFormat::M3u);
+    let parsed = std::fs::read(&path)
+        .map_err(|e| e.to_string())
+        // older .m3u files are often Latin-1; keep what decodes rather than refuse
+        .and_then(|bytes| playlist_io::parse(format, &String::from_utf8_lossy(&bytes)));
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            return Some(PlaylistFileReport::error(i18n::t_with(
+                "playlist_file_error",
+                &[("file", file_name), ("error", error)],
+            )));
+        }
+    };
+
+    let base_dir = path.parent().unwrap_or(std::path::Path::new(""));
+    let (tracks, unmatched) =
+        playlist_io::match_entries(&parsed.entries, base_dir, &library.peek().tracks);
+    let name = parsed.name.unwrap_or_else(|| {
+        path.file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+
+    let summary = i18n::t_with(
+        "playlist_imported",
+        &[
+            ("matched", tracks.len().to_string()),
+            ("total", parsed.entries.len().to_string()),
+            ("name", name.clone()),
+        ],
+    );
+    playlist_store.write().playlists.push(Playlist {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        tracks,
+        cover_path: None,
+        collage_path: None,
+    });
+
+    Some(PlaylistFileReport {
+        summary,
+        unmatched: unmatched.iter().map(PlaylistEntry::label).collect(),
+    })
+}
+
+/// Asks where to save and writes `tracks` with paths relative to that folder.
+/// Server tracks are written as the matching local file, when there is one.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn export_playlist_file(
+    name: String,
+    tracks: Vec<Track>,
+    library: Signal<Library>,
+) -> Option<PlaylistFileReport> {
+    let mut dialog = AsyncFileDialog::new().set_file_name(format!("{name}.m3u8"));
+    for format in PlaylistFormat::ALL {
+        dialog = dialog.add_filter(format.extension().to_uppercase(), &[format.extension()]);
+    }
+    let mut path = dialog.save_file().await?.path().to_path_buf();
+    let format = match PlaylistFormat::from_path(&path) {
+        Some(format) => format,
+        None => {
+            path.set_extension(PlaylistFormat::M3u.extension());
+            PlaylistFormat::M3u
+        }
+    };
+    let base_dir = path.parent().unwrap_or(std::path::Path::new(""));
+
+    let lib = library.peek();
+    let index = TrackIndex::new(&lib.tracks);
+    let mut entries = Vec::new();
+    let mut unmatched = Vec::new();
+    for track in &tracks {
+        let local = if track.path.to_string_lossy().starts_with("jellyfin:") {
+            index.find_by_tags(&track.title, Some(&track.artist), Some(track.duration))
+        } else {
+            Some(track)
+        };
+        match local {
+            Some(local) => entries.push(PlaylistEntry::from_track(
+                local,
+                playlist_io::relative_location(&local.path, base_dir),
+            )),
+            None => unmatched.push(format!("{} - {}", track.artist, track.title)),
+        }
+    }
+    drop(lib);
+
+    let file_name = path.display().to_string();
+    if let Err(e) = std::fs::write(&path, playlist_io::write(format, &name, &entries)) {
+        return Some(PlaylistFileReport::error(i18n::t_with(
+            "playlist_file_error",
+            &[("file", file_name), ("error", e.to_string())],
+        )));
+    }
+
+    Some(PlaylistFileReport {
+        summary: i18n::t_with(
+            "playlist_exported",
+            &[
+                ("matched", entries.len().to_string()),
+                ("total", tracks.len().to_string()),
+                ("file", file_name),
+            ],
+        ),
+        unmatched,
+    })
+}
+
+#[component]
+pub fn PlaylistFileReportModal(report: PlaylistFileReport, on_close: EventHandler) -> Element {
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/80 flex items-center justify-center z-50",
+            onclick: move |_| on_close.call(()),
+            div {
+                class: "bg-neutral-900 rounded-xl border border-white/10 w-full max-w-xl p-6 max-h-[85vh] overflow-y-auto",
+                onclick: move |e| e.stop_propagation(),
+                p { class: "text-sm text-white mb-4 break-words", "{report.summary}" }
+                if !report.unmatched.is_empty() {
+                    p { class: "text-sm text-slate-400 mb-2", "{i18n::t(\"playlist_unmatched\")}" }
+                    ul { class: "text-xs text-slate-500 space-y-1 mb-4",
+                        for (i, label) in report.unmatched.iter().enumerate() {
+                            li { key: "{i}", class: "truncate", "{label}" }
+                        }
+                    }
+                }
+                div { class: "flex justify-end",
+                    button {
+                        class: "px-4 py-2 text-sm bg-white text-black rounded-lg font-medium hover:bg-white/90 transition-colors",
+                        onclick: move |_| on_close.call(()),
+                        "{i18n::t(\"close\")}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use crate::reorder_buttons::ReorderButtons;
+use crate::saved_queues::SavedQueues;
 use config::AppConfig;
 use dioxus::document::eval;
 use dioxus::prelude::*;
+use hooks::use_lyrics::use_lyrics;
 use hooks::use_player_controller::PlayerController;
 use reader::Library;
 use serde_json::Value;
@@ -24,6 +26,7 @@ pub fn Rightbar(
     }
 
     let mut active_tab = use_signal(|| 1usize);
+    let mut show_saved_queues = use_signal(|| false);
     let mut ctrl = use_context::<PlayerController>();
     let mut exact_progress = use_signal(|| 0.0_f64);
 
@@ -36,26 +39,12 @@ pub fn Rightbar(
 
     let config = use_context::<Signal<AppConfig>>();
 
-    let lyrics = use_resource(move || {
-        let title = current_song_title.read().clone();
-        let artist = current_song_artist.read().clone();
-        let album = current_song_album.read().clone();
-        let duration = *current_song_duration.read();
-
-        async move {
-            if !title.is_empty() {
-                if let Some(l) =
-                    utils::lyrics::fetch_lyrics(&artist, &title, &album, duration).await
-                {
-                    Some(l)
-                } else {
-                    Some(utils::lyrics::Lyrics::Plain(i18n::t("lyrics_not_found").to_string()))
-                }
-            } else {
-                None
-            }
-        }
-    });
+    let lyrics = use_lyrics(
+        current_song_title,
+        current_song_artist,
+        current_song_album,
+        current_song_duration,
+    );
 
     let active_lyric_index = use_memo(move || {
         if *active_tab.read() == 2 {
@@ -178,14 +167,17 @@ pub fn Rightbar(
             format!("{minutes}:{secs:02}")
         }
     };
+    let up_next = ctrl.up_next.read().clone();
     let up_next_count = queue
         .read()
         .len()
-        .saturating_sub(*current_queue_index.read() + 1);
+        .saturating_sub(*current_queue_index.read() + 1)
+        + up_next.len();
     let up_next_duration: u64 = queue
         .read()
         .iter()
         .skip(*current_queue_index.read() + 1)
+        .chain(&up_next)
         .map(|track| track.duration)
         .sum();
     let up_next_summary = format!(
@@ -240,10 +232,22 @@ pub fn Rightbar(
                         "{lyrics_text}"
                     }
                 }
-                button {
-                    class: "text-white/40 hover:text-white",
-                    onclick: move |_| is_rightbar_open.set(false),
-                    i { class: "fa-solid fa-xmark text-sm" }
+                div {
+                    class: "flex items-center gap-3",
+                    button {
+                        class: if show_saved_queues() { "text-white" } else { "text-white/40 hover:text-white" },
+                        title: i18n::t("saved_queues"),
+                        onclick: move |_| {
+                            show_saved_queues.set(!show_saved_queues());
+                            active_tab.set(1);
+                        },
+                        i { class: "fa-solid fa-layer-group text-sm" }
+                    }
+                    button {
+                        class: "text-white/40 hover:text-white",
+                        onclick: move |_| is_rightbar_open.set(false),
+                        i { class: "fa-solid fa-xmark text-sm" }
+                    }
                 }
             }
 
@@ -321,7 +325,10 @@ pub fn Rightbar(
                         }
                     }
                 } else if *active_tab.read() == 1 {
-                    if queue.read().len() <= *current_queue_index.read() + 1 {
+                    if show_saved_queues() {
+                        SavedQueues {}
+                    }
+                    if up_next_count == 0 {
                         div { class: "text-white/30 text-center py-10 text-sm", "{i18n::t(\"no_more_songs\")}" }
                     } else {
                         div {
@@ -329,6 +336,70 @@ pub fn Rightbar(
                             "{up_next_summary}"
                         }
                     }
+                    if !up_next.is_empty() {
+                        div {
+                            class: "flex items-center justify-between px-2 pt-2 pb-1",
+                            span { class: "text-xs font-semibold text-white/70", "{i18n::t(\"queued_by_you\")}" }
+                            button {
+                                class: "text-[11px] text-slate-500 hover:text-white transition-colors",
+                                onclick: move |_| ctrl.clear_up_next(),
+                                "{i18n::t(\"clear_queue\")}"
+                            }
+                        }
+                    }
+                    for (i, track) in up_next.iter().cloned().enumerate() {
+                        {
+                            let cover_url = get_track_cover(&track);
+                            let can_move_up = i > 0;
+                            let can_move_down = i + 1 < up_next.len();
+                            rsx! {
+                                div {
+                                    key: "up-next-{i}",
+                                    class: "flex items-center gap-3 px-2 py-2 hover:bg-white/5 cursor-pointer rounded-lg transition-colors group",
+                                    ondoubleclick: move |_| ctrl.play_up_next(i),
+                                    div {
+                                        class: "rounded-md overflow-hidden bg-black/30 flex-shrink-0 shadow-sm",
+                                        style: "width: 40px; height: 40px;",
+                                        if let Some(ref url) = cover_url {
+                                            img { src: "{url}", class: "w-full h-full object-cover" }
+                                        } else {
+                                            div {
+                                                class: "w-full h-full flex items-center justify-center",
+                                                i { class: "fa-solid fa-music text-white/20", style: "font-size: 12px;" }
+                                            }
+                                        }
+                                    }
+                                    div {
+                                        class: "flex-1 min-w-0 flex flex-col justify-center gap-0.5",
+                                        div { class: "text-sm text-white truncate font-medium", "{track.title}" }
+                                        div { class: "text-xs text-white/50 truncate group-hover:text-white/70", "{track.artist}" }
+                                    }
+                                    button {
+                                        class: "p-1 text-slate-500 hover:text-white opacity-0 group-hover:opacity-100 transition-opacity",
+                                        title: i18n::t("remove_from_queue"),
+                                        onclick: move |evt| {
+                                            evt.stop_propagation();
+                                            ctrl.remove_up_next(i);
+                                        },
+                                        i { class: "fa-solid fa-xmark text-xs" }
+                                    }
+                                    ReorderButtons {
+                                        can_move_up,
+                                        can_move_down,
+                                        class: "flex flex-col pr-1 shrink-0 opacity-0 group-hover:opacity-100 transition-opacity".to_string(),
+                                        on_move_up: move |_| ctrl.move_up_next(i, i - 1),
+                                        on_move_down: move |_| ctrl.move_up_next(i, i + 1),
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    if !up_next.is_empty() && queue.read().len() > *current_queue_index.read() + 1 {
+                        div {
+                            class: "px-2 pt-3 pb-1 text-xs font-semibold text-white/70",
+                            "{i18n::t(\"next_from_context\")}"
+                        }
+                    }
                     for i in (*current_queue_index.read() + 1)..queue.read().len() {
                         {
                             let track = queue.read()[i].clone();
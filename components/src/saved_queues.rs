@@ -0,0 +1,178 @@
+use config::{AppConfig, MusicService};
+use dioxus::prelude::*;
+use hooks::use_player_controller::PlayerController;
+use reader::SavedQueueStore;
+use reader::models::{Playlist, server_item_id};
+use reader::saved_queues::SavedQueue;
+
+fn format_duration(secs: u64) -> String {
+    let hours = secs / 3600;
+    let minutes = (secs % 3600) / 60;
+    if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// Copies a saved queue into a playlist: local tracks into a local one, server
+/// tracks into one on the server.
+async fn save_as_playlist(
+    saved: SavedQueue,
+    mut ctrl: PlayerController,
+    config: Signal<AppConfig>,
+) -> Result<(), String> {
+    let (server_ids, local_paths): (Vec<_>, Vec<_>) = saved
+        .tracks()
+        .map(|t| t.path.clone())
+        .partition(|path| server_item_id(&path.to_string_lossy()).is_some());
+
+    if !local_paths.is_empty() {
+        ctrl.playlist_store.write().playlists.push(Playlist {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: saved.name.clone(),
+            tracks: local_paths,
+            cover_path: None,
+            collage_path: None,
+        });
+    }
+    if server_ids.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<String> = server_ids
+        .iter()
+        .filter_map(|path| server_item_id(&path.to_string_lossy()).map(str::to_string))
+        .collect();
+    let id_refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+    let server = {
+        let conf = config.peek();
+        conf.server
+            .as_ref()
+            .and_then(|s| match (&s.access_token, &s.user_id) {
+                (Some(token), Some(user_id)) => Some((
+                    s.service,
+                    s.url.clone(),
+                    token.clone(),
+                    user_id.clone(),
+                    conf.device_id.clone(),
+                )),
+                _ => None,
+            })
+    };
+    let Some((service, url, token, user_id, device_id)) = server else {
+        return Err(i18n::t("error_server_not_configured").to_string());
+    };
+    match service {
+        MusicService::Jellyfin => {
+            let remote = server::jellyfin::JellyfinClient::new(
+                &url,
+                Some(&token),
+                &device_id,
+                Some(&user_id),
+            );
+            remote.create_playlist(&saved.name, &id_refs).await?;
+        }
+        MusicService::Subsonic | MusicService::Custom => {
+            let remote = server::subsonic::SubsonicClient::new(&url, &user_id, &token);
+            remote.create_playlist(&saved.name, &id_refs).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Saves the current queue under a name and lists the saved ones to switch
+/// back to, turn into a playlist or delete.
+#[component]
+pub fn SavedQueues() -> Element {
+    let mut ctrl = use_context::<PlayerController>();
+    let config = use_context::<Signal<AppConfig>>();
+    let mut store = use_context::<Signal<SavedQueueStore>>();
+    let mut name = use_signal(String::new);
+    let mut status = use_signal(|| None::<String>);
+
+    let queue_empty = ctrl.queue.read().is_empty() && ctrl.up_next.read().is_empty();
+    let can_save = !queue_empty && !name.read().trim().is_empty();
+    let saved: Vec<SavedQueue> = store.read().queues.clone();
+
+    rsx! {
+        div { class: "px-2 pb-3 mb-2 border-b border-white/5",
+            div { class: "flex items-center gap-2 mb-2",
+                input {
+                    class: "flex-1 min-w-0 bg-white/5 border border-white/10 rounded-lg px-3 py-1.5 text-sm text-white focus:outline-none focus:border-white/20",
+                    placeholder: i18n::t("queue_name"),
+                    value: "{name}",
+                    oninput: move |e| name.set(e.value()),
+                }
+                button {
+                    class: "px-3 py-1.5 text-xs rounded-lg bg-white/10 text-white hover:bg-white/20 transition-colors disabled:opacity-40",
+                    disabled: !can_save,
+                    onclick: move |_| {
+                        let snapshot = ctrl.save_queue(&name.peek());
+                        store.write().put(snapshot);
+                        name.set(String::new());
+                        status.set(None);
+                    },
+                    "{i18n::t(\"save_queue\")}"
+                }
+            }
+            if let Some(message) = status() {
+                p { class: "text-xs text-slate-400 px-1 mb-2", "{message}" }
+            }
+            if saved.is_empty() {
+                p { class: "text-xs text-white/30 px-1", "{i18n::t(\"no_saved_queues\")}" }
+            }
+            for queue in saved {
+                {
+                    let summary = format!(
+                        "{} • {}",
+                        i18n::t_with("showcase_song_count", &[("count", queue.tracks().count().to_string())]),
+                        format_duration(queue.duration_secs())
+                    );
+                    let restore = queue.clone();
+                    let convert = queue.clone();
+                    let delete_name = queue.name.clone();
+                    rsx! {
+                        div {
+                            key: "{queue.name}",
+                            class: "flex items-center gap-2 px-2 py-1.5 rounded-lg hover:bg-white/5 group",
+                            div { class: "flex-1 min-w-0",
+                                div { class: "text-sm text-white truncate", "{queue.name}" }
+                                div { class: "text-[11px] text-white/40 truncate", "{summary}" }
+                            }
+                            button {
+                                class: "p-1 text-slate-400 hover:text-white",
+                                title: i18n::t("restore_queue"),
+                                onclick: move |_| ctrl.restore_saved_queue(&restore),
+                                i { class: "fa-solid fa-play text-xs" }
+                            }
+                            button {
+                                class: "p-1 text-slate-400 hover:text-white",
+                                title: i18n::t("queue_to_playlist"),
+                                onclick: move |_| {
+                                    let convert = convert.clone();
+                                    spawn(async move {
+                                        let message = match save_as_playlist(convert, ctrl, config).await {
+                                            Ok(()) => i18n::t("queue_saved_as_playlist").to_string(),
+                                            Err(e) => e,
+                                        };
+                                        status.set(Some(message));
+                                    });
+                                },
+                                i { class: "fa-solid fa-list text-xs" }
+                            }
+                            button {
+                                class: "p-1 text-slate-500 hover:text-red-400 opacity-0 group-hover:opacity-100 transition-opacity",
+                                title: i18n::t("delete"),
+                                onclick: move |_| {
+                                    store.write().remove(&delete_name);
+                                },
+                                i { class: "fa-solid fa-trash text-xs" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
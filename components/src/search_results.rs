@@ -3,13 +3,14 @@ use dioxus::prelude::*;
 use hooks::use_player_controller::PlayerController;
 use player::player;
 use reader::Library;
-use reader::models::{Album, Track};
+use reader::models::{Album, Artist, Track};
 
 #[component]
 pub fn SearchResults(
     search_query: String,
     tracks: Vec<(Track, Option<String>)>,
     albums: Vec<(Album, Option<String>)>,
+    artists: Vec<(Artist, Option<String>)>,
     library: Signal<Library>,
     playlist_store: Signal<reader::PlaylistStore>,
     player: Signal<player::Player>,
@@ -24,11 +25,48 @@ pub fn SearchResults(
     mut active_menu_track: Signal<Option<std::path::PathBuf>>,
     mut show_playlist_modal: Signal<bool>,
     mut selected_track_for_playlist: Signal<Option<std::path::PathBuf>>,
+    on_select_artist: EventHandler<String>,
 ) -> Element {
     let mut ctrl = use_context::<PlayerController>();
 
     rsx! {
         div { class: "mt-8 space-y-8",
+            if !artists.is_empty() {
+                div {
+                    h2 { class: "text-xl font-semibold text-white/80 mb-4", "{i18n::t(\"artists\")}" }
+                    div { class: "flex gap-6 overflow-x-auto pb-2",
+                        for (artist, cover_url) in &artists {
+                            {
+                                let name = artist.name.clone();
+                                rsx! {
+                                    div {
+                                        key: "{artist.id}",
+                                        class: "group cursor-pointer flex flex-col items-center w-32 shrink-0",
+                                        onclick: move |_| on_select_artist.call(name.clone()),
+                                        div { class: "w-32 h-32 rounded-full bg-black/40 mb-3 overflow-hidden",
+                                            if let Some(url) = cover_url {
+                                                img {
+                                                    src: "{url}",
+                                                    class: "w-full h-full object-cover",
+                                                    decoding: "async", loading: "lazy",
+                                                }
+                                            } else {
+                                                div { class: "w-full h-full flex items-center justify-center",
+                                                    i { class: "fa-solid fa-microphone text-4xl text-white/20" }
+                                                }
+                                            }
+                                        }
+                                        p { class: "text-sm text-white font-medium truncate w-full text-center group-hover:text-indigo-400 transition-colors",
+                                            "{artist.name}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
             if !tracks.is_empty() {
                 div {
                     h2 { class: "text-xl font-semibold text-white/80 mb-4", "{i18n::t(\"tracks\")}" }
@@ -113,7 +151,7 @@ pub fn SearchResults(
                 }
             }
 
-            if tracks.is_empty() && albums.is_empty() {
+            if tracks.is_empty() && albums.is_empty() && artists.is_empty() {
                 div { class: "text-center py-12 text-slate-500",
                     p { "{i18n::t_with(\"no_results_found\", &[(\"query\", search_query.to_string())])}" }
                 }
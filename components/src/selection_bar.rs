@@ -7,6 +7,7 @@ pub fn SelectionBar(
     on_delete: EventHandler<()>,
     on_cancel: EventHandler<()>,
     #[props(default = true)] show_delete: bool,
+    on_edit_tags: Option<EventHandler<()>>,
 ) -> Element {
     if count == 0 {
         return rsx! { "" };
@@ -14,6 +15,7 @@ pub fn SelectionBar(
 
     let delete_text = i18n::t("delete").to_string();
     let add_to_playlist_text = i18n::t("add_to_playlist").to_string();
+    let edit_tags_text = i18n::t("edit_tags").to_string();
 
     rsx! {
         div {
@@ -31,6 +33,15 @@ pub fn SelectionBar(
                     span { class: "hidden sm:inline", "{add_to_playlist_text}" }
                 }
 
+                if let Some(on_edit_tags) = on_edit_tags {
+                    button {
+                        class: "hover:opacity-80 transition-opacity flex items-center gap-2 font-medium whitespace-nowrap",
+                        onclick: move |_| on_edit_tags.call(()),
+                        i { class: "fa-solid fa-pen text-sm" }
+                        span { class: "hidden sm:inline", "{edit_tags_text}" }
+                    }
+                }
+
                 if show_delete {
                     button {
                         class: "hover:opacity-80 transition-opacity flex items-center gap-2 font-medium whitespace-nowrap",
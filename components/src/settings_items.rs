@@ -1,5 +1,7 @@
+use crate::reorder_buttons::ReorderButtons;
 use config::{
-    AppConfig, BackBehavior, EqPreset, EqualizerSettings as EqualizerConfig, MusicServer,
+    AppConfig, BackBehavior, EqPreset, EqualizerSettings as EqualizerConfig, LyricsSource,
+    MusicServer,
 };
 use dioxus::prelude::*;
 #[cfg(not(target_arch = "wasm32"))]
@@ -95,9 +97,11 @@ pub fn MultiDirectoryPicker(
     current_paths: Vec<std::path::PathBuf>,
     on_add: EventHandler<std::path::PathBuf>,
     on_remove: EventHandler<usize>,
+    on_relocate: EventHandler<(usize, std::path::PathBuf)>,
 ) -> Element {
     let add_text = i18n::t("add_folder");
     let remove_text = i18n::t("remove");
+    let relocate_text = i18n::t("relocate_folder");
     let no_folders_text = i18n::t("no_music_folders");
 
     rsx! {
@@ -116,6 +120,10 @@ pub fn MultiDirectoryPicker(
                                 class: "text-xs text-slate-400 font-mono truncate flex-1",
                                 "{display}"
                             }
+                            RelocateFolderButton {
+                                on_pick: move |path| on_relocate.call((i, path)),
+                                text: relocate_text.clone(),
+                            }
                             button {
                                 onclick: move |_| {
                                     on_remove.call(i);
@@ -132,6 +140,51 @@ pub fn MultiDirectoryPicker(
     }
 }
 
+/// One box of exclude patterns per music folder, one gitignore-style pattern per
+/// line.
+#[component]
+pub fn ScanExcludes(
+    roots: Vec<std::path::PathBuf>,
+    excludes: std::collections::HashMap<std::path::PathBuf, Vec<String>>,
+    on_change: EventHandler<(std::path::PathBuf, Vec<String>)>,
+) -> Element {
+    let placeholder = i18n::t("scan_excludes_placeholder");
+
+    rsx! {
+        div { class: "flex flex-col gap-3 w-full",
+            if roots.is_empty() {
+                p { class: "text-xs text-slate-500 italic", "{i18n::t(\"no_music_folders\")}" }
+            }
+            for root in roots {
+                {
+                    let display = root.display().to_string();
+                    let patterns = excludes.get(&root).cloned().unwrap_or_default().join("\n");
+                    rsx! {
+                        div { key: "{display}", class: "flex flex-col gap-1 w-full",
+                            span { class: "text-xs text-slate-400 font-mono truncate", "{display}" }
+                            textarea {
+                                class: "w-full h-20 bg-stone-800 text-white rounded-lg px-3 py-2 text-xs font-mono border border-white/10 focus:outline-none focus:border-indigo-500",
+                                placeholder: "{placeholder}",
+                                value: "{patterns}",
+                                // onchange fires on blur, so a rescan only starts once editing is done
+                                onchange: move |evt| {
+                                    let patterns = evt
+                                        .value()
+                                        .lines()
+                                        .map(|line| line.trim().to_string())
+                                        .filter(|line| !line.is_empty())
+                                        .collect();
+                                    on_change.call((root.clone(), patterns));
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 #[component]
 fn AddFolderButton(on_add: EventHandler<std::path::PathBuf>, add_text: String) -> Element {
@@ -150,6 +203,34 @@ fn AddFolderButton(on_add: EventHandler<std::path::PathBuf>, add_text: String) -
     }
 }
 
+/// Points a music folder at its new location after the files were moved.
+#[cfg(not(target_arch = "wasm32"))]
+#[component]
+fn RelocateFolderButton(on_pick: EventHandler<std::path::PathBuf>, text: String) -> Element {
+    rsx! {
+        button {
+            onclick: move |_| {
+                spawn(async move {
+                    if let Some(handle) = AsyncFileDialog::new().pick_folder().await {
+                        on_pick.call(handle.path().to_path_buf());
+                    }
+                });
+            },
+            title: i18n::t("relocate_folder_hint").to_string(),
+            class: "text-slate-400 hover:text-white text-xs px-2 py-0.5 rounded transition-colors shrink-0",
+            "{text}"
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[component]
+fn RelocateFolderButton(on_pick: EventHandler<std::path::PathBuf>, text: String) -> Element {
+    let _ = on_pick;
+    let _ = text;
+    rsx! {}
+}
+
 #[cfg(target_arch = "wasm32")]
 #[component]
 fn AddFolderButton(on_add: EventHandler<std::path::PathBuf>, add_text: String) -> Element {
@@ -911,3 +992,57 @@ pub fn BackBehaviorSelector(
         }
     }
 }
+
+fn lyrics_source_label(source: LyricsSource) -> String {
+    i18n::t(match source {
+        LyricsSource::Sidecar => "lyrics_source_sidecar",
+        LyricsSource::EmbeddedSynced => "lyrics_source_embedded_synced",
+        LyricsSource::EmbeddedPlain => "lyrics_source_embedded_plain",
+        LyricsSource::Online => "lyrics_source_online",
+    })
+    .to_string()
+}
+
+#[component]
+pub fn LyricsSourceOrder(
+    sources: Vec<LyricsSource>,
+    online_enabled: bool,
+    on_change: EventHandler<Vec<LyricsSource>>,
+) -> Element {
+    let len = sources.len();
+
+    rsx! {
+        div { class: "flex flex-col gap-1 w-64",
+            for (i, source) in sources.iter().copied().enumerate() {
+                {
+                    let dimmed = source == LyricsSource::Online && !online_enabled;
+                    let up = sources.clone();
+                    let down = sources.clone();
+                    rsx! {
+                        div { key: "{i}-{source:?}",
+                            class: "flex items-center gap-2 bg-white/5 px-2 py-1.5 rounded",
+                            ReorderButtons {
+                                can_move_up: i > 0,
+                                can_move_down: i + 1 < len,
+                                on_move_up: move |_| {
+                                    let mut next = up.clone();
+                                    next.swap(i, i - 1);
+                                    on_change.call(next);
+                                },
+                                on_move_down: move |_| {
+                                    let mut next = down.clone();
+                                    next.swap(i, i + 1);
+                                    on_change.call(next);
+                                },
+                            }
+                            span {
+                                class: if dimmed { "text-sm text-slate-600 line-through" } else { "text-sm text-white/80" },
+                                "{i + 1}. {lyrics_source_label(source)}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
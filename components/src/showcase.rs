@@ -34,6 +34,28 @@ pub struct ShowcaseProps {
     pub on_move_up: EventHandler<usize>,
     #[props(default)]
     pub on_move_down: EventHandler<usize>,
+    /// Puts a "Disc N" heading above each disc when the tracks span several.
+    /// Tracks must already be ordered by disc.
+    #[props(default = false)]
+    pub group_by_disc: bool,
+}
+
+/// Whether a track list covers more than one disc and is worth splitting up.
+pub fn spans_several_discs<'a>(tracks: impl IntoIterator<Item = &'a Track>) -> bool {
+    let mut discs = tracks.into_iter().map(|t| t.disc_number.unwrap_or(1));
+    discs
+        .next()
+        .is_some_and(|first| discs.any(|disc| disc != first))
+}
+
+#[component]
+pub fn DiscHeader(disc: u32) -> Element {
+    rsx! {
+        div { class: "flex items-center gap-3 px-4 pt-6 pb-2 text-sm font-semibold text-slate-400 uppercase tracking-wider",
+            i { class: "fa-solid fa-compact-disc" }
+            "{i18n::t_with(\"disc_number\", &[(\"number\", disc.to_string())])}"
+        }
+    }
 }
 
 #[component]
@@ -44,6 +66,7 @@ pub fn Showcase(props: ShowcaseProps) -> Element {
 
     let lib = props.library.read();
     let is_server_source = config.read().active_source == MusicSource::Server;
+    let show_discs = props.group_by_disc && spans_several_discs(&props.tracks);
 
     rsx! {
          div {
@@ -145,70 +168,78 @@ pub fn Showcase(props: ShowcaseProps) -> Element {
                              } else {
                                  lib.albums.iter()
                                     .find(|a| a.id == track.album_id)
-                                    .and_then(|a| utils::format_artwork_url(a.cover_path.as_ref()))
+                                    .and_then(|a| utils::format_artwork_url_sized(a.cover_path.as_ref(), 96))
                              };
 
                              let is_selected = props.selected_tracks.contains(&track.path);
                              let track_count = props.tracks.len();
                              let can_move_up = props.is_reorderable && idx > 0;
                              let can_move_down = props.is_reorderable && idx + 1 < track_count;
+                             let disc_header = (show_discs
+                                 && (idx == 0 || props.tracks[idx - 1].disc_number != track.disc_number))
+                                 .then(|| track.disc_number.unwrap_or(1));
 
                              rsx! {
                                  div {
                                      key: "{track.path.display()}",
-                                     class: "flex items-center group",
-                                     style: "content-visibility: auto; contain-intrinsic-size: 0 60px;",
-                                     div { class: "flex-1 min-w-0",
-                                         TrackRow {
-                                             track: track.clone(),
-                                             cover_url: cover_url,
-                                             is_menu_open: props.active_track.as_ref() == Some(&track.path),
-                                             is_selection_mode: props.is_selection_mode,
-                                             is_selected: is_selected,
-                                             on_select: move |selected| {
-                                                if let Some(handler) = &props.on_select {
-                                                    handler.call((idx, selected));
-                                                }
-                                             },
-                                             on_long_press: move |_| {
-                                                if let Some(handler) = &props.on_long_press {
-                                                    handler.call(idx);
-                                                }
-                                             },
-                                             on_click_menu: move |_| {
-                                                if let Some(handler) = &props.on_click_menu {
-                                                    handler.call(idx);
-                                                }
-                                             },
-                                             on_add_to_playlist: move |_| {
-                                                if let Some(handler) = &props.on_add_to_playlist {
-                                                    handler.call(idx);
-                                                }
-                                             },
-                                             on_close_menu: move |_| {
-                                                if let Some(handler) = &props.on_close_menu {
-                                                    handler.call(());
-                                                }
-                                             },
-                                             on_delete: move |_| {
-                                                if let Some(handler) = &props.on_delete_track {
-                                                    handler.call(idx);
-                                                }
-                                             },
-                                             on_remove_from_playlist: move |_| {
-                                                 if let Some(handler) = &props.on_remove_from_playlist {
-                                                     handler.call(idx);
-                                                 }
-                                             },
-                                             on_play: move |_| props.on_play.call(idx)
-                                         }
+                                     if let Some(disc) = disc_header {
+                                         DiscHeader { disc }
                                      }
-                                     if props.is_reorderable && !props.is_selection_mode {
-                                         ReorderButtons {
-                                             can_move_up,
-                                             can_move_down,
-                                             on_move_up: move |_| props.on_move_up.call(idx),
-                                             on_move_down: move |_| props.on_move_down.call(idx),
+                                     div {
+                                         class: "flex items-center group",
+                                         style: "content-visibility: auto; contain-intrinsic-size: 0 60px;",
+                                         div { class: "flex-1 min-w-0",
+                                             TrackRow {
+                                                 track: track.clone(),
+                                                 cover_url: cover_url,
+                                                 is_menu_open: props.active_track.as_ref() == Some(&track.path),
+                                                 is_selection_mode: props.is_selection_mode,
+                                                 is_selected: is_selected,
+                                                 on_select: move |selected| {
+                                                    if let Some(handler) = &props.on_select {
+                                                        handler.call((idx, selected));
+                                                    }
+                                                 },
+                                                 on_long_press: move |_| {
+                                                    if let Some(handler) = &props.on_long_press {
+                                                        handler.call(idx);
+                                                    }
+                                                 },
+                                                 on_click_menu: move |_| {
+                                                    if let Some(handler) = &props.on_click_menu {
+                                                        handler.call(idx);
+                                                    }
+                                                 },
+                                                 on_add_to_playlist: move |_| {
+                                                    if let Some(handler) = &props.on_add_to_playlist {
+                                                        handler.call(idx);
+                                                    }
+                                                 },
+                                                 on_close_menu: move |_| {
+                                                    if let Some(handler) = &props.on_close_menu {
+                                                        handler.call(());
+                                                    }
+                                                 },
+                                                 on_delete: move |_| {
+                                                    if let Some(handler) = &props.on_delete_track {
+                                                        handler.call(idx);
+                                                    }
+                                                 },
+                                                 on_remove_from_playlist: move |_| {
+                                                     if let Some(handler) = &props.on_remove_from_playlist {
+                                                         handler.call(idx);
+                                                     }
+                                                 },
+                                                 on_play: move |_| props.on_play.call(idx)
+                                             }
+                                         }
+                                         if props.is_reorderable && !props.is_selection_mode {
+                                             ReorderButtons {
+                                                 can_move_up,
+                                                 can_move_down,
+                                                 on_move_up: move |_| props.on_move_up.call(idx),
+                                                 on_move_down: move |_| props.on_move_down.call(idx),
+                                             }
                                          }
                                      }
                                  }
@@ -0,0 +1,413 @@
+use config::AppConfig;
+use dioxus::prelude::*;
+use hooks::use_player_controller::PlayerController;
+use reader::smart::{
+    RULE_FIELDS, Rule, RuleField, RuleOp, SMART_SORTS, SmartContext, SmartPlaylist, SmartSort,
+};
+use reader::{FavoritesStore, HistoryStore, Library, PlaylistStore};
+
+fn field_label(field: RuleField) -> String {
+    i18n::t(match field {
+        RuleField::Title => "smart_field_title",
+        RuleField::Artist => "smart_field_artist",
+        RuleField::Album => "smart_field_album",
+        RuleField::Genre => "smart_field_genre",
+        RuleField::Year => "smart_field_year",
+        RuleField::Duration => "smart_field_duration",
+        RuleField::PlayCount => "smart_field_play_count",
+        RuleField::DateAdded => "smart_field_date_added",
+        RuleField::LastPlayed => "smart_field_last_played",
+        RuleField::Favorite => "smart_field_favorite",
+        RuleField::Rating => "smart_field_rating",
+    })
+}
+
+fn op_label(op: RuleOp) -> String {
+    i18n::t(match op {
+        RuleOp::Contains => "smart_op_contains",
+        RuleOp::NotContains => "smart_op_not_contains",
+        RuleOp::Is => "smart_op_is",
+        RuleOp::IsNot => "smart_op_is_not",
+        RuleOp::LessThan => "smart_op_less_than",
+        RuleOp::GreaterThan => "smart_op_greater_than",
+        RuleOp::InLast => "smart_op_in_last",
+        RuleOp::NotInLast => "smart_op_not_in_last",
+    })
+}
+
+fn sort_label(sort: SmartSort) -> String {
+    field_label(match sort {
+        SmartSort::Artist => RuleField::Artist,
+        SmartSort::Title => RuleField::Title,
+        SmartSort::Album => RuleField::Album,
+        SmartSort::Year => RuleField::Year,
+        SmartSort::DateAdded => RuleField::DateAdded,
+        SmartSort::PlayCount => RuleField::PlayCount,
+        SmartSort::LastPlayed => RuleField::LastPlayed,
+        SmartSort::Duration => RuleField::Duration,
+        SmartSort::Rating => RuleField::Rating,
+    })
+}
+
+fn unix_now() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Runs a smart playlist against the current library, listen stats and
+/// favorites. Reading the signals here makes callers re-evaluate whenever any
+/// of them change.
+pub fn evaluate_smart_playlist(
+    smart: &SmartPlaylist,
+    library: Signal<Library>,
+    history: Signal<HistoryStore>,
+    favorites_store: Signal<FavoritesStore>,
+) -> Vec<reader::Track> {
+    let lib = library.read();
+    let history = history.read();
+    let favorites = favorites_store.read();
+    let ctx = SmartContext {
+        listen_counts: &history.listen_counts,
+        last_played: &history.last_played,
+        favorites: &favorites,
+        now: unix_now(),
+    };
+    let tracks = if smart.server {
+        &lib.jellyfin_tracks
+    } else {
+        &lib.tracks
+    };
+    smart.evaluate(tracks, &ctx)
+}
+
+#[component]
+pub fn SmartPlaylistGrid(
+    playlist_store: Signal<PlaylistStore>,
+    server: bool,
+    on_select: EventHandler<String>,
+) -> Element {
+    let playlists: Vec<SmartPlaylist> = playlist_store
+        .read()
+        .smart_playlists
+        .iter()
+        .filter(|p| p.server == server)
+        .cloned()
+        .collect();
+
+    if playlists.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        h2 { class: "text-sm font-semibold text-white/40 uppercase tracking-widest mb-4", "{i18n::t(\"smart_playlists\")}" }
+        div { class: "grid grid-cols-2 md:grid-cols-3 lg:grid-cols-4 gap-6 mb-8",
+            for playlist in playlists {
+                {
+                    let id = playlist.id.clone();
+                    rsx! {
+                        div {
+                            key: "{playlist.id}",
+                            class: "bg-white/5 border border-white/5 rounded-2xl p-6 hover:bg-white/10 transition-all cursor-pointer group relative",
+                            onclick: move |_| on_select.call(id.clone()),
+                            div {
+                                class: "mb-4 w-full aspect-square rounded-xl flex items-center justify-center overflow-hidden",
+                                style: "background: color-mix(in srgb, var(--color-emerald-500), transparent 80%); color: var(--color-emerald-400)",
+                                i { class: "fa-solid fa-wand-magic-sparkles text-2xl" }
+                            }
+                            h3 { class: "text-xl font-bold text-white mb-1 truncate", "{playlist.name}" }
+                            p {
+                                class: "text-sm text-slate-400",
+                                "{i18n::t_with(\"smart_rule_count\", &[(\"count\", playlist.rules.len().to_string())])}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn SmartPlaylistDetail(
+    smart_id: String,
+    playlist_store: Signal<PlaylistStore>,
+    library: Signal<Library>,
+    config: Signal<AppConfig>,
+    favorites_store: Signal<FavoritesStore>,
+    mut queue: Signal<Vec<reader::Track>>,
+    on_edit: EventHandler<SmartPlaylist>,
+    on_close: EventHandler<()>,
+) -> Element {
+    let mut ctrl = use_context::<PlayerController>();
+    let history = use_context::<Signal<HistoryStore>>();
+    let smart_id = use_signal(|| smart_id);
+
+    let smart = use_memo(move || {
+        playlist_store
+            .read()
+            .smart_playlists
+            .iter()
+            .find(|p| p.id == *smart_id.read())
+            .cloned()
+    });
+    let tracks = use_memo(move || match smart() {
+        Some(smart) => evaluate_smart_playlist(&smart, library, history, favorites_store),
+        None => Vec::new(),
+    });
+
+    let Some(playlist) = smart() else {
+        return rsx! { div { "{i18n::t(\"playlist_not_found\")}" } };
+    };
+    let cover_url = tracks.read().first().and_then(|t| {
+        if playlist.server {
+            let conf = config.read();
+            let server = conf.server.as_ref()?;
+            utils::jellyfin_image::jellyfin_image_url_from_path(
+                &t.path.to_string_lossy(),
+                &server.url,
+                server.access_token.as_deref(),
+                512,
+                90,
+            )
+        } else {
+            let lib = library.read();
+            let album = lib.albums.iter().find(|a| a.id == t.album_id)?;
+            utils::format_artwork_url(album.cover_path.as_ref())
+        }
+    });
+
+    rsx! {
+        div {
+            class: "w-full max-w-[1600px] mx-auto select-none",
+
+            div { class: "flex items-center justify-between mb-8",
+                button {
+                    class: "flex items-center gap-2 text-slate-400 hover:text-white transition-colors",
+                    onclick: move |_| on_close.call(()),
+                    i { class: "fa-solid fa-arrow-left" }
+                    "{i18n::t(\"back_to_playlists\")}"
+                }
+                button {
+                    class: "flex items-center gap-2 text-slate-400 hover:text-white transition-colors",
+                    onclick: {
+                        let playlist = playlist.clone();
+                        move |_| on_edit.call(playlist.clone())
+                    },
+                    i { class: "fa-solid fa-sliders" }
+                    "{i18n::t(\"edit_rules\")}"
+                }
+            }
+
+            crate::showcase::Showcase {
+                name: playlist.name.clone(),
+                description: i18n::t("smart_playlist").to_string(),
+                cover_url,
+                tracks: tracks(),
+                library,
+                on_play: move |idx: usize| {
+                    queue.set(tracks.peek().clone());
+                    ctrl.play_track(idx);
+                },
+                active_track: None,
+            }
+        }
+    }
+}
+
+#[component]
+pub fn SmartPlaylistEditor(
+    playlist_store: Signal<PlaylistStore>,
+    playlist: SmartPlaylist,
+    on_close: EventHandler<()>,
+    /// Called after the playlist was deleted, so a detail view can close too.
+    on_delete: EventHandler<()>,
+) -> Element {
+    let is_new = !playlist_store
+        .peek()
+        .smart_playlists
+        .iter()
+        .any(|p| p.id == playlist.id);
+    let mut draft = use_signal(|| playlist);
+
+    let save = move |_| {
+        let mut smart = draft.peek().clone();
+        smart.name = smart.name.trim().to_string();
+        if smart.name.is_empty() {
+            smart.name = i18n::t("smart_playlist").to_string();
+        }
+        let mut store = playlist_store.write();
+        match store.smart_playlists.iter_mut().find(|p| p.id == smart.id) {
+            Some(existing) => *existing = smart,
+            None => store.smart_playlists.push(smart),
+        }
+        on_close.call(());
+    };
+
+    let delete = move |_| {
+        let id = draft.peek().id.clone();
+        playlist_store
+            .write()
+            .smart_playlists
+            .retain(|p| p.id != id);
+        on_delete.call(());
+    };
+
+    let input_class = "bg-white/5 border border-white/10 rounded-lg px-3 py-1.5 text-sm text-white placeholder:text-white/30 focus:outline-none focus:border-white/20";
+    let select_class = "bg-stone-800 text-white rounded-lg px-2 py-1.5 text-sm border border-white/10 focus:outline-none focus:border-indigo-500";
+    let current = draft.read().clone();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/80 flex items-center justify-center z-50",
+            onclick: move |_| on_close.call(()),
+            onkeydown: move |evt| evt.stop_propagation(),
+            div {
+                class: "bg-neutral-900 rounded-xl border border-white/10 w-full max-w-2xl p-6 max-h-[85vh] overflow-y-auto",
+                onclick: move |e| e.stop_propagation(),
+                h2 {
+                    class: "text-xl font-bold text-white mb-4",
+                    if is_new { "{i18n::t(\"new_smart_playlist\")}" } else { "{i18n::t(\"edit_rules\")}" }
+                }
+
+                input {
+                    class: "{input_class} w-full mb-4",
+                    r#type: "text",
+                    placeholder: "{i18n::t(\"playlist_name_placeholder\")}",
+                    value: "{current.name}",
+                    oninput: move |evt| draft.write().name = evt.value(),
+                }
+
+                div { class: "flex items-center gap-2 text-sm text-slate-300 mb-3",
+                    select {
+                        class: select_class,
+                        value: if current.match_all { "all" } else { "any" },
+                        onchange: move |evt| draft.write().match_all = evt.value() == "all",
+                        option { value: "all", "{i18n::t(\"smart_match_all\")}" }
+                        option { value: "any", "{i18n::t(\"smart_match_any\")}" }
+                    }
+                }
+
+                div { class: "space-y-2",
+                    for (i, rule) in current.rules.iter().enumerate() {
+                        div { key: "{i}", class: "flex items-center gap-2",
+                            select {
+                                class: select_class,
+                                value: "{RULE_FIELDS.iter().position(|f| *f == rule.field).unwrap_or(0)}",
+                                onchange: move |evt| {
+                                    let Some(field) = evt.value().parse::<usize>().ok().and_then(|n| RULE_FIELDS.get(n)) else {
+                                        return;
+                                    };
+                                    let mut draft = draft.write();
+                                    let value = std::mem::take(&mut draft.rules[i].value);
+                                    draft.rules[i] = Rule { value, ..Rule::new(*field) };
+                                },
+                                for (n, field) in RULE_FIELDS.iter().enumerate() {
+                                    option { value: "{n}", "{field_label(*field)}" }
+                                }
+                            }
+                            select {
+                                class: select_class,
+                                value: "{rule.field.ops().iter().position(|o| *o == rule.op).unwrap_or(0)}",
+                                onchange: move |evt| {
+                                    let mut draft = draft.write();
+                                    let ops = draft.rules[i].field.ops();
+                                    if let Some(op) = evt.value().parse::<usize>().ok().and_then(|n| ops.get(n)) {
+                                        draft.rules[i].op = *op;
+                                    }
+                                },
+                                for (n, op) in rule.field.ops().iter().enumerate() {
+                                    option { value: "{n}", "{op_label(*op)}" }
+                                }
+                            }
+                            if rule.field.takes_value() {
+                                input {
+                                    class: "{input_class} flex-1 min-w-0",
+                                    r#type: if matches!(rule.field, RuleField::Title | RuleField::Artist | RuleField::Album | RuleField::Genre) { "text" } else { "number" },
+                                    placeholder: if matches!(rule.op, RuleOp::InLast | RuleOp::NotInLast) { i18n::t("smart_days").to_string() } else { String::new() },
+                                    value: "{rule.value}",
+                                    oninput: move |evt| draft.write().rules[i].value = evt.value(),
+                                }
+                            } else {
+                                div { class: "flex-1" }
+                            }
+                            button {
+                                class: "text-slate-500 hover:text-red-400 px-2 transition-colors",
+                                onclick: move |_| {
+                                    draft.write().rules.remove(i);
+                                },
+                                i { class: "fa-solid fa-xmark" }
+                            }
+                        }
+                    }
+                }
+                button {
+                    class: "bg-white/10 hover:bg-white/20 px-3 py-1 rounded text-sm text-white transition-colors mt-3",
+                    onclick: move |_| draft.write().rules.push(Rule::new(RuleField::Genre)),
+                    i { class: "fa-solid fa-plus mr-2" }
+                    "{i18n::t(\"add_rule\")}"
+                }
+
+                div { class: "flex flex-wrap items-center gap-3 mt-6 text-sm text-slate-300",
+                    span { "{i18n::t(\"sort_by\")}" }
+                    select {
+                        class: select_class,
+                        value: "{SMART_SORTS.iter().position(|s| *s == current.sort).unwrap_or(0)}",
+                        onchange: move |evt| {
+                            if let Some(sort) = evt.value().parse::<usize>().ok().and_then(|n| SMART_SORTS.get(n)) {
+                                draft.write().sort = *sort;
+                            }
+                        },
+                        for (n, sort) in SMART_SORTS.iter().enumerate() {
+                            option { value: "{n}", "{sort_label(*sort)}" }
+                        }
+                    }
+                    label { class: "flex items-center gap-1.5",
+                        input {
+                            r#type: "checkbox",
+                            checked: current.descending,
+                            onchange: move |evt| draft.write().descending = evt.checked(),
+                        }
+                        "{i18n::t(\"smart_descending\")}"
+                    }
+                    span { class: "ml-auto", "{i18n::t(\"smart_limit\")}" }
+                    input {
+                        class: "{input_class} w-24",
+                        r#type: "number",
+                        min: "1",
+                        placeholder: "∞",
+                        value: current.limit.map(|n| n.to_string()).unwrap_or_default(),
+                        oninput: move |evt| {
+                            draft.write().limit = evt.value().trim().parse::<usize>().ok().filter(|n| *n > 0);
+                        },
+                    }
+                }
+
+                div { class: "flex items-center justify-between mt-6",
+                    if is_new {
+                        div {}
+                    } else {
+                        button {
+                            class: "px-4 py-2 text-sm text-red-400 hover:text-red-300 transition-colors",
+                            onclick: delete,
+                            "{i18n::t(\"delete_playlist\")}"
+                        }
+                    }
+                    div { class: "flex gap-2",
+                        button {
+                            class: "px-4 py-2 text-sm text-slate-400 hover:text-white transition-colors",
+                            onclick: move |_| on_close.call(()),
+                            "{i18n::t(\"cancel\")}"
+                        }
+                        button {
+                            class: "px-4 py-2 text-sm bg-white text-black rounded-lg font-medium hover:bg-white/90 transition-colors",
+                            onclick: save,
+                            "{i18n::t(\"save\")}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use config::{AppConfig, MusicService};
+use dioxus::{logger::tracing, prelude::*};
+use reader::FavoritesStore;
+use reader::models::{Track, server_item_id};
+use std::path::PathBuf;
+
+/// Five clickable stars. Clicking the current rating again clears it.
+#[component]
+pub fn StarRating(
+    rating: u8,
+    on_rate: EventHandler<u8>,
+    #[props(default = "text-xs".to_string())] size: String,
+) -> Element {
+    let mut hovered = use_signal(|| None::<u8>);
+    let shown = hovered().unwrap_or(rating);
+
+    rsx! {
+        div {
+            class: "flex items-center gap-0.5",
+            onmouseleave: move |_| hovered.set(None),
+            for star in 1..=5u8 {
+                button {
+                    key: "{star}",
+                    class: if star <= shown { "text-amber-400 transition-colors" } else { "text-slate-500 hover:text-amber-300 transition-colors" },
+                    title: i18n::t_with("rate_stars", &[("stars", star.to_string())]),
+                    onmouseenter: move |_| hovered.set(Some(star)),
+                    onclick: move |evt| {
+                        evt.stop_propagation();
+                        on_rate.call(if star == rating { 0 } else { star });
+                    },
+                    i { class: if star <= shown { "fa-solid fa-star {size}" } else { "fa-regular fa-star {size}" } }
+                }
+            }
+        }
+    }
+}
+
+/// Rates a track. The store is the source of truth and the app copies it onto
+/// library tracks; the queue holds its own copies, so those are updated here.
+/// Local files get the rating written to their tags when that is enabled,
+/// server tracks send it to the server.
+pub fn rate_track(
+    path: PathBuf,
+    stars: u8,
+    mut favorites_store: Signal<FavoritesStore>,
+    mut queue: Signal<Vec<Track>>,
+    config: Signal<AppConfig>,
+) {
+    let stars = stars.min(5);
+    favorites_store.write().set_track_rating(&path, stars);
+
+    let rating = Some(stars).filter(|s| *s > 0);
+    if queue
+        .peek()
+        .iter()
+        .any(|t| t.path == path && t.rating != rating)
+    {
+        for track in queue.write().iter_mut().filter(|t| t.path == path) {
+            track.rating = rating;
+        }
+    }
+
+    let path_str = path.to_string_lossy().into_owned();
+    if let Some(item_id) = server_item_id(&path_str) {
+        let item_id = item_id.to_string();
+        spawn(async move {
+            if let Err(e) = sync_rating(config, &item_id, stars).await {
+                tracing::warn!("Failed to sync rating to server: {e}");
+            }
+        });
+        return;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if config.peek().write_ratings_to_tags {
+        spawn(async move {
+            let result =
+                tokio::task::spawn_blocking(move || reader::tag_writer::write_rating(&path, stars))
+                    .await;
+            if let Ok(Err(e)) = result {
+                tracing::warn!("Failed to write rating to tags: {e}");
+            }
+        });
+    }
+}
+
+/// Rates an album, syncing it to the server for server albums.
+pub fn rate_album(
+    album_id: String,
+    stars: u8,
+    mut favorites_store: Signal<FavoritesStore>,
+    config: Signal<AppConfig>,
+) {
+    let item_id = server_item_id(&album_id).map(str::to_string);
+    favorites_store.write().set_album_rating(album_id, stars);
+
+    if let Some(item_id) = item_id {
+        spawn(async move {
+            if let Err(e) = sync_rating(config, &item_id, stars).await {
+                tracing::warn!("Failed to sync album rating to server: {e}");
+            }
+        });
+    }
+}
+
+async fn sync_rating(config: Signal<AppConfig>, item_id: &str, stars: u8) -> Result<(), String> {
+    let (service, url, token, user_id, device_id) = {
+        let conf = config.peek();
+        let Some(server) = &conf.server else {
+            return Ok(());
+        };
+        let (Some(token), Some(user_id)) = (&server.access_token, &server.user_id) else {
+            return Ok(());
+        };
+        (
+            server.service,
+            server.url.clone(),
+            token.clone(),
+            user_id.clone(),
+            conf.device_id.clone(),
+        )
+    };
+
+    match service {
+        MusicService::Jellyfin => {
+            server::jellyfin::JellyfinClient::new(&url, Some(&token), &device_id, Some(&user_id))
+                .set_rating(item_id, stars)
+                .await
+        }
+        MusicService::Subsonic | MusicService::Custom => {
+            server::subsonic::SubsonicClient::new(&url, &user_id, &token)
+                .set_rating(item_id, stars)
+                .await
+        }
+    }
+}
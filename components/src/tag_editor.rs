@@ -0,0 +1,525 @@
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+#[cfg(not(target_arch = "wasm32"))]
+use hooks::use_player_controller::PlayerController;
+#[cfg(not(target_arch = "wasm32"))]
+use reader::metadata::{CoverMemo, ScannedTrack};
+#[cfg(not(target_arch = "wasm32"))]
+use reader::models::{AlbumGrouping, CoverOptions, Track};
+#[cfg(not(target_arch = "wasm32"))]
+use reader::tag_writer::{TagBackup, TagEdit};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+/// App-wide tag editor state: where album covers are cached, and the backups
+/// from the most recent write so it can be undone from any editor.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Copy)]
+pub struct TagEditorState {
+    pub cover_cache: Signal<PathBuf>,
+    pub last_write: Signal<Vec<TagBackup>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Title,
+    Artists,
+    Album,
+    AlbumArtist,
+    TrackNumber,
+    DiscNumber,
+    Year,
+    Genres,
+    Lyrics,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const FIELDS: [Field; 9] = [
+    Field::Title,
+    Field::Artists,
+    Field::Album,
+    Field::AlbumArtist,
+    Field::TrackNumber,
+    Field::DiscNumber,
+    Field::Year,
+    Field::Genres,
+    Field::Lyrics,
+];
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Field {
+    fn label(self) -> &'static str {
+        match self {
+            Field::Title => "title",
+            Field::Artists => "artists",
+            Field::Album => "album",
+            Field::AlbumArtist => "album_artist",
+            Field::TrackNumber => "track_number",
+            Field::DiscNumber => "disc",
+            Field::Year => "year",
+            Field::Genres => "genre",
+            Field::Lyrics => "lyrics_tag",
+        }
+    }
+
+    fn value(self, track: &Track) -> String {
+        match self {
+            Field::Title => track.title.clone(),
+            Field::Artists => track.artists.join("; "),
+            Field::Album => track.album.clone(),
+            Field::AlbumArtist => track.album_artist.clone().unwrap_or_default(),
+            Field::TrackNumber => track
+                .track_number
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            Field::DiscNumber => track.disc_number.map(|n| n.to_string()).unwrap_or_default(),
+            Field::Year => track.date.clone().unwrap_or_default(),
+            Field::Genres => track.genres.join("; "),
+            // read off the UI thread once the editor is open
+            Field::Lyrics => String::new(),
+        }
+    }
+}
+
+// Only `;` separates, like the reader does: commas belong to names such as
+// "Earth, Wind & Fire".
+#[cfg(not(target_arch = "wasm32"))]
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_number(value: &str) -> Result<Option<u32>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| i18n::t_with("invalid_number", &[("value", value.to_string())]))
+}
+
+/// Builds the edit from the fields the user touched; untouched fields are kept.
+#[cfg(not(target_arch = "wasm32"))]
+fn build_edit(
+    values: &[String],
+    keep: &[bool],
+    cover: Option<Option<Vec<u8>>>,
+) -> Result<TagEdit, String> {
+    let mut edit = TagEdit {
+        cover,
+        ..Default::default()
+    };
+    for (i, field) in FIELDS.iter().enumerate() {
+        if keep[i] {
+            continue;
+        }
+        let value = values[i].clone();
+        match field {
+            Field::Title => edit.title = Some(value),
+            Field::Artists => edit.artists = Some(split_list(&value)),
+            Field::Album => edit.album = Some(value),
+            Field::AlbumArtist => edit.album_artist = Some(value),
+            Field::TrackNumber => edit.track_number = Some(parse_number(&value)?),
+            Field::DiscNumber => edit.disc_number = Some(parse_number(&value)?),
+            Field::Year => edit.date = Some(value),
+            Field::Genres => edit.genres = Some(split_list(&value)),
+            Field::Lyrics => edit.lyrics = Some(value),
+        }
+    }
+    Ok(edit)
+}
+
+/// Merges re-read files into the library as it is now, so a scan or rating
+/// change made while the tags were being written survives, and pushes them into
+/// the play queue, including the now playing labels.
+#[cfg(not(target_arch = "wasm32"))]
+fn apply_reread(mut ctrl: PlayerController, scanned: Vec<ScannedTrack>) {
+    let updated: Vec<Track> = ctrl.library.with_mut(|lib| {
+        scanned
+            .into_iter()
+            .map(|s| reader::metadata::merge_reread(s, lib))
+            .collect()
+    });
+    let index = *ctrl.current_queue_index.peek();
+    for mut queue in [ctrl.queue, ctrl.up_next] {
+        queue.with_mut(|queue| {
+            for track in queue.iter_mut() {
+                if let Some(fresh) = updated.iter().find(|t| t.path == track.path) {
+                    *track = fresh.clone();
+                }
+            }
+        });
+    }
+    let current = ctrl.queue.peek().get(index).cloned();
+    if let Some(current) = current
+        && updated.iter().any(|t| t.path == current.path)
+    {
+        ctrl.current_song_title.set(current.title);
+        ctrl.current_song_artist.set(current.artist);
+        ctrl.current_song_album.set(current.album);
+    }
+}
+
+/// Reads the edited files back; run off the UI thread.
+#[cfg(not(target_arch = "wasm32"))]
+fn reread_all(
+    paths: &[PathBuf],
+    cover_cache: &Path,
+    grouping: AlbumGrouping,
+    covers: &CoverOptions,
+) -> Vec<ScannedTrack> {
+    let memo = CoverMemo::default();
+    paths
+        .iter()
+        .filter_map(|path| reader::metadata::probe(path, grouping, covers, cover_cache, &memo))
+        .collect()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn scan_options(ctrl: PlayerController) -> (AlbumGrouping, CoverOptions) {
+    let lib = ctrl.library.peek();
+    (
+        lib.album_grouping.unwrap_or_default(),
+        lib.cover_options.clone().unwrap_or_default(),
+    )
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[component]
+pub fn TagEditor(paths: Vec<PathBuf>, on_close: EventHandler) -> Element {
+    let ctrl = use_context::<PlayerController>();
+    let mut state = use_context::<TagEditorState>();
+    let batch = paths.len() > 1;
+
+    // a field starts filled in only when every selected track agrees on it
+    let initial = use_hook({
+        let paths = paths.clone();
+        move || {
+            let lib = ctrl.library.peek();
+            let tracks: Vec<&Track> = paths
+                .iter()
+                .filter_map(|p| lib.tracks.iter().find(|t| &t.path == p))
+                .collect();
+            FIELDS
+                .iter()
+                .map(|field| {
+                    if *field == Field::Lyrics && tracks.len() != 1 {
+                        return (String::new(), tracks.len() > 1);
+                    }
+                    let mut values = tracks.iter().map(|t| field.value(t));
+                    let first = values.next().unwrap_or_default();
+                    let mixed = values.any(|v| v != first);
+                    if mixed {
+                        (String::new(), true)
+                    } else {
+                        (first, false)
+                    }
+                })
+                .collect::<Vec<_>>()
+        }
+    });
+
+    let mut values = use_signal({
+        let initial = initial.clone();
+        move || initial.iter().map(|(v, _)| v.clone()).collect::<Vec<_>>()
+    });
+    let mut keep = use_signal(|| vec![true; FIELDS.len()]);
+    let mut cover = use_signal(|| None::<Option<Vec<u8>>>);
+    let mut error = use_signal(|| None::<String>);
+    let mut saving = use_signal(|| false);
+
+    // embedded lyrics mean reading the file, so they fill in once loaded
+    use_hook({
+        let paths = paths.clone();
+        move || {
+            let [path] = paths.as_slice() else {
+                return;
+            };
+            let path = path.clone();
+            let lyrics = FIELDS
+                .iter()
+                .position(|f| *f == Field::Lyrics)
+                .unwrap_or_default();
+            spawn(async move {
+                let Ok(embedded) =
+                    tokio::task::spawn_blocking(move || reader::lyrics::read_embedded(&path)).await
+                else {
+                    return;
+                };
+                // don't clobber anything typed in the meantime
+                if keep.peek()[lyrics] {
+                    values.write()[lyrics] = embedded.plain.or(embedded.synced).unwrap_or_default();
+                }
+            });
+        }
+    });
+
+    let save = {
+        let paths = paths.clone();
+        move |_| {
+            let edit = match build_edit(&values.peek(), &keep.peek(), cover.peek().clone()) {
+                Ok(edit) => edit,
+                Err(e) => {
+                    error.set(Some(e));
+                    return;
+                }
+            };
+            if edit.is_empty() {
+                on_close.call(());
+                return;
+            }
+
+            let paths = paths.clone();
+            let cover_cache = state.cover_cache.peek().clone();
+            let (grouping, covers) = scan_options(ctrl);
+            saving.set(true);
+            spawn(async move {
+                let result = tokio::task::spawn_blocking(move || {
+                    let mut backups = Vec::new();
+                    let mut failures = Vec::new();
+                    for path in &paths {
+                        match reader::tag_writer::write_tags(path, &edit) {
+                            Ok(backup) => backups.push(backup),
+                            Err(e) => failures.push(format!("{}: {e}", path.display())),
+                        }
+                    }
+                    let scanned = reread_all(&paths, &cover_cache, grouping, &covers);
+                    (scanned, backups, failures)
+                })
+                .await;
+                saving.set(false);
+
+                let (scanned, backups, failures) = match result {
+                    Ok(done) => done,
+                    Err(e) => {
+                        error.set(Some(i18n::t_with(
+                            "tag_write_failed",
+                            &[("error", e.to_string())],
+                        )));
+                        return;
+                    }
+                };
+                apply_reread(ctrl, scanned);
+                if !backups.is_empty() {
+                    state.last_write.set(backups);
+                }
+                if failures.is_empty() {
+                    on_close.call(());
+                } else {
+                    error.set(Some(i18n::t_with(
+                        "tag_write_failed",
+                        &[("error", failures.join("\n"))],
+                    )));
+                }
+            });
+        }
+    };
+
+    let undo = move |_| {
+        let backups = std::mem::take(&mut *state.last_write.write());
+        if backups.is_empty() {
+            return;
+        }
+        // kept to offer the undo again if restoring falls over
+        let pending = backups.clone();
+        let cover_cache = state.cover_cache.peek().clone();
+        let (grouping, covers) = scan_options(ctrl);
+        saving.set(true);
+        spawn(async move {
+            let result = tokio::task::spawn_blocking(move || {
+                let mut failures = Vec::new();
+                for backup in &backups {
+                    if let Err(e) = reader::tag_writer::restore(backup) {
+                        failures.push(format!("{}: {e}", backup.path.display()));
+                    }
+                }
+                let paths: Vec<PathBuf> = backups.into_iter().map(|b| b.path).collect();
+                let scanned = reread_all(&paths, &cover_cache, grouping, &covers);
+                (scanned, failures)
+            })
+            .await;
+            saving.set(false);
+
+            let (scanned, failures) = match result {
+                Ok(done) => done,
+                Err(e) => {
+                    state.last_write.set(pending);
+                    error.set(Some(i18n::t_with(
+                        "tag_write_failed",
+                        &[("error", e.to_string())],
+                    )));
+                    return;
+                }
+            };
+            apply_reread(ctrl, scanned);
+            if failures.is_empty() {
+                on_close.call(());
+            } else {
+                error.set(Some(i18n::t_with(
+                    "tag_write_failed",
+                    &[("error", failures.join("\n"))],
+                )));
+            }
+        });
+    };
+
+    let pick_cover = move |_| {
+        spawn(async move {
+            let file = rfd::AsyncFileDialog::new()
+                .add_filter("Images", &["jpg", "jpeg", "png", "webp", "gif", "bmp"])
+                .pick_file()
+                .await;
+            if let Some(file) = file {
+                cover.set(Some(Some(file.read().await)));
+            }
+        });
+    };
+
+    let cover_status = match &*cover.read() {
+        None => i18n::t("tag_cover_unchanged"),
+        Some(Some(_)) => i18n::t("tag_cover_new"),
+        Some(None) => i18n::t("tag_cover_removed"),
+    };
+    let title = if batch {
+        i18n::t_with("edit_tags_count", &[("count", paths.len().to_string())])
+    } else {
+        i18n::t("edit_tags")
+    };
+    let can_undo = !state.last_write.read().is_empty();
+    let input_class = "w-full bg-white/5 border border-white/10 rounded-lg px-3 py-1.5 text-sm text-white placeholder:text-white/30 focus:outline-none focus:border-white/20";
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/80 flex items-center justify-center z-50",
+            onclick: move |evt| {
+                evt.stop_propagation();
+                on_close.call(());
+            },
+            // rendered inside track rows, so keep row gestures from firing underneath
+            ondoubleclick: move |evt| evt.stop_propagation(),
+            oncontextmenu: move |evt| evt.stop_propagation(),
+            onmousedown: move |evt| evt.stop_propagation(),
+            ontouchstart: move |evt| evt.stop_propagation(),
+            onkeydown: move |evt| evt.stop_propagation(),
+            div {
+                class: "bg-neutral-900 rounded-xl border border-white/10 w-full max-w-xl p-6 max-h-[85vh] overflow-y-auto",
+                onclick: move |e| e.stop_propagation(),
+                h2 { class: "text-xl font-bold text-white mb-4", "{title}" }
+
+                if let Some(err) = error() {
+                    p { class: "text-sm text-red-400 mb-4 whitespace-pre-wrap", "{err}" }
+                }
+
+                div { class: "space-y-3",
+                    for (i, field) in FIELDS.iter().copied().enumerate() {
+                        div { key: "{i}", class: "flex flex-col gap-1",
+                            div { class: "flex items-center justify-between",
+                                label { class: "text-xs text-slate-400", "{i18n::t(field.label())}" }
+                                if batch {
+                                    label { class: "flex items-center gap-1.5 text-xs text-slate-500",
+                                        input {
+                                            r#type: "checkbox",
+                                            checked: keep.read()[i],
+                                            onchange: move |evt| keep.write()[i] = evt.checked(),
+                                        }
+                                        "{i18n::t(\"tag_keep_existing\")}"
+                                    }
+                                }
+                            }
+                            if field == Field::Lyrics {
+                                textarea {
+                                    class: "{input_class} h-32 resize-y font-mono",
+                                    placeholder: if initial[i].1 { i18n::t("multiple_values") } else { String::new() },
+                                    value: "{values.read()[i]}",
+                                    oninput: move |evt| {
+                                        values.write()[i] = evt.value();
+                                        keep.write()[i] = false;
+                                    },
+                                }
+                            } else {
+                                input {
+                                    class: "{input_class}",
+                                    r#type: "text",
+                                    placeholder: if initial[i].1 { i18n::t("multiple_values") } else { String::new() },
+                                    value: "{values.read()[i]}",
+                                    oninput: move |evt| {
+                                        values.write()[i] = evt.value();
+                                        keep.write()[i] = false;
+                                    },
+                                }
+                            }
+                        }
+                    }
+
+                    div { class: "flex flex-col gap-1",
+                        label { class: "text-xs text-slate-400", "{i18n::t(\"cover\")}" }
+                        div { class: "flex items-center gap-3",
+                            button {
+                                class: "bg-white/10 hover:bg-white/20 px-3 py-1 rounded text-sm text-white transition-colors",
+                                onclick: pick_cover,
+                                "{i18n::t(\"choose_image\")}"
+                            }
+                            button {
+                                class: "text-red-400 hover:text-red-300 text-sm px-2 py-1 rounded transition-colors",
+                                onclick: move |_| cover.set(Some(None)),
+                                "{i18n::t(\"remove_cover\")}"
+                            }
+                            if cover.read().is_some() {
+                                button {
+                                    class: "text-slate-400 hover:text-white text-sm px-2 py-1 rounded transition-colors",
+                                    onclick: move |_| cover.set(None),
+                                    i { class: "fa-solid fa-rotate-left" }
+                                }
+                            }
+                            span { class: "text-xs text-slate-500", "{cover_status}" }
+                        }
+                    }
+                }
+
+                div { class: "flex items-center justify-between mt-6",
+                    if can_undo {
+                        button {
+                            class: "px-4 py-2 text-sm text-slate-400 hover:text-white transition-colors disabled:opacity-50",
+                            disabled: saving(),
+                            onclick: undo,
+                            i { class: "fa-solid fa-rotate-left mr-2" }
+                            "{i18n::t(\"undo_last_tag_edit\")}"
+                        }
+                    } else {
+                        div {}
+                    }
+                    div { class: "flex gap-2",
+                        button {
+                            class: "px-4 py-2 text-sm text-slate-400 hover:text-white transition-colors",
+                            onclick: move |_| on_close.call(()),
+                            "{i18n::t(\"cancel\")}"
+                        }
+                        button {
+                            class: "px-4 py-2 text-sm bg-white text-black rounded-lg font-medium hover:bg-white/90 transition-colors disabled:opacity-50",
+                            disabled: saving(),
+                            onclick: save,
+                            "{i18n::t(\"save\")}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Tag writing needs local files.
+#[cfg(target_arch = "wasm32")]
+#[component]
+pub fn TagEditor(paths: Vec<PathBuf>, on_close: EventHandler) -> Element {
+    let _ = paths;
+    let _ = on_close;
+    rsx! {}
+}
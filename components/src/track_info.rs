@@ -0,0 +1,115 @@
+use dioxus::prelude::*;
+use reader::models::Track;
+
+/// "44.1 kHz · 16-bit · 1411 kbps", leaving out whatever the source did not report.
+pub fn format_quality(khz: u32, bit_depth: Option<u8>, kbps: u32) -> String {
+    let mut parts = Vec::new();
+    if khz > 0 {
+        parts.push(format!("{} kHz", khz as f32 / 1000.0));
+    }
+    if let Some(bits) = bit_depth.filter(|b| *b > 0) {
+        parts.push(format!("{bits}-bit"));
+    }
+    if kbps > 0 {
+        parts.push(format!("{kbps} kbps"));
+    }
+    parts.join(" · ")
+}
+
+pub(crate) fn format_size(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= MB {
+        format!("{:.1} MB", bytes as f64 / MB)
+    } else {
+        format!("{:.0} KB", bytes as f64 / 1024.0)
+    }
+}
+
+fn format_duration(seconds: u64) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+// Days-since-epoch to civil date, so we don't pull in a date crate for one label.
+fn format_unix_date(secs: u64) -> String {
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[component]
+pub fn TrackInfoModal(track: Track, on_close: EventHandler) -> Element {
+    let mut rows: Vec<(String, String)> = Vec::new();
+    let mut push = |key: &str, value: Option<String>| {
+        if let Some(value) = value.filter(|v| !v.trim().is_empty()) {
+            rows.push((i18n::t(key).to_string(), value));
+        }
+    };
+
+    push("title", Some(track.title.clone()));
+    push("artist", Some(track.artist.clone()));
+    push("album", Some(track.album.clone()));
+    push("album_artist", track.album_artist.clone());
+    push("composer", track.composer.clone());
+    push("release_date", track.date.clone());
+    push("genre", Some(track.genres.join(", ")));
+    push("track_number", track.track_number.map(|n| n.to_string()));
+    push("disc", track.disc_number.map(|n| n.to_string()));
+    push("time", Some(format_duration(track.duration)));
+    push("bpm", track.bpm.map(|b| b.to_string()));
+    push("record_label", track.label.clone());
+    push("isrc", track.isrc.clone());
+    push("codec", track.codec.clone());
+    push(
+        "quality",
+        Some(format_quality(track.khz, track.bit_depth, track.bitrate)),
+    );
+    push("file_size", track.file_size.map(format_size));
+    push("date_added", track.date_added.map(format_unix_date));
+    push("comment", track.comment.clone());
+    push("mb_recording_id", track.musicbrainz_recording_id.clone());
+    push(
+        "mb_artist_ids",
+        Some(track.musicbrainz_artist_ids.join(", ")),
+    );
+    push("file_path", Some(track.path.display().to_string()));
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black/80 flex items-center justify-center z-50",
+            onclick: move |evt| {
+                evt.stop_propagation();
+                on_close.call(());
+            },
+            // rendered inside track rows, so keep row gestures from firing underneath
+            ondoubleclick: move |evt| evt.stop_propagation(),
+            oncontextmenu: move |evt| evt.stop_propagation(),
+            onmousedown: move |evt| evt.stop_propagation(),
+            ontouchstart: move |evt| evt.stop_propagation(),
+            div {
+                class: "bg-neutral-900 rounded-xl border border-white/10 w-full max-w-lg p-6 max-h-[80vh] overflow-y-auto",
+                onclick: move |e| e.stop_propagation(),
+                h2 { class: "text-xl font-bold text-white mb-4", "{i18n::t(\"track_info\")}" }
+                dl { class: "grid grid-cols-[max-content_1fr] gap-x-6 gap-y-2 text-sm",
+                    for (label, value) in rows {
+                        dt { class: "text-slate-500", "{label}" }
+                        dd { class: "text-white/90 break-all select-text", "{value}" }
+                    }
+                }
+                div { class: "flex justify-end mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm text-slate-400 hover:text-white transition-colors",
+                        onclick: move |_| on_close.call(()),
+                        "{i18n::t(\"close\")}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,11 @@
 use crate::dots_menu::{DotsMenu, MenuAction};
+use crate::tag_editor::TagEditor;
+use crate::track_info::TrackInfoModal;
 use dioxus::prelude::*;
-use reader::models::Track;
+use hooks::radio::RadioSeed;
+use hooks::use_player_controller::PlayerController;
+use reader::BookmarkStore;
+use reader::models::{Track, rating_key};
 
 #[component]
 pub fn TrackRow(
@@ -22,6 +27,8 @@ pub fn TrackRow(
     let add_to_playlist_text = i18n::t("add_to_playlist").to_string();
     let remove_from_playlist_text = i18n::t("remove_from_playlist").to_string();
     let delete_song_text = i18n::t("delete").to_string();
+    let track_info_text = i18n::t("track_info").to_string();
+    let edit_tags_text = i18n::t("edit_tags").to_string();
     
     let mut actions = vec![MenuAction::new(add_to_playlist_text.as_str(), "fa-solid fa-plus")];
 
@@ -30,6 +37,41 @@ pub fn TrackRow(
         actions.push(MenuAction::new(remove_from_playlist_text.as_str(), "fa-solid fa-minus"));
     }
 
+    let mut ctrl = use_context::<PlayerController>();
+    let play_next_idx = actions.len();
+    actions.push(MenuAction::new(i18n::t("play_next"), "fa-solid fa-arrow-turn-down"));
+    let enqueue_idx = actions.len();
+    actions.push(MenuAction::new(i18n::t("add_to_queue"), "fa-solid fa-list"));
+    let radio_idx = actions.len();
+    actions.push(MenuAction::new(i18n::t("start_radio"), "fa-solid fa-tower-broadcast"));
+    let queue_track = track.clone();
+
+    let bookmarks = use_context::<Signal<BookmarkStore>>();
+    let resume_progress = bookmarks
+        .read()
+        .progress(&rating_key(&track.path), track.duration);
+    let restart_idx = actions.len();
+    if resume_progress.is_some() {
+        actions.push(MenuAction::new(
+            i18n::t("restart_from_beginning"),
+            "fa-solid fa-backward-fast",
+        ));
+    }
+
+    let info_idx = actions.len();
+    actions.push(MenuAction::new(track_info_text.as_str(), "fa-solid fa-circle-info"));
+    let mut show_info = use_signal(|| false);
+    let info_track = track.clone();
+
+    // server tracks use "jellyfin:<id>" style paths; only real files can be retagged
+    let can_edit_tags = cfg!(not(target_arch = "wasm32")) && track.path.is_absolute();
+    let edit_idx = actions.len();
+    if can_edit_tags {
+        actions.push(MenuAction::new(edit_tags_text.as_str(), "fa-solid fa-pen"));
+    }
+    let mut show_editor = use_signal(|| false);
+    let edit_path = track.path.clone();
+
     if !hide_delete {
         actions.push(MenuAction::new(delete_song_text.as_str(), "fa-solid fa-trash").destructive());
     }
@@ -122,6 +164,14 @@ pub fn TrackRow(
             div { class: "flex-1 min-w-0 pr-4",
                 p { class: "text-sm font-medium text-white/90 truncate", "{track.title}" }
                 p { class: "text-xs text-slate-500 truncate", "{track.artist}" }
+                if let Some(progress) = resume_progress.map(|p| format!("{:.1}%", p * 100.0)) {
+                    div { class: "mt-1 h-0.5 w-full max-w-48 rounded-full bg-white/10 overflow-hidden",
+                        div {
+                            class: "h-full bg-indigo-400/80",
+                            style: "width: {progress}",
+                        }
+                    }
+                }
             }
 
             if !is_selection_mode {
@@ -139,12 +189,46 @@ pub fn TrackRow(
                             if let Some(handler) = on_remove_from_playlist {
                                 handler.call(());
                             }
+                        } else if idx == play_next_idx {
+                            on_close_menu.call(());
+                            ctrl.enqueue_next(queue_track.clone());
+                        } else if idx == enqueue_idx {
+                            on_close_menu.call(());
+                            ctrl.enqueue(queue_track.clone());
+                        } else if idx == radio_idx {
+                            on_close_menu.call(());
+                            ctrl.start_radio(RadioSeed::Track(Box::new(queue_track.clone())));
+                        } else if resume_progress.is_some() && idx == restart_idx {
+                            on_close_menu.call(());
+                            if !ctrl.restart_from_beginning(&queue_track) {
+                                on_play.call(());
+                            }
+                        } else if idx == info_idx {
+                            on_close_menu.call(());
+                            show_info.set(true);
+                        } else if can_edit_tags && idx == edit_idx {
+                            on_close_menu.call(());
+                            show_editor.set(true);
                         } else {
                             on_delete.call(());
                         }
                     },
                 }
             }
+
+            if show_info() {
+                TrackInfoModal {
+                    track: info_track,
+                    on_close: move |_| show_info.set(false),
+                }
+            }
+
+            if show_editor() {
+                TagEditor {
+                    paths: vec![edit_path],
+                    on_close: move |_| show_editor.set(false),
+                }
+            }
         }
     }
 }
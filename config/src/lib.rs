@@ -141,6 +141,13 @@ pub enum SortOrder {
     Title,
     Artist,
     Album,
+    Year,
+    DateAdded,
+    Composer,
+    Genre,
+    Bpm,
+    Bitrate,
+    Rating,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -156,6 +163,17 @@ pub enum BackBehavior {
     AlwaysPrev,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ShuffleMode {
+    /// A plain random permutation.
+    #[default]
+    Random,
+    /// Spreads artists and albums out and applies the shuffle weights.
+    Smart,
+    /// Shuffles whole albums, keeping each one in track order.
+    Albums,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub enum EqPreset {
     #[default]
@@ -279,6 +297,28 @@ pub enum TitlebarMode {
     Off,
 }
 
+/// Places lyrics are looked up, tried in the user's order.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LyricsSource {
+    /// `.lrc` or `.txt` file next to the track.
+    Sidecar,
+    /// Time-coded lyrics in the file's tags (SYLT, or LRC text in a lyrics tag).
+    EmbeddedSynced,
+    /// Unsynchronized lyrics in the file's tags (USLT, Vorbis `LYRICS`).
+    EmbeddedPlain,
+    /// lrclib.net.
+    Online,
+}
+
+impl LyricsSource {
+    pub const ALL: [LyricsSource; 4] = [
+        LyricsSource::Sidecar,
+        LyricsSource::EmbeddedSynced,
+        LyricsSource::EmbeddedPlain,
+        LyricsSource::Online,
+    ];
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     #[serde(default)]
@@ -299,8 +339,21 @@ pub struct AppConfig {
     pub sort_order: SortOrder,
     #[serde(default = "default_artist_view_order")]
     pub artist_view_order: ArtistViewOrder,
-    #[serde(default)]
-    pub listen_counts: HashMap<String, u64>,
+    /// Play counts from before the listening history had its own file. Read once
+    /// to move them over and never written back.
+    #[serde(
+        default,
+        rename = "listen_counts",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub legacy_listen_counts: HashMap<String, u64>,
+    /// Last play times that moved along with `legacy_listen_counts`.
+    #[serde(
+        default,
+        rename = "last_played",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub legacy_last_played: HashMap<String, u64>,
     #[serde(default)]
     pub musicbrainz_token: String,
     #[serde(default)]
@@ -320,6 +373,31 @@ pub struct AppConfig {
     #[serde(default)]
     pub back_behavior: BackBehavior,
     #[serde(default)]
+    pub shuffle_mode: ShuffleMode,
+    /// Smart shuffle plays highly rated tracks sooner.
+    #[serde(default)]
+    pub shuffle_by_rating: bool,
+    /// Smart shuffle plays rarely heard tracks sooner.
+    #[serde(default)]
+    pub shuffle_by_plays: bool,
+    /// Smart shuffle saves tracks played this many hours ago or less for last.
+    #[serde(default)]
+    pub shuffle_avoid_recent_hours: u32,
+    /// Keeps playing related tracks when the queue runs out with looping off.
+    #[serde(default)]
+    pub autoplay: bool,
+    /// Tracks at least this many minutes long remember where they were left
+    /// off. Zero turns bookmarks off.
+    #[serde(default = "default_bookmark_min_minutes")]
+    pub bookmark_min_minutes: u32,
+    /// Space the offline downloads may take up, in megabytes. Pinned albums and
+    /// playlists can go over it; other downloads are evicted to make room.
+    #[serde(default = "default_offline_cache_limit_mb")]
+    pub offline_cache_limit_mb: u64,
+    /// Download a 320 kbps MP3 transcode instead of the original file.
+    #[serde(default)]
+    pub offline_transcode: bool,
+    #[serde(default)]
     pub equalizer: EqualizerSettings,
     #[serde(default)]
     pub ytdlp_output_dir: String,
@@ -329,6 +407,24 @@ pub struct AppConfig {
     pub ytdlp_history: Vec<YtdlpHistoryEntry>,
     #[serde(default)]
     pub titlebar_mode: TitlebarMode,
+    #[serde(default)]
+    pub album_grouping: reader::AlbumGrouping,
+    #[serde(default)]
+    pub cover_options: reader::CoverOptions,
+    #[serde(default)]
+    pub scan_rules: reader::ScanRules,
+    #[serde(default = "default_lyrics_sources")]
+    pub lyrics_sources: Vec<LyricsSource>,
+    #[serde(default = "default_lyrics_online")]
+    pub lyrics_online: bool,
+    #[serde(default)]
+    pub playlist_collage: reader::PlaylistCollage,
+    /// Sends generated collages to Jellyfin as the playlist image.
+    #[serde(default)]
+    pub upload_playlist_collages: bool,
+    /// Also stores ratings of local files in their tags (POPM or RATING).
+    #[serde(default)]
+    pub write_ratings_to_tags: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -398,6 +494,22 @@ pub fn default_sidebar_order() -> Vec<String> {
     ]
 }
 
+fn default_lyrics_sources() -> Vec<LyricsSource> {
+    LyricsSource::ALL.to_vec()
+}
+
+fn default_lyrics_online() -> bool {
+    true
+}
+
+fn default_bookmark_min_minutes() -> u32 {
+    20
+}
+
+fn default_offline_cache_limit_mb() -> u64 {
+    4096
+}
+
 fn default_volume() -> f32 {
     1.0
 }
@@ -437,7 +549,8 @@ impl Default for AppConfig {
             discord_presence: Some(true),
             sort_order: default_sort_order(),
             artist_view_order: default_artist_view_order(),
-            listen_counts: HashMap::new(),
+            legacy_listen_counts: HashMap::new(),
+            legacy_last_played: HashMap::new(),
             musicbrainz_token: String::new(),
             lastfm_token: String::new(),
             language: default_language(),
@@ -447,11 +560,27 @@ impl Default for AppConfig {
             volume: default_volume(),
             custom_themes: HashMap::new(),
             back_behavior: BackBehavior::RewindThenPrev,
+            shuffle_mode: ShuffleMode::default(),
+            shuffle_by_rating: false,
+            shuffle_by_plays: false,
+            shuffle_avoid_recent_hours: 0,
+            autoplay: false,
+            bookmark_min_minutes: default_bookmark_min_minutes(),
+            offline_cache_limit_mb: default_offline_cache_limit_mb(),
+            offline_transcode: false,
             equalizer: EqualizerSettings::default(),
             ytdlp_output_dir: String::new(),
             ytdlp_options: YtdlpOptions::default(),
             ytdlp_history: Vec::new(),
             titlebar_mode: TitlebarMode::Custom,
+            album_grouping: reader::AlbumGrouping::default(),
+            cover_options: reader::CoverOptions::default(),
+            scan_rules: reader::ScanRules::default(),
+            lyrics_sources: default_lyrics_sources(),
+            lyrics_online: default_lyrics_online(),
+            playlist_collage: reader::PlaylistCollage::default(),
+            upload_playlist_collages: false,
+            write_ratings_to_tags: false,
         }
     }
 }
@@ -481,6 +610,27 @@ impl AppConfig {
         self.active_service() == Some(MusicService::Jellyfin)
     }
 
+    /// Every lyrics source in the user's order, with anything missing from an
+    /// older config appended.
+    pub fn lyrics_source_order(&self) -> Vec<LyricsSource> {
+        let mut order: Vec<LyricsSource> = Vec::new();
+        for source in self.lyrics_sources.iter().chain(LyricsSource::ALL.iter()) {
+            if !order.contains(source) {
+                order.push(*source);
+            }
+        }
+        order
+    }
+
+    /// Sources to actually try, skipping online lookup when it is turned off.
+    pub fn lyrics_lookup_order(&self) -> Vec<LyricsSource> {
+        let mut order = self.lyrics_source_order();
+        if !self.lyrics_online {
+            order.retain(|s| *s != LyricsSource::Online);
+        }
+        order
+    }
+
     pub fn load(path: &Path) -> Self {
         if !path.exists() {
             return Self::default();
@@ -514,7 +664,7 @@ impl AppConfig {
                 return Err(std::io::Error::new(std::io::ErrorKind::Other, e));
             }
         };
-        if let Err(e) = fs::write(path, data) {
+        if let Err(e) = utils::artwork::write_atomically(path, data.as_bytes()) {
             eprintln!("Failed to write config to {:?}: {}", path, e);
             return Err(e);
         }
@@ -0,0 +1,151 @@
+use crate::use_player_controller::PlayerController;
+use config::{AppConfig, MusicService};
+use dioxus::{logger::tracing, prelude::*};
+use reader::bookmarks::Bookmark;
+use reader::models::{rating_key, server_item_id};
+use reader::{BookmarkStore, Track};
+use server::subsonic::SubsonicClient;
+use std::time::Duration;
+
+/// While playing, the bookmark follows along in steps of this many seconds.
+const BOOKMARK_STEP_SECS: u64 = 30;
+
+pub(crate) fn unix_now() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// A client for the connected server when it keeps bookmarks, which only
+/// Subsonic-style servers do.
+fn bookmark_client(config: &AppConfig) -> Option<SubsonicClient> {
+    let server = config.server.as_ref()?;
+    if !matches!(
+        server.service,
+        MusicService::Subsonic | MusicService::Custom
+    ) {
+        return None;
+    }
+    let (Some(password), Some(username)) = (&server.access_token, &server.user_id) else {
+        return None;
+    };
+    Some(SubsonicClient::new(&server.url, username, password))
+}
+
+/// Pulls the server's bookmarks into `bookmarks`, keeping whichever side
+/// changed last.
+pub async fn pull_server_bookmarks(
+    config: Signal<AppConfig>,
+    mut bookmarks: Signal<BookmarkStore>,
+) -> Result<(), String> {
+    let Some(client) = bookmark_client(&config.peek()) else {
+        return Ok(());
+    };
+    let fetched = client.get_bookmarks().await?;
+    bookmarks.write().merge(fetched.into_iter().map(|b| {
+        let bookmark = Bookmark {
+            position_secs: b.position / 1000,
+            updated_at: b.changed_at().unwrap_or(0),
+        };
+        (b.entry.id, bookmark)
+    }));
+    Ok(())
+}
+
+impl PlayerController {
+    /// Whether `track` is long enough to get a bookmark.
+    fn keeps_bookmark(&self, track: &Track) -> bool {
+        let minutes = u64::from(self.config.peek().bookmark_min_minutes);
+        minutes > 0 && track.duration >= minutes * 60
+    }
+
+    /// Where `track` was left off, if it is long enough to remember.
+    pub(crate) fn bookmark_position(&self, track: &Track) -> Option<u64> {
+        if !self.keeps_bookmark(track) {
+            return None;
+        }
+        self.bookmarks.peek().position(&rating_key(&track.path))
+    }
+
+    /// Remembers how far into `track` playback got, or forgets it once the
+    /// track played to the end.
+    pub(crate) fn save_bookmark(&mut self, track: &Track, position_secs: u64, completed: bool) {
+        if !self.keeps_bookmark(track) {
+            return;
+        }
+        let key = rating_key(&track.path);
+        let changed = if completed {
+            self.bookmarks.write().clear(&key)
+        } else {
+            self.bookmarks
+                .write()
+                .set(&key, position_secs, track.duration, unix_now())
+        };
+        if changed {
+            self.push_bookmark(track);
+        }
+    }
+
+    /// Moves the bookmark of the track being played along with playback.
+    /// Called on every progress tick; only writes once playback has moved a
+    /// step past the saved position, so a tick before a resume seek lands
+    /// can't wind it back.
+    pub fn follow_bookmark(&mut self, position_secs: u64) {
+        let Some(track) = self.current_track(*self.current_queue_index.peek()) else {
+            return;
+        };
+        if !self.keeps_bookmark(&track) {
+            return;
+        }
+        let saved = self
+            .bookmarks
+            .peek()
+            .position(&rating_key(&track.path))
+            .unwrap_or(0);
+        if position_secs >= saved + BOOKMARK_STEP_SECS {
+            self.save_bookmark(&track, position_secs, false);
+        }
+    }
+
+    /// Forgets where `track` was left off. If it is the one playing it jumps
+    /// back to the start and returns `true`; otherwise the caller plays it.
+    pub fn restart_from_beginning(&mut self, track: &Track) -> bool {
+        if self.bookmarks.write().clear(&rating_key(&track.path)) {
+            self.push_bookmark(track);
+        }
+        let is_current = self
+            .current_track(*self.current_queue_index.peek())
+            .is_some_and(|current| current.path == track.path);
+        if is_current {
+            self.player.write().seek(Duration::ZERO);
+            self.current_song_progress.set(0);
+            if !*self.is_playing.peek() {
+                self.resume();
+            }
+        }
+        is_current
+    }
+
+    /// Sends the bookmark of a server track to the server, or deletes it there
+    /// when it was cleared here.
+    fn push_bookmark(&self, track: &Track) {
+        let path = track.path.to_string_lossy();
+        let Some(item_id) = server_item_id(&path).map(str::to_string) else {
+            return;
+        };
+        let Some(client) = bookmark_client(&self.config.peek()) else {
+            return;
+        };
+        let position = self.bookmarks.peek().position(&item_id);
+        spawn(async move {
+            let result = match position {
+                Some(secs) => client.create_bookmark(&item_id, secs * 1000).await,
+                None => client.delete_bookmark(&item_id).await,
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to sync bookmark for {item_id}: {e}");
+            }
+        });
+    }
+}
@@ -1,9 +1,22 @@
+pub mod bookmarks;
+pub mod offline;
+pub mod radio;
+pub mod saved_queues;
 pub mod use_library_items;
+pub mod use_lyrics;
 pub mod use_player_controller;
 pub mod use_player_task;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod use_playlist_collages;
 pub mod use_search_data;
 
+pub use bookmarks::*;
+pub use offline::*;
+pub use radio::*;
 pub use use_library_items::*;
+pub use use_lyrics::*;
 pub use use_player_controller::*;
 pub use use_player_task::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use use_playlist_collages::*;
 pub use use_search_data::*;
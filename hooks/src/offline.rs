@@ -0,0 +1,343 @@
+use crate::bookmarks::unix_now;
+use crate::use_player_controller::PlayerController;
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+#[cfg(not(target_arch = "wasm32"))]
+use config::{AppConfig, MusicService};
+#[cfg(not(target_arch = "wasm32"))]
+use dioxus::logger::tracing;
+#[cfg(not(target_arch = "wasm32"))]
+use reader::OfflineStore;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+/// Bitrate of the MP3 transcode downloaded when `offline_transcode` is on.
+#[cfg(not(target_arch = "wasm32"))]
+const TRANSCODE_KBPS: u32 = 320;
+
+/// How far a download gets between updates of its progress.
+#[cfg(not(target_arch = "wasm32"))]
+const PROGRESS_STEP_BYTES: u64 = 512 * 1024;
+
+/// The download the offline queue is working on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DownloadProgress {
+    pub track: String,
+    pub bytes: u64,
+    pub total: Option<u64>,
+    /// The track doesn't fit in the space left for downloads, even without the
+    /// unpinned ones; the queue waits until the limit goes up or something is
+    /// unpinned.
+    pub no_room: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+enum DownloadError {
+    /// The server or network had a problem; try again later.
+    Retry(String),
+    /// The server won't give us this track; drop it from the queue.
+    Skip(String),
+    /// The track is this many bytes, more than the space left for downloads.
+    NoRoom(u64),
+}
+
+/// Where to download `track` from on the connected server.
+#[cfg(not(target_arch = "wasm32"))]
+fn download_url(config: &AppConfig, track: &str) -> Option<String> {
+    let server = config.server.as_ref()?;
+    let token = server.access_token.as_ref()?;
+    match server.service {
+        MusicService::Jellyfin => Some(if config.offline_transcode {
+            format!(
+                "{}/Audio/{}/stream.mp3?audioBitRate={}&api_key={}",
+                server.url,
+                track,
+                TRANSCODE_KBPS * 1000,
+                token
+            )
+        } else {
+            format!(
+                "{}/Audio/{}/stream?static=true&api_key={}",
+                server.url, track, token
+            )
+        }),
+        MusicService::Subsonic | MusicService::Custom => {
+            let username = server.user_id.as_ref()?;
+            let remote = ::server::subsonic::SubsonicClient::new(&server.url, username, token);
+            let url = if config.offline_transcode {
+                remote.transcoded_stream_url(track, "mp3", TRANSCODE_KBPS)
+            } else {
+                remote.download_url(track)
+            };
+            url.ok()
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn extension(content_type: &str) -> &'static str {
+    match content_type.split(';').next().unwrap_or_default().trim() {
+        "audio/flac" | "audio/x-flac" => "flac",
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/ogg" | "audio/opus" => "ogg",
+        "audio/mp4" | "audio/aac" | "audio/x-m4a" => "m4a",
+        "audio/wav" | "audio/x-wav" => "wav",
+        _ => "audio",
+    }
+}
+
+/// Item IDs are the server's business; keep them out of the way of the file
+/// system.
+#[cfg(not(target_arch = "wasm32"))]
+fn file_stem(track: &str) -> String {
+    track
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Evicts unpinned downloads until `size` more bytes fit under `limit`, or
+/// returns false without touching anything when they can't.
+#[cfg(not(target_arch = "wasm32"))]
+fn make_room(mut store: Signal<OfflineStore>, limit: u64, size: u64) -> bool {
+    let (room, full) = {
+        let store = store.peek();
+        (
+            store.has_room(limit, size),
+            store.used_bytes() + size > limit,
+        )
+    };
+    if room && full {
+        let evicted = store.write().evict(limit, size);
+        delete_offline_files(evicted);
+    }
+    room
+}
+
+/// Downloads `track` into `dir`, carrying on from a `.part` file left by an
+/// interrupted download when the server supports ranges. When the server says
+/// how big the track is, room is made before anything is written.
+#[cfg(not(target_arch = "wasm32"))]
+async fn download(
+    http: &reqwest::Client,
+    url: &str,
+    dir: &Path,
+    track: &str,
+    store: Signal<OfflineStore>,
+    limit: u64,
+    mut progress: Signal<Option<DownloadProgress>>,
+) -> Result<(PathBuf, u64), DownloadError> {
+    use tokio::io::AsyncWriteExt;
+
+    let retry = |e: std::io::Error| DownloadError::Retry(e.to_string());
+    tokio::fs::create_dir_all(dir).await.map_err(retry)?;
+    let stem = file_stem(track);
+    let part = dir.join(format!("{stem}.part"));
+    let mut have = tokio::fs::metadata(&part)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    let mut response = loop {
+        let mut request = http.get(url);
+        if have > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={have}-"));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| DownloadError::Retry(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && have > 0 {
+            // The partial file doesn't match what the server has; start over.
+            let _ = tokio::fs::remove_file(&part).await;
+            have = 0;
+            continue;
+        }
+        break response;
+    };
+
+    let status = response.status();
+    // an expired login is worth another try once the user signs in again
+    if status.is_client_error() && status != reqwest::StatusCode::UNAUTHORIZED {
+        return Err(DownloadError::Skip(format!("HTTP {status}")));
+    }
+    if !status.is_success() {
+        return Err(DownloadError::Retry(format!("HTTP {status}")));
+    }
+
+    let resumed = status == reqwest::StatusCode::PARTIAL_CONTENT;
+    let ext = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(extension)
+        .unwrap_or("audio");
+    let mut bytes = if resumed { have } else { 0 };
+    let total = response.content_length().map(|len| len + bytes);
+    if let Some(total) = total
+        && !make_room(store, limit, total)
+    {
+        let _ = tokio::fs::remove_file(&part).await;
+        return Err(DownloadError::NoRoom(total));
+    }
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(&part)
+        .await
+        .map_err(retry)?;
+    let mut reported = bytes;
+    progress.set(Some(DownloadProgress {
+        track: track.to_string(),
+        bytes,
+        total,
+        no_room: false,
+    }));
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| DownloadError::Retry(e.to_string()))?
+    {
+        file.write_all(&chunk).await.map_err(retry)?;
+        bytes += chunk.len() as u64;
+        if bytes >= reported + PROGRESS_STEP_BYTES {
+            reported = bytes;
+            progress.set(Some(DownloadProgress {
+                track: track.to_string(),
+                bytes,
+                total,
+                no_room: false,
+            }));
+        }
+    }
+    file.flush().await.map_err(retry)?;
+    drop(file);
+
+    let path = dir.join(format!("{stem}.{ext}"));
+    tokio::fs::rename(&part, &path).await.map_err(retry)?;
+    Ok((path, bytes))
+}
+
+/// Deletes downloads dropped from the offline store.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn delete_offline_files(paths: Vec<PathBuf>) {
+    if paths.is_empty() {
+        return;
+    }
+    spawn(async move {
+        for path in paths {
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                tracing::warn!("Failed to delete offline copy {}: {e}", path.display());
+            }
+        }
+    });
+}
+
+/// Works through the offline download queue one track at a time, making room
+/// in `cache_dir` by evicting the least recently used unpinned downloads. A
+/// track that can't fit holds up the queue and shows in the progress.
+/// The queue lives in the store, so whatever an interruption leaves behind
+/// carries on after a restart or the next change to the downloads.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn use_offline_downloads(
+    mut store: Signal<OfflineStore>,
+    config: Signal<AppConfig>,
+    cache_dir: PathBuf,
+) -> Signal<Option<DownloadProgress>> {
+    let mut progress = use_signal(|| None::<DownloadProgress>);
+    let mut running = use_signal(|| false);
+
+    use_effect(move || {
+        let has_work = !store.read().queue.is_empty();
+        let connected = config.read().server.is_some();
+        if !has_work || !connected || *running.peek() {
+            return;
+        }
+        running.set(true);
+        let dir = cache_dir.clone();
+        spawn(async move {
+            let http = reqwest::Client::new();
+            let mut blocked = None;
+            loop {
+                let Some(track) = store.peek().queue.first().cloned() else {
+                    break;
+                };
+                let Some(url) = download_url(&config.peek(), &track) else {
+                    break;
+                };
+                let limit = config.peek().offline_cache_limit_mb * 1024 * 1024;
+                // the size may have been unknown up front, so check it again
+                let result = match download(&http, &url, &dir, &track, store, limit, progress).await
+                {
+                    Ok((path, size)) if !make_room(store, limit, size) => {
+                        let _ = tokio::fs::remove_file(&path).await;
+                        Err(DownloadError::NoRoom(size))
+                    }
+                    result => result,
+                };
+                match result {
+                    Ok((path, size)) => {
+                        store.write().finish(&track, path, size, unix_now());
+                    }
+                    Err(DownloadError::Skip(e)) => {
+                        tracing::warn!("Dropping offline download of {track}: {e}");
+                        store.write().queue.retain(|t| t != &track);
+                    }
+                    Err(DownloadError::Retry(e)) => {
+                        tracing::warn!("Offline download of {track} interrupted: {e}");
+                        break;
+                    }
+                    Err(DownloadError::NoRoom(size)) => {
+                        tracing::warn!(
+                            "No room for the offline download of {track} ({size} bytes)"
+                        );
+                        blocked = Some(DownloadProgress {
+                            track,
+                            bytes: 0,
+                            total: Some(size),
+                            no_room: true,
+                        });
+                        break;
+                    }
+                }
+            }
+            progress.set(blocked);
+            running.set(false);
+        });
+    });
+
+    progress
+}
+
+impl PlayerController {
+    /// The downloaded copy of a server track, if there is one on disk. A copy
+    /// that has gone missing is forgotten and queued again when it is still
+    /// wanted offline.
+    pub(crate) fn offline_copy(&mut self, item_id: &str) -> Option<PathBuf> {
+        let path = self.offline.peek().cached_path(item_id)?.to_path_buf();
+        let mut store = self.offline.write();
+        if path.exists() {
+            store.touch(item_id, unix_now());
+            return Some(path);
+        }
+        store.forget(item_id);
+        let wanted = store
+            .collections
+            .iter()
+            .any(|c| c.tracks.iter().any(|t| t == item_id));
+        if wanted && !store.queue.iter().any(|t| t == item_id) {
+            store.queue.push(item_id.to_string());
+        }
+        None
+    }
+}
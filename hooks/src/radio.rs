@@ -0,0 +1,217 @@
+use crate::use_player_controller::PlayerController;
+use config::MusicService;
+use dioxus::{logger::tracing, prelude::*};
+use reader::Track;
+use reader::models::server_item_id;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// How many tracks a radio or an autoplay top-up adds at a time.
+const RADIO_BATCH: usize = 25;
+/// Autoplay looks at this many of the last played tracks for what comes next.
+const AUTOPLAY_SEEDS: usize = 5;
+
+/// What a radio is started from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RadioSeed {
+    Track(Box<Track>),
+    /// Album ID, local or server.
+    Album(String),
+    /// Artist name.
+    Artist(String),
+}
+
+impl PlayerController {
+    /// Replaces the queue with `seed` followed by related tracks and starts playing.
+    pub fn start_radio(&mut self, seed: RadioSeed) {
+        let seeds = self.radio_seeds(&seed);
+        let Some(first) = seeds.first().cloned() else {
+            return;
+        };
+
+        self.history.set(Vec::new());
+        self.shuffle_order.set(Vec::new());
+        self.queue.set(vec![first]);
+        self.play_track_no_history(0);
+
+        let mut ctrl = *self;
+        let current_gen = *self.play_generation.peek();
+        spawn(async move {
+            let exclude = HashSet::from([seeds[0].path.clone()]);
+            let related = ctrl.related_tracks(seeds, exclude).await;
+            // something else started meanwhile; don't mix the radio into it
+            if *ctrl.play_generation.peek() != current_gen {
+                return;
+            }
+            ctrl.extend_queue(related);
+        });
+    }
+
+    /// Called when the queue runs out with looping off. Appends tracks related
+    /// to what was just played and carries on, or stops if nothing turns up.
+    pub(crate) fn autoplay(&mut self) {
+        let seeds: Vec<Track> = {
+            let queue = self.queue.peek();
+            let history = self.history.peek();
+            std::iter::once(*self.current_queue_index.peek())
+                .chain(history.iter().rev().copied())
+                .filter_map(|i| queue.get(i).cloned())
+                .take(AUTOPLAY_SEEDS)
+                .collect()
+        };
+        if seeds.is_empty() {
+            self.is_playing.set(false);
+            return;
+        }
+
+        let mut ctrl = *self;
+        let current_gen = *self.play_generation.peek();
+        spawn(async move {
+            let exclude = ctrl.queue.peek().iter().map(|t| t.path.clone()).collect();
+            let related = ctrl.related_tracks(seeds, exclude).await;
+            // the user played something else while we were looking
+            if *ctrl.play_generation.peek() != current_gen {
+                return;
+            }
+            if related.is_empty() {
+                ctrl.is_playing.set(false);
+                return;
+            }
+            ctrl.extend_queue(related);
+            ctrl.play_next();
+        });
+    }
+
+    /// Appends to the context, keeping the new tracks in order when shuffling.
+    fn extend_queue(&mut self, tracks: Vec<Track>) {
+        let start = self.queue.peek().len();
+        let added = tracks.len();
+        self.queue.with_mut(|queue| queue.extend(tracks));
+        if *self.shuffle.peek() {
+            // the order is played from the back, so new tracks go in front
+            self.shuffle_order.with_mut(|order| {
+                let mut new: Vec<usize> = (start..start + added).rev().collect();
+                new.append(order);
+                *order = new;
+            });
+        }
+    }
+
+    fn radio_seeds(&self, seed: &RadioSeed) -> Vec<Track> {
+        let library = self.library.peek();
+        let all = || library.tracks.iter().chain(library.jellyfin_tracks.iter());
+        match seed {
+            RadioSeed::Track(track) => vec![(**track).clone()],
+            RadioSeed::Album(album_id) => {
+                let mut tracks: Vec<Track> =
+                    all().filter(|t| &t.album_id == album_id).cloned().collect();
+                tracks.sort_by_key(|t| (t.disc_number.unwrap_or(1), t.track_number.unwrap_or(0)));
+                tracks
+            }
+            RadioSeed::Artist(name) => {
+                use rand::seq::SliceRandom;
+                let mut tracks: Vec<Track> = all()
+                    .filter(|t| {
+                        t.artist.eq_ignore_ascii_case(name)
+                            || t.artists.iter().any(|a| a.eq_ignore_ascii_case(name))
+                    })
+                    .cloned()
+                    .collect();
+                tracks.shuffle(&mut rand::thread_rng());
+                tracks
+            }
+        }
+    }
+
+    /// Server picks for server tracks, falling back to the local similarity
+    /// score when the server has nothing or isn't reachable.
+    async fn related_tracks(
+        &self,
+        seeds: Vec<Track>,
+        exclude: HashSet<std::path::PathBuf>,
+    ) -> Vec<Track> {
+        let seed_key = seeds[0].path.to_string_lossy().into_owned();
+        let is_server = server_item_id(&seed_key).is_some();
+
+        if let Some(item_id) = server_item_id(&seed_key) {
+            match self.server_related(item_id).await {
+                Ok(ids) => {
+                    let library = self.library.peek();
+                    let picked: Vec<Track> = ids
+                        .iter()
+                        .filter_map(|id| {
+                            library.jellyfin_tracks.iter().find(|t| {
+                                server_item_id(&t.path.to_string_lossy()) == Some(id.as_str())
+                            })
+                        })
+                        .filter(|t| !exclude.contains(&t.path) && t.path != seeds[0].path)
+                        .take(RADIO_BATCH)
+                        .cloned()
+                        .collect();
+                    if !picked.is_empty() {
+                        return picked;
+                    }
+                }
+                Err(e) => tracing::warn!("Server radio failed, using local picks: {e}"),
+            }
+        }
+
+        let library = self.library.peek();
+        let candidates = if is_server {
+            &library.jellyfin_tracks
+        } else {
+            &library.tracks
+        };
+        let store = self.playlist_store.peek();
+        let exclude: HashSet<&Path> = exclude.iter().map(|p| p.as_path()).collect();
+        reader::radio::similar_tracks(&seeds, candidates, &store.playlists, &exclude, RADIO_BATCH)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Item IDs of songs related to `item_id`: Jellyfin's instant mix, or on
+    /// Subsonic the similar songs of the track's artist.
+    async fn server_related(&self, item_id: &str) -> Result<Vec<String>, String> {
+        let (service, url, token, user_id, device_id) = {
+            let conf = self.config.peek();
+            let Some(server) = &conf.server else {
+                return Ok(Vec::new());
+            };
+            let (Some(token), Some(user_id)) = (&server.access_token, &server.user_id) else {
+                return Ok(Vec::new());
+            };
+            (
+                server.service,
+                server.url.clone(),
+                token.clone(),
+                user_id.clone(),
+                conf.device_id.clone(),
+            )
+        };
+
+        match service {
+            MusicService::Jellyfin => {
+                let client = ::server::jellyfin::JellyfinClient::new(
+                    &url,
+                    Some(&token),
+                    &device_id,
+                    Some(&user_id),
+                );
+                let items = client.instant_mix(item_id, RADIO_BATCH * 2).await?;
+                Ok(items.into_iter().map(|item| item.id).collect())
+            }
+            MusicService::Subsonic | MusicService::Custom => {
+                let client = ::server::subsonic::SubsonicClient::new(&url, &user_id, &token);
+                let Some(artist_id) = client.get_song(item_id).await?.and_then(|s| s.artist_id)
+                else {
+                    return Ok(Vec::new());
+                };
+                let songs = client
+                    .get_similar_songs2(&artist_id, RADIO_BATCH * 2)
+                    .await?;
+                Ok(songs.into_iter().map(|song| song.id).collect())
+            }
+        }
+    }
+}
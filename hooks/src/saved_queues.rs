@@ -0,0 +1,68 @@
+use crate::use_player_controller::{LoopMode, PlayerController};
+use dioxus::prelude::*;
+use reader::saved_queues::{QueueLoop, SavedQueue};
+
+impl From<LoopMode> for QueueLoop {
+    fn from(mode: LoopMode) -> Self {
+        match mode {
+            LoopMode::None => QueueLoop::Off,
+            LoopMode::Queue => QueueLoop::Queue,
+            LoopMode::Track => QueueLoop::Track,
+        }
+    }
+}
+
+impl From<QueueLoop> for LoopMode {
+    fn from(mode: QueueLoop) -> Self {
+        match mode {
+            QueueLoop::Off => LoopMode::None,
+            QueueLoop::Queue => LoopMode::Queue,
+            QueueLoop::Track => LoopMode::Track,
+        }
+    }
+}
+
+impl PlayerController {
+    /// The queue as it stands, with position, shuffle and repeat, under `name`.
+    pub fn save_queue(&self, name: &str) -> SavedQueue {
+        SavedQueue {
+            name: name.trim().to_string(),
+            saved_at: web_time::SystemTime::now()
+                .duration_since(web_time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            queue: self.queue.peek().clone(),
+            up_next: self.up_next.peek().clone(),
+            current_queue_index: *self.current_queue_index.peek(),
+            from_up_next: self.playing_from_up_next(),
+            progress_secs: *self.current_song_progress.peek(),
+            shuffle: *self.shuffle.peek(),
+            loop_mode: (*self.loop_mode.peek()).into(),
+        }
+    }
+
+    /// Swaps in a saved queue and carries on from where it was saved.
+    pub fn restore_saved_queue(&mut self, saved: &SavedQueue) {
+        self.finish_listen(false);
+        self.restore_queue_state(
+            saved.queue.clone(),
+            saved.up_next.clone(),
+            saved.current_queue_index,
+            saved.progress_secs,
+            saved.from_up_next,
+        );
+        self.shuffle.set(saved.shuffle);
+        self.loop_mode.set(saved.loop_mode.into());
+        self.shuffle_order.set(Vec::new());
+        if saved.shuffle {
+            self.rebuild_shuffle_order();
+        }
+
+        if self.queue.peek().is_empty() {
+            self.play_next();
+        } else {
+            let idx = *self.current_queue_index.peek();
+            self.play_track_no_history(idx);
+        }
+    }
+}
@@ -1,20 +1,122 @@
 use config::{AppConfig, SortOrder};
 use dioxus::prelude::*;
-use reader::Library;
 use reader::models::Track;
+use reader::{Library, TrackFilter};
+use std::cmp::Reverse;
 use std::collections::HashMap;
 
 pub struct LibraryItems {
     pub all_tracks: Memo<Vec<(Track, Option<String>)>>,
     pub artist_count: Memo<usize>,
     pub sort_order: Signal<SortOrder>,
+    pub filter_query: Signal<String>,
 }
 
+/// Sorts anything that wraps a track. Newest, fastest and highest-bitrate
+/// tracks come first; tracks missing the sorted field go last.
+pub fn sort_tracks<T>(items: &mut [T], order: &SortOrder, track: impl Fn(&T) -> &Track) {
+    let album_order = |t: &Track| {
+        (
+            t.album.to_lowercase(),
+            t.disc_number,
+            t.track_number,
+            t.title.to_lowercase(),
+        )
+    };
+    match order {
+        SortOrder::Title => items.sort_by_cached_key(|item| {
+            let a = track(item);
+            (
+                a.title.to_lowercase(),
+                a.artist.to_lowercase(),
+                a.album.to_lowercase(),
+                a.disc_number,
+                a.track_number,
+            )
+        }),
+        SortOrder::Artist => items.sort_by_cached_key(|item| {
+            let a = track(item);
+            (a.artist.to_lowercase(), album_order(a))
+        }),
+        SortOrder::Album => items.sort_by_cached_key(|item| album_order(track(item))),
+        SortOrder::Year => items.sort_by_cached_key(|item| {
+            let a = track(item);
+            (a.year().is_none(), Reverse(a.year()), album_order(a))
+        }),
+        SortOrder::DateAdded => items.sort_by_cached_key(|item| {
+            let a = track(item);
+            (
+                a.date_added.is_none(),
+                Reverse(a.date_added),
+                album_order(a),
+            )
+        }),
+        SortOrder::Composer => items.sort_by_cached_key(|item| {
+            let a = track(item);
+            (
+                a.composer.is_none(),
+                a.composer.as_deref().map(str::to_lowercase),
+                album_order(a),
+            )
+        }),
+        SortOrder::Genre => items.sort_by_cached_key(|item| {
+            let a = track(item);
+            (
+                a.genres.is_empty(),
+                a.genres.first().map(|g| g.to_lowercase()),
+                a.artist.to_lowercase(),
+                album_order(a),
+            )
+        }),
+        SortOrder::Bpm => items.sort_by_cached_key(|item| {
+            let a = track(item);
+            (a.bpm.is_none(), Reverse(a.bpm), album_order(a))
+        }),
+        SortOrder::Bitrate => items.sort_by_cached_key(|item| {
+            let a = track(item);
+            (Reverse(a.bitrate), Reverse(a.bit_depth), album_order(a))
+        }),
+        SortOrder::Rating => items.sort_by_cached_key(|item| {
+            let a = track(item);
+            (Reverse(a.rating.unwrap_or(0)), album_order(a))
+        }),
+    }
+}
+
+pub fn sort_order_label(order: &SortOrder) -> &'static str {
+    match order {
+        SortOrder::Title => "title",
+        SortOrder::Artist => "artist",
+        SortOrder::Album => "album",
+        SortOrder::Year => "year",
+        SortOrder::DateAdded => "date_added",
+        SortOrder::Composer => "composer",
+        SortOrder::Genre => "genre",
+        SortOrder::Bpm => "bpm",
+        SortOrder::Bitrate => "quality",
+        SortOrder::Rating => "rating",
+    }
+}
+
+pub const SORT_ORDERS: [SortOrder; 10] = [
+    SortOrder::Title,
+    SortOrder::Artist,
+    SortOrder::Album,
+    SortOrder::Year,
+    SortOrder::DateAdded,
+    SortOrder::Composer,
+    SortOrder::Genre,
+    SortOrder::Bpm,
+    SortOrder::Bitrate,
+    SortOrder::Rating,
+];
+
 pub fn use_library_items(library: Signal<Library>) -> LibraryItems {
     let config = use_context::<Signal<AppConfig>>();
 
     let initial_sort_order = config.read().sort_order.clone();
     let sort_order = use_signal(move || initial_sort_order);
+    let filter_query = use_signal(String::new);
 
     let artist_count = use_memo(move || {
         let lib = library.read();
@@ -39,48 +141,23 @@ pub fn use_library_items(library: Signal<Library>) -> LibraryItems {
                     &a.id,
                     a.cover_path
                         .as_ref()
-                        .and_then(|p| utils::format_artwork_url(Some(p))),
+                        .and_then(|p| utils::format_artwork_url_sized(Some(p), 96)),
                 )
             })
             .collect();
 
+        let filter = TrackFilter::parse(&filter_query.read());
         let mut tracks: Vec<(Track, Option<String>)> = lib
             .tracks
             .iter()
+            .filter(|track| filter.matches(track))
             .map(|track| {
                 let cover_url = album_covers.get(&track.album_id).cloned().flatten();
                 (track.clone(), cover_url)
             })
             .collect();
 
-        match *sort_order.read() {
-            SortOrder::Title => tracks.sort_by_cached_key(|(a, _)| {
-                (
-                    a.title.to_lowercase(),
-                    a.artist.to_lowercase(),
-                    a.album.to_lowercase(),
-                    a.disc_number,
-                    a.track_number,
-                )
-            }),
-            SortOrder::Artist => tracks.sort_by_cached_key(|(a, _)| {
-                (
-                    a.artist.to_lowercase(),
-                    a.album.to_lowercase(),
-                    a.disc_number,
-                    a.track_number,
-                    a.title.to_lowercase(),
-                )
-            }),
-            SortOrder::Album => tracks.sort_by_cached_key(|(a, _)| {
-                (
-                    a.album.to_lowercase(),
-                    a.disc_number,
-                    a.track_number,
-                    a.title.to_lowercase(),
-                )
-            }),
-        }
+        sort_tracks(&mut tracks, &sort_order.read(), |(track, _)| track);
 
         tracks
     });
@@ -89,5 +166,6 @@ pub fn use_library_items(library: Signal<Library>) -> LibraryItems {
         all_tracks,
         artist_count,
         sort_order,
+        filter_query,
     }
 }
@@ -0,0 +1,75 @@
+use crate::use_player_controller::PlayerController;
+use config::{AppConfig, LyricsSource};
+use dioxus::prelude::*;
+use utils::lyrics::Lyrics;
+
+/// Lyrics for the playing track, resolved through the sources in the user's
+/// configured order. Resolves to `None` while nothing is playing.
+pub fn use_lyrics(
+    title: Signal<String>,
+    artist: Signal<String>,
+    album: Signal<String>,
+    duration: Signal<u64>,
+) -> Resource<Option<Lyrics>> {
+    let ctrl = use_context::<PlayerController>();
+    let config = use_context::<Signal<AppConfig>>();
+
+    use_resource(move || {
+        let title = title.read().clone();
+        let artist = artist.read().clone();
+        let album = album.read().clone();
+        let duration = *duration.read();
+        let order = config.read().lyrics_lookup_order();
+        let index = *ctrl.current_queue_index.read();
+        let path = ctrl.queue.peek().get(index).map(|t| t.path.clone());
+
+        async move {
+            if title.is_empty() {
+                return None;
+            }
+
+            for source in order {
+                let found = match source {
+                    LyricsSource::Online => {
+                        utils::lyrics::fetch_lyrics(&artist, &title, &album, duration).await
+                    }
+                    local => match &path {
+                        Some(path) => read_local(local, path.clone()).await,
+                        None => None,
+                    },
+                };
+                if found.is_some() {
+                    return found;
+                }
+            }
+
+            Some(Lyrics::Plain(i18n::t("lyrics_not_found").to_string()))
+        }
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn read_local(source: LyricsSource, path: std::path::PathBuf) -> Option<Lyrics> {
+    // server tracks use "jellyfin:<id>" style paths, so there is nothing on disk to read
+    if !path.is_file() {
+        return None;
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let text = match source {
+            LyricsSource::Sidecar => reader::lyrics::read_sidecar(&path),
+            LyricsSource::EmbeddedSynced => reader::lyrics::read_embedded(&path).synced,
+            LyricsSource::EmbeddedPlain => reader::lyrics::read_embedded(&path).plain,
+            LyricsSource::Online => None,
+        }?;
+        utils::lyrics::parse_lyrics(&text)
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn read_local(_source: LyricsSource, _path: std::path::PathBuf) -> Option<Lyrics> {
+    None
+}
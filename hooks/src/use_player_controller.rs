@@ -1,11 +1,14 @@
 use config::AppConfig;
 use config::BackBehavior;
 use config::MusicService;
+use config::ShuffleMode;
 use dioxus::{logger::tracing, prelude::*};
 use player::player::{NowPlayingMeta, Player};
-use reader::{Library, Track};
+use reader::history::{Listen, ListenSource};
+use reader::{BookmarkStore, HistoryStore, Library, OfflineStore, PlaylistStore, Track};
 use scrobble;
 use utils;
+use std::path::PathBuf;
 use std::time::Duration;
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -35,7 +38,11 @@ pub struct PlayerController {
     pub is_loading: Signal<bool>,
     pub skip_in_progress: Signal<bool>,
     pub history: Signal<Vec<usize>>,
+    /// The context being played: an album, playlist or track list.
     pub queue: Signal<Vec<Track>>,
+    /// Tracks the user queued by hand. They play before the rest of the context
+    /// and survive switching to another one.
+    pub up_next: Signal<Vec<Track>>,
     pub shuffle: Signal<bool>,
     pub shuffle_order: Signal<Vec<usize>>,
     pub loop_mode: Signal<LoopMode>,
@@ -44,15 +51,38 @@ pub struct PlayerController {
     pub current_song_artist: Signal<String>,
     pub current_song_album: Signal<String>,
     pub current_song_khz: Signal<u32>,
-    pub current_song_bitrate: Signal<u8>,
+    pub current_song_bitrate: Signal<u32>,
     pub current_song_duration: Signal<u64>,
     pub current_song_progress: Signal<u64>,
     pub current_song_cover_url: Signal<String>,
     pub volume: Signal<f32>,
     pub library: Signal<Library>,
+    pub playlist_store: Signal<PlaylistStore>,
+    pub listen_history: Signal<HistoryStore>,
+    /// Where long tracks were left off.
+    pub bookmarks: Signal<BookmarkStore>,
+    /// Server tracks downloaded for offline play.
+    pub offline: Signal<OfflineStore>,
     pub config: Signal<AppConfig>,
     pub play_generation: Signal<usize>,
     pending_resume: Signal<Option<PendingResumeState>>,
+    listening: Signal<Option<ListenSession>>,
+    playing_up_next: Signal<Option<UpNextEntry>>,
+}
+
+/// An Up Next entry put into the context to play. It is taken back out once
+/// something else plays, so it isn't replayed with the rest of the context.
+#[derive(Clone, Debug, PartialEq)]
+struct UpNextEntry {
+    index: usize,
+    path: PathBuf,
+}
+
+/// The track being listened to, until it finishes or something else starts.
+#[derive(Clone, Debug, PartialEq)]
+struct ListenSession {
+    track: Track,
+    started_at: u64,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -66,7 +96,7 @@ impl PlayerController {
         track.path.to_string_lossy().to_string()
     }
 
-    fn current_track(&self, idx: usize) -> Option<Track> {
+    pub(crate) fn current_track(&self, idx: usize) -> Option<Track> {
         self.queue.peek().get(idx).cloned()
     }
 
@@ -202,6 +232,70 @@ impl PlayerController {
         }
     }
 
+    /// Starts timing a listen of `track`, recording whatever was playing before
+    /// as skipped. Picking a paused track back up keeps its listen going.
+    fn begin_listen(&mut self, track: &Track, resuming: bool) {
+        if resuming
+            && self
+                .listening
+                .peek()
+                .as_ref()
+                .is_some_and(|session| session.track.path == track.path)
+        {
+            return;
+        }
+        self.finish_listen(false);
+        let started_at = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.listening.set(Some(ListenSession {
+            track: track.clone(),
+            started_at,
+        }));
+    }
+
+    /// Every server track has a `jellyfin:` path whatever the server, so the
+    /// connected service says where it came from.
+    fn listen_source(&self, track: &Track) -> ListenSource {
+        if reader::models::server_item_id(&track.path.to_string_lossy()).is_none() {
+            return ListenSource::Local;
+        }
+        match self.config.peek().server.as_ref().map(|s| s.service) {
+            Some(MusicService::Subsonic) => ListenSource::Subsonic,
+            Some(MusicService::Custom) => ListenSource::Custom,
+            _ => ListenSource::Jellyfin,
+        }
+    }
+
+    /// Adds the current listen to the history. `completed` is set when the
+    /// track played to the end; otherwise it counts as a skip.
+    pub fn finish_listen(&mut self, completed: bool) {
+        let Some(ListenSession { track, started_at }) = self.listening.with_mut(Option::take) else {
+            return;
+        };
+        let listened_secs = if completed {
+            track.duration
+        } else {
+            (*self.current_song_progress.peek()).min(track.duration)
+        };
+        self.save_bookmark(&track, listened_secs, completed);
+        let device = self.config.peek().device_id.clone();
+        let source = self.listen_source(&track);
+        self.listen_history.write().record(Listen {
+            track: Self::track_key(&track),
+            source,
+            title: track.title,
+            artist: track.artist,
+            album: track.album,
+            started_at,
+            listened_secs,
+            duration_secs: track.duration,
+            completed,
+            device,
+        });
+    }
+
     pub fn play_track(&mut self, idx: usize) {
         let current_idx = *self.current_queue_index.peek();
         self.history.with_mut(|h| {
@@ -213,6 +307,14 @@ impl PlayerController {
     }
 
     pub fn play_track_no_history(&mut self, idx: usize) {
+        let mut idx = idx;
+        let current = *self.current_queue_index.peek();
+        if idx != current
+            && let Some(at) = self.drop_up_next_entry()
+            && idx > at
+        {
+            idx -= 1;
+        }
         self.play_generation.with_mut(|g| *g += 1);
         let current_gen = *self.play_generation.peek();
 
@@ -220,6 +322,8 @@ impl PlayerController {
             let path_str = track.path.to_string_lossy().to_string();
             let (restore_seek_secs, clear_pending_resume_on_success) =
                 self.pending_resume_seek(&track);
+            self.begin_listen(&track, restore_seek_secs.is_some());
+            let restore_seek_secs = restore_seek_secs.or_else(|| self.bookmark_position(&track));
             let scheme = path_str
                 .split(':')
                 .next()
@@ -230,6 +334,7 @@ impl PlayerController {
             if is_server_item {
                 let parts: Vec<&str> = path_str.split(':').collect();
                 let id = parts.get(1).unwrap_or(&"").to_string();
+                let offline_copy = self.offline_copy(&id);
 
                 if let Some((stream_url, cover_url)) = {
                     let conf = self.config.read();
@@ -285,7 +390,7 @@ impl PlayerController {
                     })
                 } {
 
-                    if stream_url.is_empty() {
+                    if stream_url.is_empty() && offline_copy.is_none() {
                         self.is_loading.set(false);
                         self.skip_in_progress.set(false);
                         return;
@@ -311,12 +416,23 @@ impl PlayerController {
 
                     #[cfg(not(target_arch = "wasm32"))]
                     spawn(async move {
-                        let stream = utils::stream_buffer::StreamBuffer::new(stream_url);
-                        let source_res = tokio::task::spawn_blocking(move || {
-                            let (source, hint) = decoder::from_stream(stream);
-                            Ok::<_, std::io::Error>((source, hint))
-                        })
-                        .await;
+                        let source_res = match offline_copy {
+                            Some(path) => {
+                                tokio::task::spawn_blocking(move || {
+                                    decoder::open_file(&path)
+                                        .map_err(|e| std::io::Error::other(e.to_string()))
+                                })
+                                .await
+                            }
+                            None => {
+                                let stream = utils::stream_buffer::StreamBuffer::new(stream_url);
+                                tokio::task::spawn_blocking(move || {
+                                    let (source, hint) = decoder::from_stream(stream);
+                                    Ok::<_, std::io::Error>((source, hint))
+                                })
+                                .await
+                            }
+                        };
 
                         if let Ok(Ok((source, hint))) = source_res {
                             if *play_generation.read() == current_gen {
@@ -712,7 +828,143 @@ impl PlayerController {
         }
     }
 
+    /// Where the Up Next entry being played sits in the context, unless the
+    /// context has been swapped out from under it since.
+    fn up_next_entry(&self) -> Option<usize> {
+        let entry = self.playing_up_next.peek();
+        let entry = entry.as_ref()?;
+        self.queue
+            .peek()
+            .get(entry.index)
+            .is_some_and(|t| t.path == entry.path)
+            .then_some(entry.index)
+    }
+
+    /// Whether the current track came from Up Next rather than the context.
+    pub fn playing_from_up_next(&self) -> bool {
+        self.up_next_entry() == Some(*self.current_queue_index.peek())
+    }
+
+    fn mark_up_next_entry(&mut self, index: usize) {
+        let entry = self.queue.peek().get(index).map(|t| UpNextEntry {
+            index,
+            path: t.path.clone(),
+        });
+        self.playing_up_next.set(entry);
+    }
+
+    /// Takes the Up Next entry that was playing back out of the context and
+    /// returns where it was.
+    fn drop_up_next_entry(&mut self) -> Option<usize> {
+        let at = self.up_next_entry();
+        self.playing_up_next.set(None);
+        let at = at?;
+        self.queue.with_mut(|queue| {
+            queue.remove(at);
+        });
+
+        let unshift = |idx: &mut usize| {
+            if *idx > at {
+                *idx -= 1;
+            }
+        };
+        self.history.with_mut(|history| {
+            history.retain(|idx| *idx != at);
+            history.iter_mut().for_each(unshift);
+        });
+        self.shuffle_order.with_mut(|order| {
+            order.retain(|idx| *idx != at);
+            order.iter_mut().for_each(unshift);
+        });
+        // back onto the context track it was played after
+        self.current_queue_index.with_mut(|current| {
+            if *current == at {
+                *current = at.saturating_sub(1);
+            } else {
+                unshift(current);
+            }
+        });
+        Some(at)
+    }
+
+    /// Moves Up Next entry `i` into the context right after the current track
+    /// and returns its new index there.
+    fn take_up_next(&mut self, i: usize) -> Option<usize> {
+        let track = self
+            .up_next
+            .with_mut(|up_next| (i < up_next.len()).then(|| up_next.remove(i)))?;
+        self.drop_up_next_entry();
+        let queue_len = self.queue.peek().len();
+        let at = if queue_len == 0 {
+            0
+        } else {
+            (*self.current_queue_index.peek() + 1).min(queue_len)
+        };
+        self.queue.with_mut(|queue| queue.insert(at, track));
+
+        let shift = |idx: &mut usize| {
+            if *idx >= at {
+                *idx += 1;
+            }
+        };
+        self.history
+            .with_mut(|history| history.iter_mut().for_each(shift));
+        self.shuffle_order
+            .with_mut(|order| order.iter_mut().for_each(shift));
+        Some(at)
+    }
+
+    /// Queues `track` to play after the current one, ahead of anything queued before.
+    pub fn enqueue_next(&mut self, track: Track) {
+        self.up_next.with_mut(|up_next| up_next.insert(0, track));
+    }
+
+    /// Queues `track` after everything else in Up Next.
+    pub fn enqueue(&mut self, track: Track) {
+        self.up_next.with_mut(|up_next| up_next.push(track));
+    }
+
+    pub fn remove_up_next(&mut self, i: usize) {
+        self.up_next.with_mut(|up_next| {
+            if i < up_next.len() {
+                up_next.remove(i);
+            }
+        });
+    }
+
+    pub fn move_up_next(&mut self, from: usize, to: usize) {
+        self.up_next.with_mut(|up_next| {
+            if from < up_next.len() && to < up_next.len() && from != to {
+                let track = up_next.remove(from);
+                up_next.insert(to, track);
+            }
+        });
+    }
+
+    pub fn clear_up_next(&mut self) {
+        self.up_next.set(Vec::new());
+    }
+
+    /// Plays Up Next entry `i` right away.
+    pub fn play_up_next(&mut self, i: usize) {
+        let had_context = !self.queue.peek().is_empty();
+        if let Some(idx) = self.take_up_next(i) {
+            if had_context {
+                self.play_track(idx);
+            } else {
+                self.play_track_no_history(idx);
+            }
+            self.mark_up_next_entry(idx);
+        }
+    }
+
     pub fn play_next(&mut self) {
+        let loop_mode = *self.loop_mode.peek();
+        if loop_mode != LoopMode::Track && !self.up_next.peek().is_empty() {
+            self.play_up_next(0);
+            return;
+        }
+
         let idx = *self.current_queue_index.peek();
         let queue_len = self.queue.peek().len();
 
@@ -720,7 +972,6 @@ impl PlayerController {
             return;
         }
 
-        let loop_mode = *self.loop_mode.peek();
         let shuffle = *self.shuffle.peek();
 
         match loop_mode {
@@ -737,6 +988,8 @@ impl PlayerController {
                                 self.rebuild_shuffle_order();
                                 let i = self.shuffle_order.with_mut(|order| order.pop()).unwrap_or(0);
                                 self.play_track(i);
+                            } else if self.config.peek().autoplay {
+                                self.autoplay();
                             } else {
                                 self.is_playing.set(false);
                             }
@@ -748,6 +1001,8 @@ impl PlayerController {
                     self.play_track(idx + 1);
                 } else if loop_mode == LoopMode::Queue {
                     self.play_track(0);
+                } else if self.config.peek().autoplay {
+                    self.autoplay();
                 } else {
                     self.is_playing.set(false);
                 }
@@ -774,24 +1029,68 @@ impl PlayerController {
             return;
         }
 
-        if let Some(prev_idx) = self.history.with_mut(|h| h.pop()) {
-            self.play_track_no_history(prev_idx);
-            return;
-        }
-
-        if idx > 0 {
-            self.play_track_no_history(idx - 1);
-        } else if *self.loop_mode.peek() == LoopMode::Queue {
-            self.play_track_no_history(queue_len - 1);
+        let prev_idx = match self.history.with_mut(|h| h.pop()) {
+            Some(prev_idx) => prev_idx,
+            None if idx > 0 => idx - 1,
+            None if *self.loop_mode.peek() == LoopMode::Queue => queue_len - 1,
+            None => return,
+        };
+
+        // step the shuffle back too, so next returns to the track being left
+        if *self.shuffle.peek() {
+            self.shuffle_order.with_mut(|order| {
+                order.retain(|i| *i != prev_idx);
+                if idx != prev_idx {
+                    order.push(idx);
+                }
+            });
         }
+        self.play_track_no_history(prev_idx);
     }
 
-    fn rebuild_shuffle_order(&mut self) {
+    pub fn rebuild_shuffle_order(&mut self) {
         use rand::seq::SliceRandom;
-        let queue_len = self.queue.peek().len();
+        let queue = self.queue.peek();
         let current = *self.current_queue_index.peek();
-        let mut order: Vec<usize> = (0..queue_len).filter(|&i| i != current).collect();
-        order.shuffle(&mut rand::thread_rng());
+        let conf = self.config.peek();
+        let order = match conf.shuffle_mode {
+            ShuffleMode::Random => {
+                let mut order: Vec<usize> = (0..queue.len()).filter(|&i| i != current).collect();
+                order.shuffle(&mut rand::thread_rng());
+                order
+            }
+            ShuffleMode::Smart => {
+                let history = self.listen_history.peek();
+                let ctx = reader::shuffle::ShuffleContext {
+                    listen_counts: &history.listen_counts,
+                    last_played: &history.last_played,
+                    by_rating: conf.shuffle_by_rating,
+                    by_plays: conf.shuffle_by_plays,
+                    avoid_recent_secs: u64::from(conf.shuffle_avoid_recent_hours) * 3600,
+                    now: web_time::SystemTime::now()
+                        .duration_since(web_time::UNIX_EPOCH)
+                        .map(|d| d.as_secs())
+                        .unwrap_or(0),
+                };
+                let mut order = reader::shuffle::smart_order(
+                    &queue,
+                    Some(current),
+                    &ctx,
+                    &mut rand::thread_rng(),
+                );
+                // played from the back
+                order.reverse();
+                order
+            }
+            ShuffleMode::Albums => {
+                let mut order =
+                    reader::shuffle::album_order(&queue, Some(current), &mut rand::thread_rng());
+                order.reverse();
+                order
+            }
+        };
+        drop(conf);
+        drop(queue);
         self.shuffle_order.set(order);
     }
 
@@ -810,6 +1109,11 @@ impl PlayerController {
     pub fn pause(&mut self) {
         self.player.write().pause();
         self.is_playing.set(false);
+        let idx = *self.current_queue_index.peek();
+        if let Some(track) = self.current_track(idx) {
+            let progress_secs = *self.current_song_progress.peek();
+            self.save_bookmark(&track, progress_secs, false);
+        }
     }
 
     pub fn resume(&mut self) {
@@ -855,13 +1159,26 @@ impl PlayerController {
                 *idx = Self::remap_queue_index(*idx, from, to);
             }
         });
+        self.shuffle_order.with_mut(|order| {
+            for idx in order.iter_mut() {
+                *idx = Self::remap_queue_index(*idx, from, to);
+            }
+        });
+        self.playing_up_next.with_mut(|entry| {
+            if let Some(entry) = entry {
+                entry.index = Self::remap_queue_index(entry.index, from, to);
+            }
+        });
     }
 
+    /// `from_up_next` says the current track was being played from Up Next.
     pub fn restore_queue_state(
         &mut self,
         queue: Vec<Track>,
+        up_next: Vec<Track>,
         current_queue_index: usize,
         progress_secs: u64,
+        from_up_next: bool,
     ) {
         self.player.write().stop();
         self.is_playing.set(false);
@@ -869,6 +1186,8 @@ impl PlayerController {
         self.skip_in_progress.set(false);
         self.history.set(Vec::new());
         self.queue.set(queue);
+        self.up_next.set(up_next);
+        self.playing_up_next.set(None);
 
         let queue_len = self.queue.peek().len();
         if queue_len == 0 {
@@ -884,6 +1203,9 @@ impl PlayerController {
 
         self.hydrate_current_track_metadata(idx, progress_secs);
         self.set_pending_resume_for_track(&track, progress_secs);
+        if from_up_next {
+            self.mark_up_next_entry(idx);
+        }
     }
 }
 
@@ -896,12 +1218,16 @@ pub fn use_player_controller(
     current_song_artist: Signal<String>,
     current_song_album: Signal<String>,
     current_song_khz: Signal<u32>,
-    current_song_bitrate: Signal<u8>,
+    current_song_bitrate: Signal<u32>,
     current_song_duration: Signal<u64>,
     current_song_progress: Signal<u64>,
     current_song_cover_url: Signal<String>,
     volume: Signal<f32>,
     library: Signal<Library>,
+    playlist_store: Signal<PlaylistStore>,
+    listen_history: Signal<HistoryStore>,
+    bookmarks: Signal<BookmarkStore>,
+    offline: Signal<OfflineStore>,
     config: Signal<AppConfig>,
 ) -> PlayerController {
     let play_generation = use_signal(|| 0);
@@ -910,8 +1236,11 @@ pub fn use_player_controller(
     let history = use_signal(|| Vec::new());
     let shuffle = use_signal(|| false);
     let shuffle_order = use_signal(|| Vec::<usize>::new());
+    let up_next = use_signal(Vec::<Track>::new);
     let loop_mode = use_signal(|| LoopMode::None);
     let pending_resume = use_signal(|| None::<PendingResumeState>);
+    let listening = use_signal(|| None::<ListenSession>);
+    let playing_up_next = use_signal(|| None::<UpNextEntry>);
 
     PlayerController {
         player,
@@ -920,6 +1249,7 @@ pub fn use_player_controller(
         skip_in_progress,
         history,
         queue,
+        up_next,
         shuffle,
         shuffle_order,
         loop_mode,
@@ -934,8 +1264,14 @@ pub fn use_player_controller(
         current_song_cover_url,
         volume,
         library,
+        playlist_store,
+        listen_history,
+        bookmarks,
+        offline,
         config,
         play_generation,
         pending_resume,
+        listening,
+        playing_up_next,
     }
 }
@@ -74,7 +74,7 @@ fn nudge_event_loop() {
 pub fn use_player_task(mut ctrl: PlayerController) {
     #[cfg(not(target_arch = "wasm32"))]
     let presence: Option<Arc<Presence>> = use_context();
-    let mut config: Signal<AppConfig> = use_context();
+    let config: Signal<AppConfig> = use_context();
 
     #[cfg(not(target_arch = "wasm32"))]
     let mut last_title = use_signal(String::new);
@@ -338,6 +338,7 @@ pub fn use_player_task(mut ctrl: PlayerController) {
                     if pos_secs != last_progress_secs {
                         last_progress_secs = pos_secs;
                         ctrl.current_song_progress.set(pos_secs);
+                        ctrl.follow_bookmark(pos_secs);
                     }
 
                     #[cfg(not(target_arch = "wasm32"))]
@@ -419,15 +420,7 @@ pub fn use_player_task(mut ctrl: PlayerController) {
                             last_progress_secs = duration;
                             ctrl.current_song_progress.set(duration);
                         }
-                        {
-                            let mut config_write = config.write();
-                            let q = ctrl.queue.peek();
-                            let idx = *ctrl.current_queue_index.peek();
-                            if let Some(track) = q.get(idx) {
-                                let track_id = track.path.to_string_lossy().to_string();
-                                *config_write.listen_counts.entry(track_id).or_insert(0) += 1;
-                            }
-                        }
+                        ctrl.finish_listen(true);
                         ctrl.play_next();
                         nudge_event_loop();
                     }
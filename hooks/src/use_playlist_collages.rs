@@ -0,0 +1,321 @@
+use ::server::jellyfin::JellyfinClient;
+use config::{AppConfig, MusicService};
+use dioxus::{logger::tracing, prelude::*};
+use reader::models::Track;
+use reader::{Library, PlaylistStore};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// One collage tile: a cached local cover or a server image.
+#[derive(Clone)]
+enum Tile {
+    File(PathBuf),
+    Url(String),
+}
+
+impl Tile {
+    fn key(&self) -> String {
+        match self {
+            Self::File(path) => path.to_string_lossy().into_owned(),
+            Self::Url(url) => url.clone(),
+        }
+    }
+
+    async fn load(&self, http: &reqwest::Client) -> Option<Vec<u8>> {
+        match self {
+            Self::File(path) => tokio::fs::read(path).await.ok(),
+            Self::Url(url) => {
+                let resp = http.get(url).send().await.ok()?;
+                if !resp.status().is_success() {
+                    return None;
+                }
+                resp.bytes().await.ok().map(|b| b.to_vec())
+            }
+        }
+    }
+}
+
+struct CollageJob {
+    playlist_id: String,
+    server: bool,
+    /// Send the collage to the server as the playlist image once it is built.
+    upload: bool,
+    side: u32,
+    tiles: Vec<Tile>,
+    out: PathBuf,
+}
+
+fn file_name(path: &Path) -> Option<String> {
+    path.file_name().map(|n| n.to_string_lossy().into_owned())
+}
+
+/// Keeps a cover collage for every playlist without a cover of its own. Collages
+/// are cached in `collage_dir` under a name derived from the covers they show,
+/// so they are rebuilt only when the first albums of a playlist change.
+pub fn use_playlist_collages(
+    mut playlist_store: Signal<PlaylistStore>,
+    library: Signal<Library>,
+    config: Signal<AppConfig>,
+    collage_dir: PathBuf,
+) {
+    let mut pending = use_signal(HashSet::<PathBuf>::new);
+
+    use_effect(move || {
+        let (style, upload, server) = {
+            let conf = config.read();
+            (
+                conf.playlist_collage,
+                conf.upload_playlist_collages
+                    && conf
+                        .server
+                        .as_ref()
+                        .is_some_and(|s| s.service == MusicService::Jellyfin),
+                conf.server.clone(),
+            )
+        };
+        let store = playlist_store.read();
+        let lib = library.read();
+
+        let mut jobs = Vec::new();
+        let mut stale = Vec::new();
+        let mut plan = |id: &str,
+                        server: bool,
+                        current: Option<&PathBuf>,
+                        uploaded: Option<&str>,
+                        picked: Option<(u32, Vec<Tile>)>| match picked {
+            Some((side, tiles)) => {
+                let keys: Vec<String> = tiles.iter().map(Tile::key).collect();
+                let name = reader::collage::file_name(side, &keys);
+                let out = collage_dir.join(&name);
+                let needs_upload = server && upload && uploaded != Some(name.as_str());
+                if current != Some(&out) || !out.exists() || needs_upload {
+                    jobs.push(CollageJob {
+                        playlist_id: id.to_string(),
+                        server,
+                        upload: needs_upload,
+                        side,
+                        tiles,
+                        out,
+                    });
+                }
+            }
+            None if current.is_some() => stale.push((id.to_string(), server)),
+            None => {}
+        };
+
+        let local_tracks: HashMap<&Path, &Track> =
+            lib.tracks.iter().map(|t| (t.path.as_path(), t)).collect();
+        let album_covers: HashMap<&str, &PathBuf> = lib
+            .albums
+            .iter()
+            .filter_map(|a| Some((a.id.as_str(), a.cover_path.as_ref()?)))
+            .collect();
+        for p in &store.playlists {
+            let picked = p.cover_path.is_none().then(|| {
+                let tracks = p
+                    .tracks
+                    .iter()
+                    .filter_map(|path| local_tracks.get(path.as_path()).copied());
+                reader::collage::pick_covers(tracks, style, |t| {
+                    album_covers
+                        .get(t.album_id.as_str())
+                        .map(|c| Tile::File(c.to_path_buf()))
+                })
+            });
+            plan(
+                &p.id,
+                false,
+                p.collage_path.as_ref(),
+                None,
+                picked.flatten(),
+            );
+        }
+
+        if let Some(server) = &server {
+            let server_tracks: HashMap<&str, &Track> = lib
+                .jellyfin_tracks
+                .iter()
+                .filter_map(|t| {
+                    let id = t.path.to_str()?.split(':').nth(1)?;
+                    Some((id, t))
+                })
+                .collect();
+            for p in &store.jellyfin_playlists {
+                // an image set from another client wins, unless it is our own upload
+                let has_own_image = p.image_tag.is_some() && p.collage_path.is_none();
+                let picked = (p.cover_path.is_none() && !has_own_image).then(|| {
+                    let tracks = p
+                        .tracks
+                        .iter()
+                        .filter_map(|id| server_tracks.get(id.as_str()).copied());
+                    reader::collage::pick_covers(tracks, style, |t| {
+                        server_cover_url(server, t).map(Tile::Url)
+                    })
+                });
+                plan(
+                    &p.id,
+                    true,
+                    p.collage_path.as_ref(),
+                    p.uploaded_collage.as_deref(),
+                    picked.flatten(),
+                );
+            }
+        }
+
+        drop(store);
+        drop(lib);
+
+        if !stale.is_empty() {
+            let mut store = playlist_store.write();
+            for (id, server) in stale {
+                set_collage(&mut store, &id, server, None);
+            }
+        }
+
+        jobs.retain(|job| !pending.peek().contains(&job.out));
+        if jobs.is_empty() {
+            return;
+        }
+        pending
+            .write()
+            .extend(jobs.iter().map(|job| job.out.clone()));
+
+        let collage_dir = collage_dir.clone();
+        spawn(async move {
+            let http = reqwest::Client::new();
+            let _ = tokio::fs::create_dir_all(&collage_dir).await;
+            for job in jobs {
+                let made = job.out.exists() || build(&http, &job).await;
+                pending.write().remove(&job.out);
+                if !made {
+                    continue;
+                }
+                let uploaded = job.upload && upload_collage(config, &job).await;
+                let mut store = playlist_store.write();
+                if uploaded
+                    && let Some(p) = store
+                        .jellyfin_playlists
+                        .iter_mut()
+                        .find(|p| p.id == job.playlist_id)
+                {
+                    p.uploaded_collage = file_name(&job.out);
+                }
+                set_collage(&mut store, &job.playlist_id, job.server, Some(job.out));
+            }
+        });
+    });
+}
+
+/// Points a playlist at a new collage, deleting the one it replaces unless
+/// another playlist shows the same covers.
+fn set_collage(store: &mut PlaylistStore, id: &str, server: bool, path: Option<PathBuf>) {
+    let slot = if server {
+        store
+            .jellyfin_playlists
+            .iter_mut()
+            .find(|p| p.id == id)
+            .map(|p| &mut p.collage_path)
+    } else {
+        store
+            .playlists
+            .iter_mut()
+            .find(|p| p.id == id)
+            .map(|p| &mut p.collage_path)
+    };
+    let Some(slot) = slot else {
+        return;
+    };
+    if *slot == path {
+        return;
+    }
+    let Some(previous) = std::mem::replace(slot, path) else {
+        return;
+    };
+    let still_used = store
+        .playlists
+        .iter()
+        .map(|p| &p.collage_path)
+        .chain(store.jellyfin_playlists.iter().map(|p| &p.collage_path))
+        .any(|c| c.as_ref() == Some(&previous));
+    if !still_used {
+        let _ = std::fs::remove_file(&previous);
+    }
+}
+
+fn server_cover_url(server: &config::MusicServer, track: &Track) -> Option<String> {
+    let path_str = track.path.to_string_lossy();
+    match server.service {
+        MusicService::Jellyfin => utils::jellyfin_image::track_cover_url_with_album_fallback(
+            &path_str,
+            &track.album_id,
+            &server.url,
+            server.access_token.as_deref(),
+            320,
+            80,
+        ),
+        MusicService::Subsonic | MusicService::Custom => {
+            utils::subsonic_image::subsonic_image_url_from_path(
+                &path_str,
+                &server.url,
+                server.access_token.as_deref(),
+                320,
+                80,
+            )
+        }
+    }
+}
+
+async fn build(http: &reqwest::Client, job: &CollageJob) -> bool {
+    let mut covers = Vec::with_capacity(job.tiles.len());
+    for tile in &job.tiles {
+        match tile.load(http).await {
+            Some(bytes) => covers.push(bytes),
+            // try again next time rather than cache a collage with a hole in it
+            None => return false,
+        }
+    }
+
+    let side = job.side;
+    let out = job.out.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let jpeg = utils::artwork::compose_collage(&covers, side).map_err(|e| e.to_string())?;
+        utils::artwork::write_atomically(&out, &jpeg).map_err(|e| e.to_string())
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            tracing::warn!("Failed to build playlist collage {:?}: {}", job.out, e);
+            false
+        }
+        Err(_) => false,
+    }
+}
+
+/// Whether the server took the collage.
+async fn upload_collage(config: Signal<AppConfig>, job: &CollageJob) -> bool {
+    let Ok(bytes) = tokio::fs::read(&job.out).await else {
+        return false;
+    };
+    let remote = {
+        let conf = config.peek();
+        let Some(server) = &conf.server else {
+            return false;
+        };
+        let (Some(token), Some(user_id)) = (&server.access_token, &server.user_id) else {
+            return false;
+        };
+        JellyfinClient::new(&server.url, Some(token), &conf.device_id, Some(user_id))
+    };
+    match remote
+        .set_playlist_image(&job.playlist_id, bytes, "image/jpeg")
+        .await
+    {
+        Ok(_) => true,
+        Err(e) => {
+            tracing::warn!("Failed to upload playlist collage: {}", e);
+            false
+        }
+    }
+}
@@ -1,17 +1,18 @@
 use config::{AppConfig, MusicService, MusicSource};
 use dioxus::prelude::*;
 use reader::Library;
-use reader::models::{Album, Track};
+use reader::models::{Album, Artist, Track, make_artist_id};
 
 // why these, its because code was looking complex and clippy said use type for them to make them look
 // good.
 type TrackRes = Vec<(Track, Option<String>)>;
 type AlbumRes = Vec<(Album, Option<String>)>;
+type ArtistRes = Vec<(Artist, Option<String>)>;
 
 #[derive(Clone, Copy)]
 pub struct SearchData {
     pub genres: Memo<Vec<(String, Option<String>)>>,
-    pub search_results: Memo<Option<(TrackRes, AlbumRes)>>,
+    pub search_results: Memo<Option<(TrackRes, AlbumRes, ArtistRes)>>,
     pub search_query: Signal<String>,
 }
 
@@ -93,7 +94,7 @@ pub fn use_search_data(
                 let cover_url = if !covers.is_empty() {
                     let idx = (g.len() + covers.len()) % covers.len();
                     let c = &covers[idx];
-                    utils::format_artwork_url(Some(c))
+                    utils::format_artwork_url_sized(Some(c), 320)
                 } else {
                     None
                 };
@@ -122,6 +123,7 @@ pub fn use_search_data(
 
         let tracks: Vec<(Track, Option<String>)>;
         let albums: Vec<(Album, Option<String>)>;
+        let artists: Vec<(Artist, Option<String>)>;
 
         match active_source {
             MusicSource::Local => {
@@ -141,7 +143,7 @@ pub fn use_search_data(
                         let cover_url = album_map
                             .get(&t.album_id)
                             .and_then(|a| a.cover_path.as_ref())
-                            .and_then(|c| utils::format_artwork_url(Some(c)));
+                            .and_then(|c| utils::format_artwork_url_sized(Some(c), 96));
                         (t.clone(), cover_url)
                     })
                     .collect();
@@ -160,7 +162,26 @@ pub fn use_search_data(
                         let cover_url = a
                             .cover_path
                             .as_ref()
-                            .and_then(|c| utils::format_artwork_url(Some(c)));
+                            .and_then(|c| utils::format_artwork_url_sized(Some(c), 320));
+                        (a.clone(), cover_url)
+                    })
+                    .collect();
+
+                artists = lib
+                    .artists
+                    .iter()
+                    .filter(|a| artist_matches(a, &query))
+                    .map(|a| {
+                        let cover_url = a
+                            .image_path
+                            .as_ref()
+                            .or_else(|| {
+                                lib.albums
+                                    .iter()
+                                    .find(|album| make_artist_id(&album.artist) == a.id)
+                                    .and_then(|album| album.cover_path.as_ref())
+                            })
+                            .and_then(|c| utils::format_artwork_url_sized(Some(c), 320));
                         (a.clone(), cover_url)
                     })
                     .collect();
@@ -248,10 +269,49 @@ pub fn use_search_data(
                         (a.clone(), cover_url)
                     })
                     .collect();
+
+                artists = lib
+                    .jellyfin_artists
+                    .iter()
+                    .filter(|a| artist_matches(a, &query))
+                    .take(50)
+                    .map(|a| {
+                        let cover_url = server.as_ref().and_then(|server| {
+                            let path_str = a.image_path.as_ref()?.to_string_lossy().into_owned();
+                            match active_service {
+                                Some(MusicService::Jellyfin) => {
+                                    utils::jellyfin_image::jellyfin_image_url_from_path(
+                                        &path_str,
+                                        &server.url,
+                                        server.access_token.as_deref(),
+                                        360,
+                                        80,
+                                    )
+                                }
+                                Some(MusicService::Subsonic) | Some(MusicService::Custom) => {
+                                    match path_str.strip_prefix("directurl:") {
+                                        Some(url) => Some(url.to_string()),
+                                        None => {
+                                            utils::subsonic_image::subsonic_image_url_from_path(
+                                                &path_str,
+                                                &server.url,
+                                                server.access_token.as_deref(),
+                                                360,
+                                                80,
+                                            )
+                                        }
+                                    }
+                                }
+                                None => None,
+                            }
+                        });
+                        (a.clone(), cover_url)
+                    })
+                    .collect();
             }
         }
 
-        Some((tracks, albums))
+        Some((tracks, albums, artists))
     });
 
     SearchData {
@@ -260,3 +320,11 @@ pub fn use_search_data(
         search_query,
     }
 }
+
+fn artist_matches(artist: &Artist, query: &str) -> bool {
+    artist.name.to_lowercase().contains(query)
+        || artist
+            .sort_name
+            .as_ref()
+            .is_some_and(|s| s.to_lowercase().contains(query))
+}
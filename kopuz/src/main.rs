@@ -1,9 +1,10 @@
 #[cfg(target_arch = "wasm32")]
 use crate::web_storage::{
-    clear_web_queue_state, load_web_config, load_web_favorites, load_web_library,
-    load_web_playlists, load_web_queue_state, load_web_ui_state, save_web_config,
-    save_web_favorites, save_web_library, save_web_playlists, save_web_queue_state,
-    save_web_ui_state,
+    clear_web_queue_state, load_web_bookmarks, load_web_config, load_web_favorites,
+    load_web_history, load_web_library, load_web_playlists, load_web_queue_state,
+    load_web_saved_queues, load_web_ui_state, save_web_bookmarks, save_web_config,
+    save_web_favorites, save_web_history, save_web_library, save_web_playlists,
+    save_web_queue_state, save_web_saved_queues, save_web_ui_state,
 };
 use components::{
     bottombar::Bottombar, fullscreen::Fullscreen, rightbar::Rightbar, sidebar::Sidebar,
@@ -21,7 +22,7 @@ use discord_presence::Presence;
 use kopuz_route::Route;
 use player::player::Player;
 use queue_state::PersistedQueueState;
-use reader::FavoritesStore;
+use reader::{BookmarkStore, FavoritesStore, HistoryStore, OfflineStore, SavedQueueStore};
 #[cfg(not(target_arch = "wasm32"))]
 use std::path::PathBuf;
 #[cfg(not(target_arch = "wasm32"))]
@@ -123,8 +124,17 @@ fn is_restorable_queue_track(_track: &reader::Track) -> bool {
 }
 
 fn sanitize_queue_state(state: PersistedQueueState) -> Option<PersistedQueueState> {
+    let up_next: Vec<_> = state
+        .up_next
+        .into_iter()
+        .filter(is_restorable_queue_track)
+        .collect();
     if state.queue.is_empty() {
-        return None;
+        return (!up_next.is_empty()).then(|| PersistedQueueState {
+            version: state.version,
+            up_next,
+            ..Default::default()
+        });
     }
 
     let original_index = state.current_queue_index.min(state.queue.len().saturating_sub(1));
@@ -143,7 +153,11 @@ fn sanitize_queue_state(state: PersistedQueueState) -> Option<PersistedQueueStat
         .collect();
 
     if survivors.is_empty() {
-        return None;
+        return (!up_next.is_empty()).then(|| PersistedQueueState {
+            version: state.version,
+            up_next,
+            ..Default::default()
+        });
     }
 
     let restored_index = if selected_track_survived {
@@ -172,22 +186,131 @@ fn sanitize_queue_state(state: PersistedQueueState) -> Option<PersistedQueueStat
     Some(PersistedQueueState {
         version: state.version,
         queue,
+        up_next,
         current_queue_index: restored_index,
+        from_up_next: state.from_up_next && selected_track_survived,
         progress_secs,
     })
 }
 
+/// Queued tracks are copies, so point them at the album IDs the latest scan produced.
+fn remap_queue_album_ids(mut queue: Signal<Vec<reader::Track>>, library: &reader::Library) {
+    let album_ids: std::collections::HashMap<&std::path::Path, &str> = library
+        .tracks
+        .iter()
+        .map(|t| (t.path.as_path(), t.album_id.as_str()))
+        .collect();
+    let stale = queue.peek().iter().any(|t| {
+        album_ids
+            .get(t.path.as_path())
+            .is_some_and(|id| *id != t.album_id)
+    });
+    if !stale {
+        return;
+    }
+    for track in queue.write().iter_mut() {
+        if let Some(id) = album_ids.get(track.path.as_path()) {
+            track.album_id = id.to_string();
+        }
+    }
+}
+
+/// Play counts used to be stored in the config. Moves any still there into the
+/// listening history, which drops them from the config on its next save.
+fn migrate_listen_counts(mut config: Signal<config::AppConfig>, mut history: Signal<HistoryStore>) {
+    let has_legacy = {
+        let cfg = config.peek();
+        !cfg.legacy_listen_counts.is_empty() || !cfg.legacy_last_played.is_empty()
+    };
+    if !has_legacy {
+        return;
+    }
+    let (counts, last_played) = {
+        let mut cfg = config.write();
+        (
+            std::mem::take(&mut cfg.legacy_listen_counts),
+            std::mem::take(&mut cfg.legacy_last_played),
+        )
+    };
+    if history.write().migrate_counts(counts, last_played) {
+        tracing::info!("Moved play counts from the config into the listening history");
+    }
+}
+
+/// Files that vanished in a scan while tracks with the same tags appeared were
+/// moved or renamed outside the app, so carry favorites, playlists, play counts,
+/// bookmarks, saved queues and queued copies over to the new paths.
+#[cfg(not(target_arch = "wasm32"))]
+fn follow_moved_tracks(
+    previous: &[reader::Track],
+    library: &reader::Library,
+    mut favorites_store: Signal<FavoritesStore>,
+    mut saved_queues: Signal<SavedQueueStore>,
+    mut ctrl: hooks::use_player_controller::PlayerController,
+) {
+    let before: std::collections::HashSet<&std::path::Path> =
+        previous.iter().map(|t| t.path.as_path()).collect();
+    let after: std::collections::HashSet<&std::path::Path> =
+        library.tracks.iter().map(|t| t.path.as_path()).collect();
+    let missing: Vec<reader::Track> = previous
+        .iter()
+        .filter(|t| !after.contains(t.path.as_path()) && !t.path.exists())
+        .cloned()
+        .collect();
+    if missing.is_empty() {
+        return;
+    }
+    let appeared: Vec<reader::Track> = library
+        .tracks
+        .iter()
+        .filter(|t| !before.contains(t.path.as_path()))
+        .cloned()
+        .collect();
+
+    let relocation = reader::relocate::Relocation::match_orphans(&missing, &appeared);
+    if relocation.is_empty() {
+        return;
+    }
+    let changed = relocation.apply(
+        &mut favorites_store.write(),
+        &mut ctrl.playlist_store.write(),
+        &mut ctrl.listen_history.write(),
+        &mut ctrl.bookmarks.write(),
+        &mut saved_queues.write(),
+    );
+    for mut queue in [ctrl.queue, ctrl.up_next] {
+        if queue
+            .peek()
+            .iter()
+            .any(|t| relocation.get(&t.path).is_some())
+        {
+            for track in queue.write().iter_mut() {
+                if let Some(new) = relocation.get(&track.path) {
+                    track.path = new.to_path_buf();
+                }
+            }
+        }
+    }
+    tracing::info!(
+        "Followed {} moved tracks, updated {} references",
+        relocation.len(),
+        changed
+    );
+}
+
 fn build_queue_state_snapshot(
     queue: &[reader::Track],
+    up_next: &[reader::Track],
     current_queue_index: usize,
+    from_up_next: bool,
     current_song_progress: u64,
     is_playing: bool,
 ) -> Option<PersistedQueueState> {
-    if queue.is_empty() {
+    if queue.is_empty() && up_next.is_empty() {
         return None;
     }
 
-    let current_idx = current_queue_index.min(queue.len() - 1);
+    let current_idx = current_queue_index.min(queue.len().saturating_sub(1));
     let progress_secs = queue
         .get(current_idx)
         .map(|track| current_song_progress.min(track.duration))
@@ -201,7 +324,9 @@ fn build_queue_state_snapshot(
     Some(PersistedQueueState {
         version: 1,
         queue: queue.to_vec(),
+        up_next: up_next.to_vec(),
         current_queue_index: current_idx,
+        from_up_next,
         progress_secs,
     })
 }
@@ -283,18 +408,21 @@ fn main() {
             .with_custom_protocol("artwork", |_headers, request| {
                 let uri = request.uri();
 
-                let file_path: String = uri
-                    .query()
-                    .and_then(|q| {
+                let query_param = |key: &str| {
+                    uri.query().and_then(|q| {
                         q.split('&')
-                            .find_map(|kv| kv.strip_prefix("p="))
+                            .find_map(|kv| kv.strip_prefix(key)?.strip_prefix('='))
                             .map(|encoded| {
                                 percent_encoding::percent_decode_str(encoded)
                                     .decode_utf8_lossy()
                                     .into_owned()
                             })
                     })
-                    .unwrap_or_default();
+                };
+                let file_path = query_param("p").unwrap_or_default();
+                let size: u32 = query_param("s")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(0);
 
                 if file_path.is_empty() {
                     return http::Response::builder()
@@ -318,9 +446,9 @@ fn main() {
                     file_path
                 };
 
-                let path = std::path::Path::new(&file_path);
+                let original = std::path::Path::new(&file_path);
 
-                if !path.exists() {
+                if !original.exists() {
                     tracing::warn!("[artwork] File not found: {}", file_path);
                     return http::Response::builder()
                         .status(404)
@@ -328,15 +456,17 @@ fn main() {
                         .unwrap();
                 }
 
-                let mime = if file_path.ends_with(".png") {
-                    "image/png"
-                } else {
-                    "image/jpeg"
-                };
+                // cached covers have small JPEG variants next to them; anything else is served as is
+                let path = utils::artwork::sized_path(original, size);
 
-                match std::fs::read(path) {
+                match std::fs::read(&path) {
                     Ok(content) => http::Response::builder()
-                        .header("Content-Type", mime)
+                        .header(
+                            "Content-Type",
+                            utils::artwork::ImageKind::detect(&content)
+                                .map(utils::artwork::ImageKind::mime)
+                                .unwrap_or("image/jpeg"),
+                        )
                         .header("Access-Control-Allow-Origin", "*")
                         .header("Cache-Control", "public, max-age=31536000")
                         .body(std::borrow::Cow::from(content))
@@ -400,13 +530,30 @@ fn App() -> Element {
     let favorites_path = use_memo(move || cache_dir().join("favorites.json"));
     let queue_state_path = use_memo(move || cache_dir().join("queue_state.json"));
     let mut favorites_store = use_signal(FavoritesStore::default);
+    #[allow(unused_variables)]
+    let history_path = use_memo(move || cache_dir().join("history.json"));
+    let mut history = use_signal(HistoryStore::default);
+    #[allow(unused_variables)]
+    let bookmarks_path = use_memo(move || cache_dir().join("bookmarks.json"));
+    let mut bookmarks = use_signal(BookmarkStore::default);
+    #[allow(unused_variables)]
+    let saved_queues_path = use_memo(move || cache_dir().join("saved_queues.json"));
+    let mut saved_queues = use_signal(SavedQueueStore::default);
+    #[allow(unused_variables)]
+    let offline_path = use_memo(move || cache_dir().join("offline.json"));
+    let mut offline = use_signal(OfflineStore::default);
     let mut initial_load_done = use_signal(|| false);
     #[allow(unused_variables)]
     let cover_cache = use_memo(move || cache_dir().join("covers"));
     #[cfg(not(target_arch = "wasm32"))]
     let _ = std::fs::create_dir_all(cover_cache());
+    #[cfg(not(target_arch = "wasm32"))]
+    let tag_editor_cover_cache = use_signal(|| cover_cache.cloned());
+    #[cfg(not(target_arch = "wasm32"))]
+    let tag_editor_last_write = use_signal(Vec::new);
     let mut trigger_rescan = use_signal(|| 0);
-    let mut scan_current_file = use_signal(|| Option::<String>::None);
+    let mut scan_progress = use_signal(|| Option::<reader::ScanProgress>::None);
+    let mut scan_cancel = use_signal(|| Option::<reader::ScanCancel>::None);
     let current_playing = use_signal(|| 0);
     let mut player = use_signal(Player::new);
     let current_song_cover_url = use_signal(String::new);
@@ -415,11 +562,14 @@ fn App() -> Element {
     let current_song_album = use_signal(String::new);
     let current_song_duration = use_signal(|| 0u64);
     let current_song_khz = use_signal(|| 0u32);
-    let current_song_bitrate = use_signal(|| 0u8);
+    let current_song_bitrate = use_signal(|| 0u32);
     let current_song_progress = use_signal(|| 0u64);
     let mut volume = use_signal(|| 1.0f32);
     let mut persisted_volume = use_signal(|| 1.0f32);
     let mut configured_music_dirs = use_signal(|| config.peek().music_directory.clone());
+    let mut configured_album_grouping = use_signal(|| config.peek().album_grouping);
+    let mut configured_cover_options = use_signal(|| config.peek().cover_options.clone());
+    let mut configured_scan_rules = use_signal(|| config.peek().scan_rules.clone());
 
     let is_playing = use_signal(|| false);
     let is_fullscreen = use_signal(|| false);
@@ -465,6 +615,18 @@ fn App() -> Element {
         if *configured_music_dirs.peek() != next_dirs {
             configured_music_dirs.set(next_dirs);
         }
+        let next_grouping = config.read().album_grouping;
+        if *configured_album_grouping.peek() != next_grouping {
+            configured_album_grouping.set(next_grouping);
+        }
+        let next_cover_options = config.read().cover_options.clone();
+        if *configured_cover_options.peek() != next_cover_options {
+            configured_cover_options.set(next_cover_options);
+        }
+        let next_scan_rules = config.read().scan_rules.clone();
+        if *configured_scan_rules.peek() != next_scan_rules {
+            configured_scan_rules.set(next_scan_rules);
+        }
     });
 
     #[cfg(not(target_arch = "wasm32"))]
@@ -495,6 +657,10 @@ fn App() -> Element {
         current_song_cover_url,
         volume,
         library,
+        playlist_store,
+        history,
+        bookmarks,
+        offline,
         config,
     );
 
@@ -622,15 +788,136 @@ fn App() -> Element {
         }
     });
 
+    use_effect(move || {
+        if !*initial_load_done.read() {
+            return;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let store_snapshot = history.read().clone();
+            let path = history_path();
+            spawn(async move {
+                let result = tokio::task::spawn_blocking(move || store_snapshot.save(&path)).await;
+                if let Ok(Err(e)) = result {
+                    tracing::error!("Failed to save listening history: {}", e);
+                }
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let store_snapshot = history.read().clone();
+            save_web_history(&store_snapshot);
+        }
+    });
+
+    use_effect(move || {
+        if !*initial_load_done.read() {
+            return;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let store_snapshot = bookmarks.read().clone();
+            let path = bookmarks_path();
+            spawn(async move {
+                let result = tokio::task::spawn_blocking(move || store_snapshot.save(&path)).await;
+                if let Ok(Err(e)) = result {
+                    tracing::error!("Failed to save bookmarks: {}", e);
+                }
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let store_snapshot = bookmarks.read().clone();
+            save_web_bookmarks(&store_snapshot);
+        }
+    });
+
+    use_effect(move || {
+        if !*initial_load_done.read() {
+            return;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let store_snapshot = saved_queues.read().clone();
+            let path = saved_queues_path();
+            spawn(async move {
+                let result = tokio::task::spawn_blocking(move || store_snapshot.save(&path)).await;
+                if let Ok(Err(e)) = result {
+                    tracing::error!("Failed to save saved queues: {}", e);
+                }
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let store_snapshot = saved_queues.read().clone();
+            save_web_saved_queues(&store_snapshot);
+        }
+    });
+
+    // downloads only exist on desktop, so there is no web copy to keep
+    #[cfg(not(target_arch = "wasm32"))]
+    use_effect(move || {
+        if !*initial_load_done.read() {
+            return;
+        }
+        let store_snapshot = offline.read().clone();
+        let path = offline_path();
+        spawn(async move {
+            let result = tokio::task::spawn_blocking(move || store_snapshot.save(&path)).await;
+            if let Ok(Err(e)) = result {
+                tracing::error!("Failed to save offline downloads: {}", e);
+            }
+        });
+    });
+
+    // pick up bookmarks set on other devices whenever a server connects
+    let bookmark_server = use_memo(move || {
+        config
+            .read()
+            .server
+            .as_ref()
+            .map(|server| (server.url.clone(), server.access_token.clone()))
+    });
+    use_effect(move || {
+        if !*initial_load_done.read() || bookmark_server.read().is_none() {
+            return;
+        }
+        spawn(async move {
+            if let Err(e) = hooks::pull_server_bookmarks(config, bookmarks).await {
+                tracing::warn!("Failed to fetch server bookmarks: {}", e);
+            }
+        });
+    });
+
+    // ratings live in the favorites store; copy them onto tracks for sorting and filters
+    use_effect(move || {
+        if !*initial_load_done.read() {
+            return;
+        }
+        let store = favorites_store.read();
+        let outdated = {
+            let lib = library.read();
+            store.ratings_outdated(&lib.tracks) || store.ratings_outdated(&lib.jellyfin_tracks)
+        };
+        if outdated {
+            let mut lib = library.write();
+            store.apply_ratings(&mut lib.tracks);
+            store.apply_ratings(&mut lib.jellyfin_tracks);
+        }
+    });
+
     use_effect(move || {
         if !*initial_load_done.read() {
             return;
         }
 
         let queue_snapshot = queue.read().clone();
+        let up_next_snapshot = ctrl.up_next.read().clone();
         let queue_state = build_queue_state_snapshot(
             &queue_snapshot,
+            &up_next_snapshot,
             *current_queue_index.read(),
+            ctrl.playing_from_up_next(),
             *current_song_progress.read(),
             *is_playing.read(),
         );
@@ -685,15 +972,35 @@ fn App() -> Element {
                 let config_path_c = config_path.clone();
                 let playlist_path_c = playlist_path.clone();
                 let favorites_path_c = favorites_path.clone();
+                let history_path_c = history_path();
+                let bookmarks_path_c = bookmarks_path();
+                let saved_queues_path_c = saved_queues_path();
+                let offline_path_c = offline_path();
                 let queue_state_path_c = queue_state_path.clone();
 
-                let (lib_res, cfg_res, pl_res, fav_res, queue_res) = tokio::join!(
+                let (
+                    lib_res,
+                    cfg_res,
+                    pl_res,
+                    fav_res,
+                    history_res,
+                    bookmarks_res,
+                    saved_queues_res,
+                    offline_res,
+                    queue_res,
+                ) = tokio::join!(
                     tokio::task::spawn_blocking(move || reader::Library::load(&lib_path_c)),
                     tokio::task::spawn_blocking(move || config::AppConfig::load(&config_path_c)),
                     tokio::task::spawn_blocking(move || reader::PlaylistStore::load(
                         &playlist_path_c
                     )),
                     tokio::task::spawn_blocking(move || FavoritesStore::load(&favorites_path_c)),
+                    tokio::task::spawn_blocking(move || HistoryStore::load(&history_path_c)),
+                    tokio::task::spawn_blocking(move || BookmarkStore::load(&bookmarks_path_c)),
+                    tokio::task::spawn_blocking(move || SavedQueueStore::load(
+                        &saved_queues_path_c
+                    )),
+                    tokio::task::spawn_blocking(move || OfflineStore::load(&offline_path_c)),
                     tokio::task::spawn_blocking(move || {
                         PersistedQueueState::load(&queue_state_path_c)
                     }),
@@ -705,6 +1012,7 @@ fn App() -> Element {
                 if let Ok(loaded) = cfg_res {
                     config.set(loaded.clone());
                     configured_music_dirs.set(loaded.music_directory.clone());
+                    configured_album_grouping.set(loaded.album_grouping);
                     volume.set(loaded.volume);
                     persisted_volume.set(loaded.volume);
                     player.write().set_volume(loaded.volume);
@@ -717,6 +1025,19 @@ fn App() -> Element {
                 if let Ok(Ok(loaded)) = fav_res {
                     favorites_store.set(loaded);
                 }
+                if let Ok(Ok(loaded)) = history_res {
+                    history.set(loaded);
+                    migrate_listen_counts(config, history);
+                }
+                if let Ok(Ok(loaded)) = bookmarks_res {
+                    bookmarks.set(loaded);
+                }
+                if let Ok(Ok(loaded)) = saved_queues_res {
+                    saved_queues.set(loaded);
+                }
+                if let Ok(Ok(loaded)) = offline_res {
+                    offline.set(loaded);
+                }
 
                 {
                     let cfg = config.peek();
@@ -737,8 +1058,10 @@ fn App() -> Element {
                     if let Some(queue_state) = sanitize_queue_state(loaded_queue_state) {
                         ctrl.restore_queue_state(
                             queue_state.queue,
+                            queue_state.up_next,
                             queue_state.current_queue_index,
                             queue_state.progress_secs,
+                            queue_state.from_up_next,
                         );
                     }
                 }
@@ -786,12 +1109,24 @@ fn App() -> Element {
             if let Some(loaded_favorites) = load_web_favorites() {
                 favorites_store.set(loaded_favorites);
             }
+            if let Some(loaded_history) = load_web_history() {
+                history.set(loaded_history);
+            }
+            migrate_listen_counts(config, history);
+            if let Some(loaded_bookmarks) = load_web_bookmarks() {
+                bookmarks.set(loaded_bookmarks);
+            }
+            if let Some(loaded_saved_queues) = load_web_saved_queues() {
+                saved_queues.set(loaded_saved_queues);
+            }
             if let Some(loaded_queue_state) = load_web_queue_state() {
                 if let Some(queue_state) = sanitize_queue_state(loaded_queue_state) {
                     ctrl.restore_queue_state(
                         queue_state.queue,
+                        queue_state.up_next,
                         queue_state.current_queue_index,
                         queue_state.progress_secs,
+                        queue_state.from_up_next,
                     );
                 }
             }
@@ -828,6 +1163,9 @@ fn App() -> Element {
             return;
         }
         let configured_dirs = configured_music_dirs.read().clone();
+        let album_grouping = *configured_album_grouping.read();
+        let covers = configured_cover_options.read().clone();
+        let rules = configured_scan_rules.read().clone();
         let _ = trigger_rescan.read();
 
         #[cfg(not(target_arch = "wasm32"))]
@@ -838,6 +1176,7 @@ fn App() -> Element {
                 .cloned()
                 .collect();
             let mut current_lib = library.peek().clone();
+            let previous_tracks = current_lib.tracks.clone();
 
             let current_roots: std::collections::HashSet<_> =
                 current_lib.root_paths.iter().cloned().collect();
@@ -847,34 +1186,64 @@ fn App() -> Element {
                 current_lib.root_paths = configured_dirs.clone();
                 current_lib.tracks.clear();
                 current_lib.albums.clear();
+                current_lib.artists.clear();
                 library.set(current_lib.clone());
             }
 
             if !configured_dirs.is_empty() {
-                scan_current_file.set(Some(String::new()));
+                let cancel = reader::ScanCancel::new();
+                if let Some(previous) = scan_cancel.write().replace(cancel.clone()) {
+                    previous.cancel();
+                }
+                scan_progress.set(Some(reader::ScanProgress::default()));
 
-                let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+                let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<reader::ScanProgress>();
+                let active_scan = cancel.clone();
                 spawn(async move {
-                    while let Some(file) = rx.recv().await {
-                        scan_current_file.set(Some(file));
+                    while let Some(mut progress) = rx.recv().await {
+                        // coalesce bursts from the writer so the banner re-renders at most once per tick
+                        while let Ok(latest) = rx.try_recv() {
+                            progress = latest;
+                        }
+                        if scan_cancel.peek().as_ref() == Some(&active_scan) {
+                            scan_progress.set(Some(progress));
+                        }
                     }
-                    scan_current_file.set(None);
                 });
 
-                let progress_cb: std::sync::Arc<dyn Fn(String) + Send + Sync> =
-                    std::sync::Arc::new(move |file: String| {
-                        let _ = tx.send(file);
+                let progress_cb: std::sync::Arc<dyn Fn(reader::ScanProgress) + Send + Sync> =
+                    std::sync::Arc::new(move |progress: reader::ScanProgress| {
+                        let _ = tx.send(progress);
                     });
-                for dir in &scannable_dirs {
-                    let _ = reader::scan_directory(
-                        dir.clone(),
-                        cover_cache(),
-                        &mut current_lib,
-                        progress_cb.clone(),
-                    )
-                    .await;
+                let result = reader::scan_directories(
+                    scannable_dirs.clone(),
+                    cover_cache(),
+                    &mut current_lib,
+                    reader::ScanOptions {
+                        album_grouping,
+                        covers,
+                        rules,
+                    },
+                    progress_cb,
+                    cancel.clone(),
+                )
+                .await;
+
+                let superseded = scan_cancel.peek().as_ref() != Some(&cancel);
+                if !superseded {
+                    scan_cancel.set(None);
+                    scan_progress.set(None);
+                }
+                if let Err(e) = result {
+                    if e.kind() == std::io::ErrorKind::Interrupted {
+                        tracing::info!("Library scan cancelled");
+                        if superseded {
+                            return;
+                        }
+                    } else {
+                        tracing::error!("Library scan failed: {}", e);
+                    }
                 }
-                drop(progress_cb);
 
                 current_lib.tracks.retain(|t| {
                     let in_configured_root = configured_dirs.iter().any(|d| t.path.starts_with(d));
@@ -883,20 +1252,24 @@ fn App() -> Element {
                     in_configured_root && (!in_scannable_root || t.path.exists())
                 });
 
-                let valid_album_ids: std::collections::HashSet<_> = current_lib
-                    .tracks
-                    .iter()
-                    .map(|t| t.album_id.clone())
-                    .collect();
-                current_lib
-                    .albums
-                    .retain(|a| valid_album_ids.contains(&a.id));
+                current_lib.prune_albums();
+                current_lib.prune_artists();
 
+                follow_moved_tracks(
+                    &previous_tracks,
+                    &current_lib,
+                    favorites_store,
+                    saved_queues,
+                    ctrl,
+                );
                 library.set(current_lib.clone());
+                remap_queue_album_ids(queue, &current_lib);
+                remap_queue_album_ids(ctrl.up_next, &current_lib);
                 let _ = current_lib.save(&lib_path());
             } else {
                 current_lib.tracks.clear();
                 current_lib.albums.clear();
+                current_lib.artists.clear();
                 current_lib.root_paths.clear();
                 library.set(current_lib.clone());
                 let _ = current_lib.save(&lib_path());
@@ -913,8 +1286,22 @@ fn App() -> Element {
 
     provide_context(ctrl);
     provide_context(config);
+    provide_context(favorites_store);
+    provide_context(history);
+    provide_context(bookmarks);
+    provide_context(saved_queues);
+    provide_context(offline);
+    #[cfg(not(target_arch = "wasm32"))]
+    provide_context(components::tag_editor::TagEditorState {
+        cover_cache: tag_editor_cover_cache,
+        last_write: tag_editor_last_write,
+    });
 
     hooks::use_player_task(ctrl);
+    #[cfg(not(target_arch = "wasm32"))]
+    hooks::use_playlist_collages(playlist_store, library, config, cache_dir().join("collages"));
+    #[cfg(not(target_arch = "wasm32"))]
+    provide_context(hooks::use_offline_downloads(offline, config, cache_dir().join("offline")));
 
     // Inject CSS for all custom themes reactively
     let custom_themes_css = use_memo(move || {
@@ -981,24 +1368,40 @@ fn App() -> Element {
                 div { dir: "ltr", Titlebar {} }
             }
             if config.read().active_source == config::MusicSource::Local {
-                if let Some(file) = scan_current_file.read().clone() {
+                if let Some(progress) = *scan_progress.read() {
                     div {
                         class: "flex-shrink-0",
                         div {
                             class: "h-[2px] bg-white/5 overflow-hidden",
-                            div { class: "h-full w-1/4 bg-[var(--color-primary,#6366f1)] animate-scan" }
+                            if progress.total == 0 {
+                                div { class: "h-full w-1/4 bg-[var(--color-primary,#6366f1)] animate-scan" }
+                            } else {
+                                div {
+                                    class: "h-full bg-[var(--color-primary,#6366f1)] transition-all",
+                                    style: "width: {progress.done * 100 / progress.total}%",
+                                }
+                            }
                         }
                         div {
                             class: "px-3 py-[3px] flex items-center gap-2 bg-black/30 border-b border-white/5",
                             i { class: "fa-solid fa-compact-disc fa-spin text-[9px] text-white/30 flex-shrink-0" }
                             span {
-                                class: "text-[10px] text-white/35 font-mono truncate",
-                                if file.is_empty() {
+                                class: "text-[10px] text-white/35 font-mono truncate flex-1",
+                                if progress.total == 0 {
                                     "Scanning library…"
                                 } else {
-                                    "{file}"
+                                    "Scanning library… {progress.done} / {progress.total}"
                                 }
                             }
+                            button {
+                                class: "text-[10px] text-white/35 hover:text-white/70 transition-colors",
+                                onclick: move |_| {
+                                    if let Some(cancel) = scan_cancel.peek().as_ref() {
+                                        cancel.cancel();
+                                    }
+                                },
+                                i { class: "fa-solid fa-xmark" }
+                            }
                         }
                     }
                 }
@@ -1081,6 +1484,10 @@ fn App() -> Element {
                                 current_song_progress: current_song_progress,
                                 queue: queue,
                                 current_queue_index: current_queue_index,
+                                on_select_artist: move |name: String| {
+                                    selected_artist_name.set(name);
+                                    current_route.set(Route::Artist);
+                                },
                             }
                         },
                         Route::Library => rsx! {
@@ -1088,6 +1495,7 @@ fn App() -> Element {
                                 library: library,
                                 config: config,
                                 playlist_store: playlist_store,
+                                favorites_store: favorites_store,
                                 on_rescan: move |_| *trigger_rescan.write() += 1,
                                 player: player,
                                 is_playing: is_playing,
@@ -1125,6 +1533,7 @@ fn App() -> Element {
                                 config: config,
                                 artist_name: selected_artist_name,
                                 playlist_store: playlist_store,
+                                favorites_store: favorites_store,
                                 player: player,
                                 on_navigate: move |album_id| {
                                     selected_album_id.set(album_id);
@@ -1157,11 +1566,16 @@ fn App() -> Element {
                                 current_song_progress,
                                 queue,
                                 current_queue_index,
+                                on_select_artist: move |name: String| {
+                                    selected_artist_name.set(name);
+                                    current_route.set(Route::Artist);
+                                },
                             }
                         },
                         Route::Playlists => rsx! {
                             pages::playlists::PlaylistsPage {
                                 playlist_store: playlist_store,
+                                favorites_store: favorites_store,
                                 library: library,
                                 config: config,
                                 player: player,
@@ -1186,8 +1600,8 @@ fn App() -> Element {
                         #[cfg(not(target_arch = "wasm32"))]
                         Route::Ytdlp => rsx! { pages::ytdlp::YtdlpPage { config } },
                         #[cfg(target_arch = "wasm32")]
-                        Route::Ytdlp => rsx! { pages::settings::Settings { config } },
-                        Route::Settings => rsx! { pages::settings::Settings { config } },
+                        Route::Ytdlp => rsx! { pages::settings::Settings { config, playlist_store, favorites_store } },
+                        Route::Settings => rsx! { pages::settings::Settings { config, playlist_store, favorites_store } },
                         Route::ThemeEditor => rsx! { pages::theme_editor::ThemeEditorPage { config } },
                     }
                 }
@@ -11,10 +11,18 @@ fn default_queue_state_version() -> u8 {
 pub struct PersistedQueueState {
     #[serde(default = "default_queue_state_version")]
     pub version: u8,
+    /// The context: the album, playlist or list the queue was started from.
     #[serde(default)]
     pub queue: Vec<Track>,
+    /// Tracks queued by hand, played before the rest of the context.
+    #[serde(default)]
+    pub up_next: Vec<Track>,
     #[serde(default)]
     pub current_queue_index: usize,
+    /// The current track was played from Up Next and leaves the context once
+    /// something else plays.
+    #[serde(default)]
+    pub from_up_next: bool,
     #[serde(default)]
     pub progress_secs: u64,
 }
@@ -24,7 +32,9 @@ impl Default for PersistedQueueState {
         Self {
             version: default_queue_state_version(),
             queue: Vec::new(),
+            up_next: Vec::new(),
             current_queue_index: 0,
+            from_up_next: false,
             progress_secs: 0,
         }
     }
@@ -1,7 +1,7 @@
 #[cfg(target_arch = "wasm32")]
 use crate::queue_state::PersistedQueueState;
 #[cfg(target_arch = "wasm32")]
-use reader::FavoritesStore;
+use reader::{BookmarkStore, FavoritesStore, HistoryStore, SavedQueueStore};
 #[cfg(target_arch = "wasm32")]
 use kopuz_route::Route;
 
@@ -16,6 +16,12 @@ const WEB_PLAYLISTS_STORAGE_KEY: &str = "kopuz.playlists.v1";
 #[cfg(target_arch = "wasm32")]
 const WEB_FAVORITES_STORAGE_KEY: &str = "kopuz.favorites.v1";
 #[cfg(target_arch = "wasm32")]
+const WEB_HISTORY_STORAGE_KEY: &str = "kopuz.history.v1";
+#[cfg(target_arch = "wasm32")]
+const WEB_BOOKMARKS_STORAGE_KEY: &str = "kopuz.bookmarks.v1";
+#[cfg(target_arch = "wasm32")]
+const WEB_SAVED_QUEUES_STORAGE_KEY: &str = "kopuz.saved-queues.v1";
+#[cfg(target_arch = "wasm32")]
 const WEB_QUEUE_STATE_STORAGE_KEY: &str = "kopuz.queue-state.v1";
 
 #[cfg(target_arch = "wasm32")]
@@ -204,6 +210,69 @@ pub fn save_web_favorites(store: &FavoritesStore) {
     }
 }
 
+#[cfg(target_arch = "wasm32")]
+pub fn load_web_history() -> Option<HistoryStore> {
+    let storage = web_sys::window()
+        .and_then(|w| w.local_storage().ok())
+        .flatten()?;
+    let raw = storage.get_item(WEB_HISTORY_STORAGE_KEY).ok().flatten()?;
+    serde_json::from_str::<HistoryStore>(&raw).ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_web_history(store: &HistoryStore) {
+    if let (Some(storage), Ok(raw)) = (
+        web_sys::window()
+            .and_then(|w| w.local_storage().ok())
+            .flatten(),
+        serde_json::to_string(store),
+    ) {
+        let _ = storage.set_item(WEB_HISTORY_STORAGE_KEY, &raw);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load_web_bookmarks() -> Option<BookmarkStore> {
+    let storage = web_sys::window()
+        .and_then(|w| w.local_storage().ok())
+        .flatten()?;
+    let raw = storage.get_item(WEB_BOOKMARKS_STORAGE_KEY).ok().flatten()?;
+    serde_json::from_str::<BookmarkStore>(&raw).ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_web_bookmarks(store: &BookmarkStore) {
+    if let (Some(storage), Ok(raw)) = (
+        web_sys::window()
+            .and_then(|w| w.local_storage().ok())
+            .flatten(),
+        serde_json::to_string(store),
+    ) {
+        let _ = storage.set_item(WEB_BOOKMARKS_STORAGE_KEY, &raw);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load_web_saved_queues() -> Option<SavedQueueStore> {
+    let storage = web_sys::window()
+        .and_then(|w| w.local_storage().ok())
+        .flatten()?;
+    let raw = storage.get_item(WEB_SAVED_QUEUES_STORAGE_KEY).ok().flatten()?;
+    serde_json::from_str::<SavedQueueStore>(&raw).ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_web_saved_queues(store: &SavedQueueStore) {
+    if let (Some(storage), Ok(raw)) = (
+        web_sys::window()
+            .and_then(|w| w.local_storage().ok())
+            .flatten(),
+        serde_json::to_string(store),
+    ) {
+        let _ = storage.set_item(WEB_SAVED_QUEUES_STORAGE_KEY, &raw);
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 pub fn load_web_queue_state() -> Option<PersistedQueueState> {
     let storage = web_sys::window()
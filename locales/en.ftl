@@ -43,6 +43,10 @@ titlebar_mode = Titlebar
 titlebar_custom = Custom (app-drawn)
 titlebar_system = System decorations
 titlebar_off = No titlebar
+album_grouping = Album Grouping
+album_grouping_album_artist = Album artist + title
+album_grouping_folder = Album artist + title + folder
+album_grouping_title = Title only (legacy)
 volume = Volume
 local = Local
 server = Server
@@ -97,6 +101,8 @@ ytdlp_error_exit = yt-dlp exited: { $status }
 # UI Actions & Buttons
 add_to_favorites = Add to Favorites
 remove_from_favorites = Remove from Favorites
+rate_stars = Rate { $stars } of 5
+album_rating = Album rating
 add_to_playlist = Add to Playlist
 remove_from_playlist = Remove from Playlist
 delete = Delete
@@ -117,6 +123,7 @@ disabled = DISABLED
 tracks = Tracks
 albums = Albums
 artists = Artists
+favorite_artists = Favorite Artists
 all_albums = All Albums
 your_library = Your Library
 listening_logs = Listening Logs
@@ -137,6 +144,12 @@ lyrics = LYRICS
 # Player/Media
 loading_lyrics = Loading lyrics...
 lyrics_not_found = Lyrics not found
+lyrics_sources = Lyrics source order
+lyrics_online_lookup = Look up lyrics online
+lyrics_source_sidecar = .lrc / .txt file next to the track
+lyrics_source_embedded_synced = Embedded synced lyrics
+lyrics_source_embedded_plain = Embedded lyrics
+lyrics_source_online = lrclib.net
 no_previous_songs = No previous songs
 playlist_track_count = { $count } tracks
 music_playlist_count = Music • { $count } tracks
@@ -179,6 +192,26 @@ track_count = { $count } tracks
 track_count_singular = 1 track
 songs = songs
 min = min
+track_info = Track Info
+album_artist = Album Artist
+composer = Composer
+release_date = Release Date
+year = Year
+bpm = BPM
+isrc = ISRC
+record_label = Label
+comment = Comment
+mb_recording_id = MusicBrainz Recording ID
+mb_artist_ids = MusicBrainz Artist IDs
+file_size = File Size
+file_path = Path
+codec = Codec
+quality = Quality
+date_added = Date Added
+rating = Rating
+disc = Disc
+track_number = Track
+filter_tracks = Filter (e.g. genre:jazz year:1970-1979 bpm:>120)
 
 # Error Messages
 invalid_server_url = Invalid server URL
@@ -208,7 +241,33 @@ no_tracks_in_library = No tracks found in your library.
 no_songs_here = No songs here.
 syncing_with_server = Syncing with server...
 most_played_tracks = Your most played tracks.
+recently_played = Recently Played
+last_7_days = 7 days
+last_30_days = 30 days
+all_time = All time
+skip_rate = Skip Rate
+skip_rate_of_track = Skipped { $rate }% of the time
+time_listened = Time Listened
+top_artist = Top Artist
+listen_completed = Played to the end
+listen_skipped = Skipped
+minutes_ago = { $count }m ago
+hours_ago = { $count }h ago
+days_ago = { $count }d ago
 no_more_songs = No more songs in queue
+queued_by_you = Queued by you
+saved_queues = Saved queues
+queue_name = Queue name
+save_queue = Save
+no_saved_queues = No saved queues yet
+restore_queue = Play this queue
+queue_to_playlist = Save as playlist
+queue_saved_as_playlist = Saved as a playlist
+next_from_context = Next from the album or playlist
+clear_queue = Clear
+remove_from_queue = Remove from queue
+play_next = Play next
+add_to_queue = Add to queue
 server_playlist = Server Playlist
 folder_playlists = Folder Playlists
 folder_playlist = Folder
@@ -292,3 +351,117 @@ remove_from_folder = Remove from Folder
 back_behavior = Back Button Behavior
 back_behavior_rewind = REWIND → PREV
 back_behavior_always_prev = ALWAYS PREV
+shuffle_mode = Shuffle Mode
+shuffle_mode_random = Random
+shuffle_mode_smart = Smart (spread artists and albums)
+shuffle_mode_albums = Albums (keep track order)
+shuffle_by_rating = Smart shuffle: prefer highly rated tracks
+shuffle_by_plays = Smart shuffle: prefer rarely played tracks
+shuffle_avoid_recent_hours = Smart shuffle: play tracks heard in the last N hours last
+autoplay = Autoplay related tracks when the queue ends
+start_radio = Start radio
+bookmark_min_minutes = Remember where tracks longer than N minutes were left off
+restart_from_beginning = Restart from beginning
+offline_downloads = Offline downloads
+available_offline = Make available offline
+remove_download = Remove download
+offline_cache_limit_mb = Space for offline downloads (MB)
+offline_transcode = Download a 320 kbps MP3 instead of the original file
+offline_usage = { $used } of { $limit } used, { $pinned } pinned
+offline_downloading = Downloading { $title } ({ $progress })
+offline_waiting = { $count } tracks waiting to download
+offline_no_room = No room for { $title } ({ $size }), even without the unpinned downloads. Raise the limit or unpin something to carry on.
+no_offline_collections = No albums or playlists are available offline yet
+pin_offline = Pin, so it is never removed to make room
+unpin_offline = Unpin, so it can be removed to make room
+clear_unpinned_downloads = Clear unpinned downloads
+edit_tags = Edit tags
+edit_tags_count = Edit tags ({ $count } tracks)
+tag_keep_existing = Keep existing
+multiple_values = Multiple values
+lyrics_tag = Lyrics
+cover = Cover
+choose_image = Choose image
+remove_cover = Remove cover
+tag_cover_unchanged = Unchanged
+tag_cover_new = New image selected
+tag_cover_removed = Will be removed
+undo_last_tag_edit = Undo last edit
+tag_write_failed = Could not write tags: { $error }
+invalid_number = "{ $value }" is not a number
+cover_preference = Album art source
+cover_preference_embedded = Embedded first
+cover_preference_folder = Folder image first
+cover_file_names = Folder cover names
+cover_file_names_hint = Comma-separated, in priority order. * matches anything, {"{"}album{"}"} is the album title.
+playlist_collage = Playlist covers
+playlist_collage_off = First album cover
+playlist_collage_grid2 = 2×2 album collage
+playlist_collage_grid3 = 3×3 album collage
+upload_playlist_collages = Upload collages to Jellyfin
+write_ratings_to_tags = Write ratings to file tags
+disc_number = Disc { $number }
+compilations = Compilations
+scan_excludes = Excluded from scans
+scan_excludes_placeholder = One pattern per line, e.g. Voice Memos/ or *.m4r
+skip_hidden_files = Skip hidden files and folders
+min_track_duration = Minimum track length (seconds)
+find_duplicates = Find duplicates
+duplicates = Duplicates
+duplicates_none = No duplicates found.
+duplicates_found = Songs with more than one copy: { $count }. The selected copy is kept.
+duplicates_different_recording = Different recording
+duplicates_fingerprint = Compare audio
+duplicates_fingerprint_hint = Decodes each copy to make sure it is the same recording. Copies that differ are left alone.
+duplicates_hide = Hide others
+duplicates_hide_hint = Keeps the files on disk but leaves them out of future scans
+duplicates_trash = Move others to trash
+duplicates_trash_confirm = Move { $count } files to the trash?
+duplicates_trash_failed = Some files could not be moved to the trash: { $error }
+relocate_folder = Moved…
+relocate_folder_hint = Pick the folder's new location. Favorites, playlists and play counts follow the files.
+relocated_references = Updated { $count } references to the new location.
+smart_playlists = Smart playlists
+smart_playlist = Smart playlist
+new_smart_playlist = New smart playlist
+smart_rule_count = { $count } rules
+edit_rules = Edit rules
+add_rule = Add rule
+smart_match_all = Match all rules
+smart_match_any = Match any rule
+smart_field_title = Title
+smart_field_artist = Artist
+smart_field_album = Album
+smart_field_genre = Genre
+smart_field_year = Year
+smart_field_duration = Length (seconds)
+smart_field_play_count = Play count
+smart_field_date_added = Date added
+smart_field_last_played = Last played
+smart_field_favorite = Favorite
+smart_field_rating = Rating (stars)
+smart_op_contains = contains
+smart_op_not_contains = does not contain
+smart_op_is = is
+smart_op_is_not = is not
+smart_op_less_than = is less than
+smart_op_greater_than = is greater than
+smart_op_in_last = in the last
+smart_op_not_in_last = not in the last
+smart_days = days
+sort_by = Sort by
+smart_descending = Descending
+smart_limit = Limit
+import_playlist = Import playlist file
+export_playlist = Export playlist file
+playlist_imported = Imported { $matched } of { $total } tracks into "{ $name }".
+playlist_exported = Exported { $matched } of { $total } tracks to { $file }.
+playlist_unmatched = Not found in your library:
+playlist_file_error = Couldn't use { $file }: { $error }
+copy_to_server = Copy to server
+copy_to_local = Copy to local library
+convert_matched = { $matched } of { $total } tracks matched automatically.
+convert_review = Check these before creating the playlist:
+convert_ambiguous = Unsure
+convert_missing = Not found
+convert_skip = Leave out
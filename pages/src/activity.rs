@@ -13,7 +13,7 @@ pub fn Activity(library: Signal<Library>, config: Signal<AppConfig>) -> Element
         if is_server {
             ServerLogs { library, config }
         } else {
-            LocalLogs { library, config }
+            LocalLogs { library }
         }
     }
 }
@@ -92,24 +92,11 @@ pub fn Album(
                                 if let Some(aid) = pending_album_id_for_playlist.read().clone() {
                                     let tracks: Vec<_> = {
                                         let lib = library.read();
-                                        if is_server {
-                                            lib.jellyfin_tracks.iter()
-                                                .filter(|t| t.album_id == aid)
-                                                .map(|t| t.path.clone())
-                                                .collect()
-                                        } else {
-                                            let album_title = lib.albums.iter()
-                                                .find(|a| a.id == aid)
-                                                .map(|a| a.title.clone());
-                                            if let Some(title) = album_title {
-                                                lib.tracks.iter()
-                                                    .filter(|t| t.album == title)
-                                                    .map(|t| t.path.clone())
-                                                    .collect()
-                                            } else {
-                                                Vec::new()
-                                            }
-                                        }
+                                        let source = if is_server { &lib.jellyfin_tracks } else { &lib.tracks };
+                                        source.iter()
+                                            .filter(|t| t.album_id == aid)
+                                            .map(|t| t.path.clone())
+                                            .collect()
                                     };
                                     if is_server {
                                         let pid = playlist_id.clone();
@@ -178,24 +165,11 @@ pub fn Album(
                                 if let Some(aid) = pending_album_id_for_playlist.read().clone() {
                                     let tracks: Vec<_> = {
                                         let lib = library.read();
-                                        if is_server {
-                                            lib.jellyfin_tracks.iter()
-                                                .filter(|t| t.album_id == aid)
-                                                .map(|t| t.path.clone())
-                                                .collect()
-                                        } else {
-                                            let album_title = lib.albums.iter()
-                                                .find(|a| a.id == aid)
-                                                .map(|a| a.title.clone());
-                                            if let Some(title) = album_title {
-                                                lib.tracks.iter()
-                                                    .filter(|t| t.album == title)
-                                                    .map(|t| t.path.clone())
-                                                    .collect()
-                                            } else {
-                                                Vec::new()
-                                            }
-                                        }
+                                        let source = if is_server { &lib.jellyfin_tracks } else { &lib.tracks };
+                                        source.iter()
+                                            .filter(|t| t.album_id == aid)
+                                            .map(|t| t.path.clone())
+                                            .collect()
                                     };
                                     if is_server {
                                         let playlist_name = name.clone();
@@ -235,6 +209,8 @@ pub fn Album(
                                                         tracks: item_ids,
                                                         image_tag: None,
                                                         cover_path: None,
+                                                        collage_path: None,
+                                                        uploaded_collage: None,
                                                     });
                                                 }
                                             });
@@ -246,6 +222,7 @@ pub fn Album(
                                             name,
                                             tracks,
                                             cover_path: None,
+                                            collage_path: None,
                                         });
                                     }
                                 }
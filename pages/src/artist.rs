@@ -1,7 +1,7 @@
 use config::{AppConfig, MusicSource};
 use dioxus::prelude::*;
 use player::player;
-use reader::{Library, PlaylistStore};
+use reader::{FavoritesStore, Library, PlaylistStore};
 
 use crate::local::artist::LocalArtist;
 use crate::server::artist::ServerArtist;
@@ -12,6 +12,7 @@ pub fn Artist(
     config: Signal<AppConfig>,
     artist_name: Signal<String>,
     playlist_store: Signal<PlaylistStore>,
+    favorites_store: Signal<FavoritesStore>,
     player: Signal<player::Player>,
     on_navigate: EventHandler<String>,
     mut is_playing: Signal<bool>,
@@ -40,6 +41,7 @@ pub fn Artist(
                             config,
                             artist_name,
                             playlist_store,
+                            favorites_store,
                             on_navigate,
                             queue,
                             current_queue_index,
@@ -50,6 +52,7 @@ pub fn Artist(
                             config,
                             artist_name,
                             playlist_store,
+                            favorites_store,
                             on_navigate,
                             queue,
                             current_queue_index,
@@ -70,6 +73,7 @@ pub fn Artist(
                             config,
                             artist_name,
                             playlist_store,
+                            favorites_store,
                             on_navigate,
                             queue,
                             current_queue_index,
@@ -80,6 +84,7 @@ pub fn Artist(
                             config,
                             artist_name,
                             playlist_store,
+                            favorites_store,
                             on_navigate,
                             queue,
                             current_queue_index,
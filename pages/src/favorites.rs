@@ -1,6 +1,6 @@
-use config::{AppConfig, MusicSource};
+use config::{AppConfig, MusicService, MusicSource};
 use dioxus::prelude::*;
-use reader::{FavoritesStore, Library, PlaylistStore};
+use reader::{Artist, FavoritesStore, Library, PlaylistStore};
 
 use crate::local::favorites::LocalFavorites;
 use crate::server::favorites::ServerFavorites;
@@ -21,9 +21,27 @@ pub fn FavoritesPage(
     mut current_song_progress: Signal<u64>,
     mut queue: Signal<Vec<reader::models::Track>>,
     mut current_queue_index: Signal<usize>,
+    on_select_artist: EventHandler<String>,
 ) -> Element {
     let is_server = config.read().active_source == MusicSource::Server;
 
+    let favorite_artists = use_memo(move || {
+        let lib = library.read();
+        let store = favorites_store.read();
+        let artists = if config.read().active_source == MusicSource::Server {
+            &lib.jellyfin_artists
+        } else {
+            &lib.artists
+        };
+        let mut favorites: Vec<Artist> = artists
+            .iter()
+            .filter(|a| store.is_favorite_artist(&a.id))
+            .cloned()
+            .collect();
+        favorites.sort_by_cached_key(|a| a.sort_key());
+        favorites
+    });
+
     rsx! {
         div {
             class: "p-8 min-h-full",
@@ -34,6 +52,44 @@ pub fn FavoritesPage(
                 h1 { class: "text-3xl font-bold text-white", "{i18n::t(\"favorites\")}" }
             }
 
+            if !favorite_artists().is_empty() {
+                div { class: "mb-10",
+                    h2 { class: "text-xl font-semibold text-white mb-4", "{i18n::t(\"favorite_artists\")}" }
+                    div { class: "flex gap-6 overflow-x-auto pb-2",
+                        for artist in favorite_artists() {
+                            {
+                                let cover_url = artist_image_url(&config.read(), &artist);
+                                let name = artist.name.clone();
+                                rsx! {
+                                    div {
+                                        key: "{artist.id}",
+                                        class: "group cursor-pointer flex flex-col items-center w-32 shrink-0",
+                                        onclick: move |_| on_select_artist.call(name.clone()),
+                                        div { class: "w-32 h-32 rounded-full bg-stone-800 mb-3 overflow-hidden",
+                                            if let Some(url) = cover_url {
+                                                img {
+                                                    src: "{url}",
+                                                    class: "w-full h-full object-cover",
+                                                    decoding: "async",
+                                                    loading: "lazy",
+                                                }
+                                            } else {
+                                                div { class: "w-full h-full flex items-center justify-center text-white/20",
+                                                    i { class: "fa-solid fa-microphone text-4xl" }
+                                                }
+                                            }
+                                        }
+                                        p { class: "text-sm text-white font-medium truncate w-full text-center group-hover:text-indigo-400 transition-colors",
+                                            "{artist.name}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
             if is_server {
                 ServerFavorites {
                     favorites_store,
@@ -54,3 +110,31 @@ pub fn FavoritesPage(
         }
     }
 }
+
+fn artist_image_url(config: &AppConfig, artist: &Artist) -> Option<String> {
+    let path = artist.image_path.as_ref()?;
+    if config.active_source == MusicSource::Server {
+        let server = config.server.as_ref()?;
+        let path_str = path.to_string_lossy();
+        match server.service {
+            MusicService::Jellyfin => utils::jellyfin_image::jellyfin_image_url_from_path(
+                &path_str,
+                &server.url,
+                server.access_token.as_deref(),
+                256,
+                80,
+            ),
+            MusicService::Subsonic | MusicService::Custom => {
+                utils::subsonic_image::subsonic_image_url_from_path(
+                    &path_str,
+                    &server.url,
+                    server.access_token.as_deref(),
+                    256,
+                    80,
+                )
+            }
+        }
+    } else {
+        utils::format_artwork_url_sized(Some(path), 320)
+    }
+}
@@ -11,6 +11,7 @@ pub fn LibraryPage(
     library: Signal<Library>,
     config: Signal<AppConfig>,
     playlist_store: Signal<reader::PlaylistStore>,
+    favorites_store: Signal<reader::FavoritesStore>,
     on_rescan: EventHandler,
     player: Signal<player::Player>,
     mut is_playing: Signal<bool>,
@@ -38,6 +39,7 @@ pub fn LibraryPage(
                 library,
                 config,
                 playlist_store,
+                favorites_store,
                 on_rescan,
                 queue,
             }
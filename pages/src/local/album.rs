@@ -1,5 +1,7 @@
 use components::dots_menu::{DotsMenu, MenuAction};
 use dioxus::prelude::*;
+use hooks::radio::RadioSeed;
+use hooks::use_player_controller::PlayerController;
 use reader::{Library, PlaylistStore};
 
 #[component]
@@ -12,7 +14,8 @@ pub fn LocalAlbum(
     mut show_album_playlist_modal: Signal<bool>,
     mut pending_album_id_for_playlist: Signal<Option<String>>,
 ) -> Element {
-    let local_albums = use_memo(move || {
+    // regular albums first, then compilations under their own heading
+    let album_sections = use_memo(move || {
         let mut albums = library.read().albums.clone();
         albums.sort_by(|a, b| {
             a.title
@@ -21,39 +24,45 @@ pub fn LocalAlbum(
                 .cmp(&b.title.trim().to_lowercase())
         });
 
-        let mut unique_albums = Vec::new();
-        let mut seen_titles = std::collections::HashSet::new();
-        for album in albums {
-            let title_key = album.title.trim().to_lowercase();
-            if seen_titles.insert(title_key) {
-                unique_albums.push(album);
-            }
-        }
-        unique_albums
+        let (compilations, albums): (Vec<_>, Vec<_>) =
+            albums.into_iter().partition(|album| album.compilation);
+        [
+            (None, albums),
+            (Some(i18n::t("compilations")), compilations),
+        ]
+        .into_iter()
+        .filter(|(_, albums)| !albums.is_empty())
+        .collect::<Vec<_>>()
     });
 
+    let mut ctrl = use_context::<PlayerController>();
     let add_all_to_playlist_text = i18n::t("add_all_to_playlist").to_string();
+    let start_radio_text = i18n::t("start_radio").to_string();
     let delete_album_text = i18n::t("delete_album").to_string();
 
     let album_menu_actions = vec![
         MenuAction::new(add_all_to_playlist_text.as_str(), "fa-solid fa-list-music"),
+        MenuAction::new(start_radio_text.as_str(), "fa-solid fa-tower-broadcast"),
         MenuAction::new(delete_album_text.as_str(), "fa-solid fa-trash").destructive(),
     ];
 
     rsx! {
         div {
-            if local_albums().is_empty() {
+            if album_sections().is_empty() {
                 p { class: "text-slate-500", "{i18n::t(\"no_albums_found\")}" }
-            } else {
+            }
+            for (heading, albums) in album_sections() {
+                if let Some(heading) = heading {
+                    h2 { class: "text-2xl font-bold text-white mt-10 mb-4", "{heading}" }
+                }
                 div { class: "grid grid-cols-[repeat(auto-fill,minmax(180px,1fr))] gap-6",
-                    for album in local_albums() {
+                    for album in albums {
                         {
                             let id_for_nav    = album.id.clone();
                             let id_for_menu   = album.id.clone();
                             let id_for_action = album.id.clone();
-                            let title_for_action = album.title.clone();
                             let is_open = open_album_menu.read().as_deref() == Some(&album.id);
-                            let cover_url = utils::format_artwork_url(album.cover_path.as_ref());
+                            let cover_url = utils::format_artwork_url_sized(album.cover_path.as_ref(), 320);
                             rsx! {
                                 div {
                                     key: "{album.id}",
@@ -100,7 +109,6 @@ pub fn LocalAlbum(
                                             anchor: "right".to_string(),
                                             on_action: {
                                                 let id = id_for_action.clone();
-                                                let title = title_for_action.clone();
                                                 move |idx: usize| {
                                                     open_album_menu.set(None);
                                                     match idx {
@@ -108,20 +116,19 @@ pub fn LocalAlbum(
                                                             pending_album_id_for_playlist.set(Some(id.clone()));
                                                             show_album_playlist_modal.set(true);
                                                         }
-                                                        1 => {
+                                                        1 => ctrl.start_radio(RadioSeed::Album(id.clone())),
+                                                        2 => {
                                                             let tracks_to_delete: Vec<_> = library
                                                                 .read()
                                                                 .tracks
                                                                 .iter()
-                                                                .filter(|t| t.album == title)
+                                                                .filter(|t| t.album_id == id)
                                                                 .map(|t| t.path.clone())
                                                                 .collect();
                                                             for path in &tracks_to_delete {
                                                                 let _ = std::fs::remove_file(path);
                                                             }
-                                                            let mut lib = library.write();
-                                                            lib.albums.retain(|a| a.title != title);
-                                                            lib.tracks.retain(|t| t.album != title);
+                                                            library.write().remove_album(&id);
                                                         }
                                                         _ => {}
                                                     }
@@ -3,7 +3,9 @@ use components::playlist_modal::PlaylistModal;
 use components::selection_bar::SelectionBar;
 use config::{AppConfig, ArtistViewOrder};
 use dioxus::prelude::*;
-use reader::{Library, PlaylistStore};
+use hooks::radio::RadioSeed;
+use reader::models::make_artist_id;
+use reader::{Artist, FavoritesStore, Library, PlaylistStore};
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
@@ -13,6 +15,7 @@ pub fn LocalArtist(
     config: Signal<AppConfig>,
     artist_name: Signal<String>,
     playlist_store: Signal<PlaylistStore>,
+    mut favorites_store: Signal<FavoritesStore>,
     on_navigate: EventHandler<String>,
     mut queue: Signal<Vec<reader::models::Track>>,
     mut current_queue_index: Signal<usize>,
@@ -41,44 +44,60 @@ pub fn LocalArtist(
 
     let local_artists = use_memo(move || {
         let lib = library.read();
-        let mut artist_map: HashMap<String, Option<std::path::PathBuf>> = HashMap::new();
+        let mut covers: HashMap<String, Option<PathBuf>> = HashMap::new();
         for album in &lib.albums {
-            artist_map
-                .entry(album.artist.clone())
+            covers
+                .entry(make_artist_id(&album.artist))
                 .or_insert_with(|| album.cover_path.clone());
         }
+        let album_covers: HashMap<&str, &Option<PathBuf>> = lib
+            .albums
+            .iter()
+            .map(|a| (a.id.as_str(), &a.cover_path))
+            .collect();
         for track in &lib.tracks {
-            let cover = lib
-                .albums
-                .iter()
-                .find(|a| a.id == track.album_id)
-                .and_then(|a| a.cover_path.clone());
+            let cover = album_covers
+                .get(track.album_id.as_str())
+                .and_then(|c| (*c).clone());
             for artist in &track.artists {
-                artist_map.entry(artist.clone()).or_insert_with(|| cover.clone());
+                covers
+                    .entry(make_artist_id(artist))
+                    .or_insert_with(|| cover.clone());
             }
         }
-        let mut artists: Vec<_> = artist_map.into_iter().collect();
-        artists.sort_by(|a, b| a.0.to_lowercase().cmp(&b.0.to_lowercase()));
+        let mut artists: Vec<(Artist, Option<PathBuf>)> = lib
+            .artists
+            .iter()
+            .map(|artist| {
+                let image = artist
+                    .image_path
+                    .clone()
+                    .or_else(|| covers.get(&artist.id).cloned().flatten());
+                (artist.clone(), image)
+            })
+            .collect();
+        artists.sort_by_cached_key(|(artist, _)| artist.sort_key());
         artists
     });
 
+    let selected_artist_id = use_memo(move || make_artist_id(&artist_name.read()));
+
     let artist_tracks = use_memo(move || {
         let lib = library.read();
-        let artist = artist_name.read();
-        if artist.is_empty() {
+        if artist_name.read().is_empty() {
             return Vec::new();
         }
-        let artist_lc = artist.to_lowercase();
+        let artist_id = selected_artist_id();
         let artist_album_ids: HashSet<String> = lib
             .albums
             .iter()
-            .filter(|a| a.artist.to_lowercase() == artist_lc)
+            .filter(|a| make_artist_id(&a.artist) == artist_id)
             .map(|a| a.id.clone())
             .collect();
         lib.tracks
             .iter()
             .filter(|t| {
-                t.artists.iter().any(|a| a.to_lowercase() == artist_lc)
+                t.artists.iter().any(|a| make_artist_id(a) == artist_id)
                     || artist_album_ids.contains(&t.album_id)
             })
             .cloned()
@@ -87,28 +106,31 @@ pub fn LocalArtist(
 
     let artist_cover = use_memo(move || {
         let lib = library.read();
-        let artist = artist_name.read();
-        if artist.is_empty() {
+        if artist_name.read().is_empty() {
             return None;
         }
-        let artist_lc = artist.to_lowercase();
-        lib.albums
-            .iter()
-            .find(|a| a.artist.to_lowercase() == artist_lc)
-            .and_then(|album| utils::format_artwork_url(album.cover_path.as_ref()))
+        let artist_id = selected_artist_id();
+        lib.artist(&artist_id)
+            .and_then(|artist| artist.image_path.clone())
+            .or_else(|| {
+                lib.albums
+                    .iter()
+                    .find(|a| make_artist_id(&a.artist) == artist_id)
+                    .and_then(|album| album.cover_path.clone())
+            })
+            .and_then(|path| utils::format_artwork_url(Some(&path)))
     });
 
     let artist_albums = use_memo(move || {
         let lib = library.read();
-        let artist = artist_name.read();
-        if artist.is_empty() {
+        if artist_name.read().is_empty() {
             return Vec::new();
         }
-        let artist_lc = artist.to_lowercase();
+        let artist_id = selected_artist_id();
         let mut albums: Vec<_> = lib
             .albums
             .iter()
-            .filter(|a| a.artist.to_lowercase() == artist_lc)
+            .filter(|a| make_artist_id(&a.artist) == artist_id)
             .cloned()
             .collect();
         albums.sort_by(|a, b| {
@@ -122,7 +144,16 @@ pub fn LocalArtist(
         albums
     });
 
-    let name = artist_name.read().clone();
+    let name = {
+        let lib = library.read();
+        let name = artist_name.read();
+        lib.artist_by_name(&name)
+            .map(|artist| artist.name.clone())
+            .unwrap_or_else(|| name.clone())
+    };
+    let is_favorite = favorites_store
+        .read()
+        .is_favorite_artist(&selected_artist_id());
 
     let mut add_tracks_to_playlist = move |playlist_id: String, paths: Vec<PathBuf>| {
         let mut store = playlist_store.write();
@@ -144,6 +175,7 @@ pub fn LocalArtist(
                 name: playlist_name,
                 tracks: paths,
                 cover_path: None,
+                collage_path: None,
             });
     };
 
@@ -168,11 +200,11 @@ pub fn LocalArtist(
                 div { class: "grid grid-cols-2 sm:grid-cols-3 md:grid-cols-4 lg:grid-cols-5 xl:grid-cols-6 gap-8",
                     for (artist , cover_path) in local_artists() {
                         {
-                            let cover_url = utils::format_artwork_url(cover_path.as_ref());
-                            let art = artist.clone();
+                            let cover_url = utils::format_artwork_url_sized(cover_path.as_ref(), 320);
+                            let art = artist.name.clone();
                             rsx! {
                                 div {
-                                    key: "{artist}",
+                                    key: "{artist.id}",
                                     class: "group cursor-pointer flex flex-col items-center",
                                     style: "content-visibility: auto; contain-intrinsic-size: 0 180px;",
                                     onclick: move |_| artist_name.set(art.clone()),
@@ -185,7 +217,7 @@ pub fn LocalArtist(
                                             }
                                         }
                                     }
-                                    h3 { class: "text-white font-medium truncate text-center w-full group-hover:text-indigo-400 transition-colors", "{artist}" }
+                                    h3 { class: "text-white font-medium truncate text-center w-full group-hover:text-indigo-400 transition-colors", "{artist.name}" }
                                     p { class: "text-xs text-slate-500 uppercase tracking-wider mt-1", "{i18n::t(\"artist\")}" }
                                 }
                             }
@@ -278,16 +310,29 @@ pub fn LocalArtist(
                             }
                         }
 
-                        SortOrderToggle { sort_order }
+                        div { class: "flex items-center justify-end gap-3",
+                            FavoriteArtistButton {
+                                is_favorite,
+                                on_toggle: move |_| {
+                                    favorites_store.write().toggle_artist(selected_artist_id());
+                                },
+                            }
+                            ArtistRadioButton {
+                                on_start: move |_| ctrl.start_radio(RadioSeed::Artist(artist_name())),
+                            }
+                            SortOrderToggle { sort_order }
+                        }
 
                         if artist_albums().is_empty() {
                             p { class: "text-slate-500", "{i18n::t(\"no_albums_found\")}" }
                         } else {
                             {
                                 let add_all_to_playlist_text = i18n::t("add_all_to_playlist").to_string();
+                                let start_radio_text = i18n::t("start_radio").to_string();
                                 let delete_album_text = i18n::t("delete_album").to_string();
                                 let album_menu_actions = vec![
                                     MenuAction::new(add_all_to_playlist_text.as_str(), "fa-solid fa-list-music"),
+                                    MenuAction::new(start_radio_text.as_str(), "fa-solid fa-tower-broadcast"),
                                     MenuAction::new(delete_album_text.as_str(), "fa-solid fa-trash").destructive(),
                                 ];
                                 rsx! {
@@ -297,7 +342,7 @@ pub fn LocalArtist(
                                                 let id_for_menu = album.id.clone();
                                                 let id_for_action = album.id.clone();
                                                 let is_open = open_album_menu.read().as_deref() == Some(&album.id);
-                                                let cover_url = utils::format_artwork_url(album.cover_path.as_ref());
+                                                let cover_url = utils::format_artwork_url_sized(album.cover_path.as_ref(), 320);
                                                 rsx! {
                                                     div {
                                                         key: "{album.id}",
@@ -347,7 +392,8 @@ pub fn LocalArtist(
                                                                                 pending_album_id_for_playlist.set(Some(id.clone()));
                                                                                 show_album_playlist_modal.set(true);
                                                                             }
-                                                                            1 => {
+                                                                            1 => ctrl.start_radio(RadioSeed::Album(id.clone())),
+                                                                            2 => {
                                                                                 let tracks_to_delete: Vec<_> = library
                                                                                     .read()
                                                                                     .tracks
@@ -440,7 +486,18 @@ pub fn LocalArtist(
                                     active_menu_track.set(None);
                                 },
                                 actions: Some(rsx! {
-                                    SortOrderToggle { sort_order }
+                                    div { class: "flex items-center gap-3",
+                                        FavoriteArtistButton {
+                                            is_favorite,
+                                            on_toggle: move |_| {
+                                                favorites_store.write().toggle_artist(selected_artist_id());
+                                            },
+                                        }
+                                        ArtistRadioButton {
+                                            on_start: move |_| ctrl.start_radio(RadioSeed::Artist(artist_name())),
+                                        }
+                                        SortOrderToggle { sort_order }
+                                    }
                                 }),
                             }
                         }
@@ -451,6 +508,40 @@ pub fn LocalArtist(
     }
 }
 
+#[component]
+pub(crate) fn FavoriteArtistButton(is_favorite: bool, on_toggle: EventHandler<()>) -> Element {
+    let title = if is_favorite {
+        i18n::t("remove_from_favorites")
+    } else {
+        i18n::t("add_to_favorites")
+    };
+
+    rsx! {
+        button {
+            class: "mb-4 w-8 h-8 flex items-center justify-center rounded-full bg-white/5 hover:bg-white/10 transition-colors",
+            title: "{title}",
+            onclick: move |_| on_toggle.call(()),
+            if is_favorite {
+                i { class: "fa-solid fa-heart text-red-400" }
+            } else {
+                i { class: "fa-regular fa-heart text-white/60" }
+            }
+        }
+    }
+}
+
+#[component]
+pub(crate) fn ArtistRadioButton(on_start: EventHandler<()>) -> Element {
+    rsx! {
+        button {
+            class: "mb-4 w-8 h-8 flex items-center justify-center rounded-full bg-white/5 hover:bg-white/10 transition-colors",
+            title: i18n::t("start_radio"),
+            onclick: move |_| on_start.call(()),
+            i { class: "fa-solid fa-tower-broadcast text-white/60" }
+        }
+    }
+}
+
 #[component]
 fn SortOrderToggle(mut sort_order: Signal<ArtistViewOrder>) -> Element {
     let is_tracks = *sort_order.read() == ArtistViewOrder::Tracks;
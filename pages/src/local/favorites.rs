@@ -37,7 +37,7 @@ pub fn LocalFavorites(
                     .iter()
                     .find(|a| a.id == t.album_id)
                     .and_then(|a| a.cover_path.as_ref())
-                    .and_then(|cp| utils::format_artwork_url(Some(cp)));
+                    .and_then(|cp| utils::format_artwork_url_sized(Some(cp), 96));
                 (t.clone(), cover_url)
             })
             .collect()
@@ -161,6 +161,7 @@ pub fn LocalFavorites(
                             name,
                             tracks,
                             cover_path: None,
+                            collage_path: None,
                         });
                         show_playlist_modal.set(false);
                         active_menu_track.set(None);
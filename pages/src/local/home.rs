@@ -199,7 +199,7 @@ pub fn LocalHome(
                                             move |_| on_select_album.call(id.clone())
                                         },
                                         div { class: "w-16 h-16 md:w-20 md:h-20 flex-shrink-0 bg-stone-800/50 relative overflow-hidden",
-                                            if let Some(url) = utils::format_artwork_url(album.cover_path.as_ref()) {
+                                            if let Some(url) = utils::format_artwork_url_sized(album.cover_path.as_ref(), 320) {
                                                 img { src: "{url}", class: "w-full h-full object-cover group-hover:scale-105 transition-transform duration-500" }
                                             } else {
                                                 div { class: "w-full h-full flex items-center justify-center",
@@ -264,7 +264,7 @@ pub fn LocalHome(
                                 },
                                 div { class: "aspect-square rounded-full bg-stone-800/80 mb-4 overflow-hidden transition-all duration-300 relative mx-auto",
                                     if let Some(path) = cover_path {
-                                        if let Some(url) = utils::format_artwork_url(Some(&path)) {
+                                        if let Some(url) = utils::format_artwork_url_sized(Some(&path), 320) {
                                             img { src: "{url}", class: "w-full h-full object-cover group-hover:scale-110 transition-transform duration-700" }
                                         }
                                     } else {
@@ -309,7 +309,7 @@ pub fn LocalHome(
                                     move |_| on_select_album.call(id.clone())
                                 },
                                 div { class: "aspect-square rounded-2xl bg-stone-800/80 mb-4 overflow-hidden transition-all duration-300 relative",
-                                    if let Some(url) = utils::format_artwork_url(album.cover_path.as_ref()) {
+                                    if let Some(url) = utils::format_artwork_url_sized(album.cover_path.as_ref(), 320) {
                                         img { src: "{url}", class: "w-full h-full object-cover group-hover:scale-105 transition-transform duration-500" }
                                     } else {
                                         div { class: "w-full h-full flex items-center justify-center border border-white/5 rounded-2xl",
@@ -376,7 +376,7 @@ pub fn LocalHome(
                                                 .iter()
                                                 .find(|a| a.id == t.album_id)
                                                 .and_then(|a| a.cover_path.as_ref())
-                                                .and_then(|cp| utils::format_artwork_url(Some(cp)))
+                                                .and_then(|cp| utils::format_artwork_url_sized(Some(cp), 320))
                                         })
                                 } else {
                                     None
@@ -1,3 +1,5 @@
+use components::duplicates::DuplicatesReport;
+use components::library_toolbar::LibraryToolbar;
 use components::playlist_modal::PlaylistModal;
 use components::selection_bar::SelectionBar;
 use components::stat_card::StatCard;
@@ -17,12 +19,15 @@ pub fn LocalLibrary(
     library: Signal<Library>,
     config: Signal<AppConfig>,
     playlist_store: Signal<reader::PlaylistStore>,
+    favorites_store: Signal<reader::FavoritesStore>,
     on_rescan: EventHandler,
     mut queue: Signal<Vec<reader::models::Track>>,
 ) -> Element {
     let items = use_library_items(library);
-    let mut sort_order = items.sort_order;
+    let sort_order = items.sort_order;
+    let filter_query = items.filter_query;
     let mut scroll_stat = use_signal(|| 0.0);
+    let mut show_duplicates = use_signal(|| false);
     let mut container_height = use_signal(|| f64::NAN); // Set on mount
 
     use_effect(move || {
@@ -212,6 +217,7 @@ div {
                             name,
                             tracks,
                             cover_path: None,
+                            collage_path: None,
                         });
                         show_playlist_modal.set(false);
                         active_menu_track.set(None);
@@ -247,11 +253,29 @@ div {
             div {
                 class: "flex items-center justify-between mb-6",
                 h1 { class: "text-3xl font-bold text-white", "{i18n::t(\"your_library\")}" }
-                button {
-                    class: "text-white/60 hover:text-white transition-colors p-2 rounded-full hover:bg-white/10",
-                    title: i18n::t("rescan_library").to_string(),
-                    onclick: move |_| on_rescan.call(()),
-                    i { class: "fa-solid fa-rotate" }
+                div { class: "flex items-center gap-1",
+                    button {
+                        class: "text-white/60 hover:text-white transition-colors p-2 rounded-full hover:bg-white/10",
+                        title: i18n::t("find_duplicates").to_string(),
+                        onclick: move |_| show_duplicates.set(true),
+                        i { class: "fa-solid fa-clone" }
+                    }
+                    button {
+                        class: "text-white/60 hover:text-white transition-colors p-2 rounded-full hover:bg-white/10",
+                        title: i18n::t("rescan_library").to_string(),
+                        onclick: move |_| on_rescan.call(()),
+                        i { class: "fa-solid fa-rotate" }
+                    }
+                }
+            }
+
+            if show_duplicates() {
+                DuplicatesReport {
+                    library,
+                    config,
+                    playlist_store,
+                    favorites_store,
+                    on_close: move |_| show_duplicates.set(false),
                 }
             }
 
@@ -275,36 +299,7 @@ div {
             div {
                 class: "flex items-center justify-between mb-4",
                 h2 { class: "text-xl font-semibold text-white/80", "{i18n::t(\"tracks\")}" }
-                div {
-                    class: "flex space-x-1 bg-white/5 border border-white/5 p-1 rounded-lg",
-                    button {
-                        class: if *sort_order.read() == config::SortOrder::Title {
-                            "px-3 py-1 text-xs rounded-md bg-white/10 text-white font-medium transition-all"
-                        } else {
-                            "px-3 py-1 text-xs rounded-md text-white/40 hover:text-white/80 transition-all"
-                        },
-                        onclick: move |_| sort_order.set(config::SortOrder::Title),
-                        "{i18n::t(\"title\")}"
-                    }
-                    button {
-                        class: if *sort_order.read() == config::SortOrder::Artist {
-                            "px-3 py-1 text-xs rounded-md bg-white/10 text-white font-medium transition-all"
-                        } else {
-                            "px-3 py-1 text-xs rounded-md text-white/40 hover:text-white/80 transition-all"
-                        },
-                        onclick: move |_| sort_order.set(config::SortOrder::Artist),
-                        "{i18n::t(\"artist\")}"
-                    }
-                    button {
-                        class: if *sort_order.read() == config::SortOrder::Album {
-                            "px-3 py-1 text-xs rounded-md bg-white/10 text-white font-medium transition-all"
-                        } else {
-                            "px-3 py-1 text-xs rounded-md text-white/40 hover:text-white/80 transition-all"
-                        },
-                        onclick: move |_| sort_order.set(config::SortOrder::Album),
-                        "{i18n::t(\"album\")}"
-                    }
-                }
+                LibraryToolbar { sort_order, filter_query }
             }
 
             div {
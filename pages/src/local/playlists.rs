@@ -69,10 +69,13 @@ pub fn LocalPlaylists(
     let cover_for = |pid: &str| -> Option<String> {
         let store = playlist_store.read();
         let playlist = store.playlists.iter().find(|p| p.id == pid)?;
+        if let Some(path) = playlist.cover_path.as_ref().or(playlist.collage_path.as_ref()) {
+            return utils::format_artwork_url_sized(Some(path), 320);
+        }
         let first_path = playlist.tracks.first()?;
         let track = lib.tracks.iter().find(|t| t.path == *first_path)?;
         let album = lib.albums.iter().find(|a| a.id == track.album_id)?;
-        utils::format_artwork_url(album.cover_path.as_ref())
+        utils::format_artwork_url_sized(album.cover_path.as_ref(), 320)
     };
 
     rsx! {
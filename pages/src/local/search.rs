@@ -25,6 +25,7 @@ pub fn LocalSearch(
     current_song_progress: Signal<u64>,
     queue: Signal<Vec<reader::models::Track>>,
     current_queue_index: Signal<usize>,
+    on_select_artist: EventHandler<String>,
 ) -> Element {
     let data = use_search_data(library, search_query, config);
     let mut selected_genre = use_signal(|| None::<String>);
@@ -55,7 +56,7 @@ pub fn LocalSearch(
                     let cover = album_map
                         .get(&track.album_id)
                         .and_then(|a| a.cover_path.as_ref())
-                        .and_then(|c| utils::format_artwork_url(Some(c)));
+                        .and_then(|c| utils::format_artwork_url_sized(Some(c), 96));
                     matching_tracks.push((track.clone(), cover));
                 }
             }
@@ -94,6 +95,7 @@ pub fn LocalSearch(
                                 name,
                                 tracks: vec![path],
                                 cover_path: None,
+                                collage_path: None,
                             });
                         }
                         show_playlist_modal.set(false);
@@ -126,11 +128,12 @@ pub fn LocalSearch(
             } else {
                 SearchBar { search_query: data.search_query }
 
-                if let Some((tracks, albums)) = (data.search_results)() {
+                if let Some((tracks, albums, artists)) = (data.search_results)() {
                     SearchResults {
                         search_query: data.search_query.read().clone(),
                         tracks: tracks.clone(),
                         albums: albums.clone(),
+                        artists: artists.clone(),
                         library,
                         playlist_store,
                         player,
@@ -145,6 +148,7 @@ pub fn LocalSearch(
                         active_menu_track,
                         show_playlist_modal,
                         selected_track_for_playlist,
+                        on_select_artist,
                     }
                 } else {
                     SearchGenres {
@@ -1,10 +1,13 @@
 use components::folder_detail::FolderDetail;
 use components::playlist_detail::PlaylistDetail;
+use components::playlist_files::{PlaylistFileReport, PlaylistFileReportModal};
 use components::playlist_popups::AddPlaylistPopup;
+use components::smart_playlist::{SmartPlaylistDetail, SmartPlaylistEditor, SmartPlaylistGrid};
 use config::{AppConfig, MusicService, MusicSource};
 use dioxus::prelude::*;
 use player::player;
-use reader::{Library, PlaylistStore};
+use reader::smart::SmartPlaylist;
+use reader::{FavoritesStore, Library, PlaylistStore};
 use ::server::jellyfin::JellyfinClient;
 use ::server::subsonic::SubsonicClient;
 
@@ -14,6 +17,7 @@ use crate::server::playlists::ServerPlaylists;
 #[component]
 pub fn PlaylistsPage(
     playlist_store: Signal<PlaylistStore>,
+    favorites_store: Signal<FavoritesStore>,
     library: Signal<Library>,
     config: Signal<AppConfig>,
     player: Signal<player::Player>,
@@ -36,6 +40,9 @@ pub fn PlaylistsPage(
     let mut error = use_signal(|| Option::<String>::None);
     let mut saving = use_signal(|| false);
     let mut playlist_refresh_trigger = use_signal(|| 0u64);
+    let mut selected_smart_id = use_signal(|| Option::<String>::None);
+    let mut editing_smart = use_signal(|| Option::<SmartPlaylist>::None);
+    let mut file_report = use_signal(|| Option::<PlaylistFileReport>::None);
 
     let handle_add_playlist = move |_| {
         if saving() { return; }
@@ -85,6 +92,7 @@ pub fn PlaylistsPage(
                 name,
                 tracks: Vec::new(),
                 cover_path: None,
+                collage_path: None,
             });
             show_add_playlist.set(false);
             playlist_name.set(String::new());
@@ -94,6 +102,7 @@ pub fn PlaylistsPage(
     let mut last_source = use_signal(|| config.read().active_source.clone());
     if *last_source.read() != config.read().active_source {
         selected_playlist_id.set(None);
+        selected_smart_id.set(None);
         last_source.set(config.read().active_source.clone());
     }
 
@@ -101,7 +110,38 @@ pub fn PlaylistsPage(
         div {
             class: "p-8",
 
-            if let Some(folder_path) = selected_folder.read().clone() {
+            if let Some(smart) = editing_smart.read().clone() {
+                SmartPlaylistEditor {
+                    playlist_store,
+                    playlist: smart,
+                    on_close: move |_| editing_smart.set(None),
+                    on_delete: move |_| {
+                        editing_smart.set(None);
+                        selected_smart_id.set(None);
+                    },
+                }
+            }
+
+            if let Some(report) = file_report.read().clone() {
+                PlaylistFileReportModal {
+                    report,
+                    on_close: move |_| file_report.set(None),
+                }
+            }
+
+            if let Some(smart_id) = selected_smart_id.read().clone() {
+                SmartPlaylistDetail {
+                    key: "{smart_id}",
+                    smart_id: smart_id.clone(),
+                    playlist_store,
+                    library,
+                    config,
+                    favorites_store,
+                    queue,
+                    on_edit: move |smart| editing_smart.set(Some(smart)),
+                    on_close: move |_| selected_smart_id.set(None),
+                }
+            } else if let Some(folder_path) = selected_folder.read().clone() {
                 FolderDetail {
                     folder_path,
                     library,
@@ -146,6 +186,31 @@ pub fn PlaylistsPage(
                                 },
                                 i { class: "fa-solid fa-folder-plus" }
                             }
+                            button {
+                                class: "text-white/60 flex items-center hover:text-white transition-colors p-3 rounded-full hover:bg-white/10",
+                                title: i18n::t("import_playlist").to_string(),
+                                onclick: move |_| {
+                                    #[cfg(not(target_arch = "wasm32"))]
+                                    spawn(async move {
+                                        if let Some(report) = components::playlist_files::import_playlist_file(library, playlist_store).await {
+                                            file_report.set(Some(report));
+                                        }
+                                    });
+                                },
+                                i { class: "fa-solid fa-file-import" }
+                            }
+                        }
+                        button {
+                            class: "text-white/60 flex items-center hover:text-white transition-colors p-3 rounded-full hover:bg-white/10",
+                            title: i18n::t("new_smart_playlist").to_string(),
+                            onclick: move |_| {
+                                editing_smart.set(Some(SmartPlaylist::new(
+                                    uuid::Uuid::new_v4().to_string(),
+                                    i18n::t("smart_playlist").to_string(),
+                                    is_server,
+                                )));
+                            },
+                            i { class: "fa-solid fa-wand-magic-sparkles" }
                         }
                         button {
                             class: "text-white/60 flex items-center hover:text-white transition-colors p-3 rounded-full hover:bg-white/10",
@@ -165,6 +230,12 @@ pub fn PlaylistsPage(
                     }
                 }
 
+                SmartPlaylistGrid {
+                    playlist_store,
+                    server: is_server,
+                    on_select: move |id| selected_smart_id.set(Some(id)),
+                }
+
                 if is_server {
                     ServerPlaylists {
                         playlist_store,
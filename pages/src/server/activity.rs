@@ -1,7 +1,8 @@
+use components::listen_summary::ListenSummary;
 use config::{AppConfig, MusicService};
 use dioxus::prelude::*;
 use hooks::use_player_controller::PlayerController;
-use reader::Library;
+use reader::{HistoryStore, Library};
 
 fn format_duration(seconds: u64) -> String {
     let minutes = seconds / 60;
@@ -12,24 +13,17 @@ fn format_duration(seconds: u64) -> String {
 #[component]
 pub fn JellyfinLogs(library: Signal<Library>, config: Signal<AppConfig>) -> Element {
     let mut ctrl = use_context::<PlayerController>();
+    let history = use_context::<Signal<HistoryStore>>();
 
     let sorted_tracks = use_memo(move || {
         let lib = library.read();
-        let conf = config.read();
+        let history = history.read();
 
         let mut all_tracks = lib.jellyfin_tracks.clone();
 
         all_tracks.sort_by(|a, b| {
-            let a_plays = conf
-                .listen_counts
-                .get(&a.path.to_string_lossy().to_string())
-                .copied()
-                .unwrap_or(0);
-            let b_plays = conf
-                .listen_counts
-                .get(&b.path.to_string_lossy().to_string())
-                .copied()
-                .unwrap_or(0);
+            let a_plays = history.plays(&a.path.to_string_lossy());
+            let b_plays = history.plays(&b.path.to_string_lossy());
 
             match b_plays.cmp(&a_plays) {
                 std::cmp::Ordering::Equal => a.title.cmp(&b.title),
@@ -41,6 +35,7 @@ pub fn JellyfinLogs(library: Signal<Library>, config: Signal<AppConfig>) -> Elem
     });
 
     let conf = config.read();
+    let listen_history = history.read();
 
     rsx! {
         div { class: "p-8 h-full overflow-y-auto w-full",
@@ -57,6 +52,8 @@ pub fn JellyfinLogs(library: Signal<Library>, config: Signal<AppConfig>) -> Elem
                     }
                 }
 
+                ListenSummary { server: true }
+
                 div { class: "flex items-center px-4 py-3 mb-2 text-xs font-semibold tracking-wider text-slate-400 uppercase border-b border-white/10",
                     div { class: "w-12 shrink-0 text-center", "#" }
                     div { class: "flex-1 min-w-0 pl-14 pr-4", "{i18n::t(\"title\")}" }
@@ -70,7 +67,12 @@ pub fn JellyfinLogs(library: Signal<Library>, config: Signal<AppConfig>) -> Elem
                     for (idx, track) in sorted_tracks.read().iter().enumerate() {
                         {
                             let track_id = track.path.to_string_lossy().to_string();
-                            let plays = conf.listen_counts.get(&track_id).copied().unwrap_or(0);
+                            let plays = listen_history.plays(&track_id);
+                            let skip_title = listen_history
+                                .skip_rate(&track_id)
+                                .map(|rate| format!("{:.0}", rate * 100.0))
+                                .map(|rate| i18n::t_with("skip_rate_of_track", &[("rate", rate)]))
+                                .unwrap_or_default();
 
                             let genre = library.read().jellyfin_albums.iter()
                                 .find(|a| a.id == track.album_id)
@@ -143,6 +145,7 @@ pub fn JellyfinLogs(library: Signal<Library>, config: Signal<AppConfig>) -> Elem
                                     }
 
                                     div { class: "w-24 shrink-0 text-right text-slate-400 text-sm tabular-nums group-hover:text-slate-300 transition-colors flex items-center justify-end gap-2",
+                                        title: "{skip_title}",
                                         if plays > 0 {
                                             i { class: "fa-solid fa-fire text-orange-500/80 text-[10px]" }
                                         }
@@ -1,15 +1,48 @@
 use components::dots_menu::{DotsMenu, MenuAction};
 use components::playlist_modal::PlaylistModal;
 use components::selection_bar::SelectionBar;
+use components::showcase::{DiscHeader, spans_several_discs};
+use components::star_rating::{StarRating, rate_album};
 use components::track_row::TrackRow;
 use config::{AppConfig, MusicService};
 use dioxus::prelude::*;
-use reader::{Library, PlaylistStore};
+use hooks::radio::RadioSeed;
+use reader::models::server_item_id;
+use reader::offline::{CollectionKind, OfflineCollection};
+use reader::{FavoritesStore, Library, OfflineStore, PlaylistStore};
 use ::server::jellyfin::JellyfinClient;
 use ::server::subsonic::SubsonicClient;
 use std::collections::HashSet;
 use std::path::PathBuf;
 
+/// Marks a server album available offline, or drops the mark again.
+fn toggle_album_offline(
+    library: Signal<Library>,
+    mut offline: Signal<OfflineStore>,
+    album_id: &str,
+) {
+    if offline.write().remove_collection(CollectionKind::Album, album_id) {
+        return;
+    }
+    let lib = library.read();
+    let Some(album) = lib.jellyfin_albums.iter().find(|a| a.id == album_id) else {
+        return;
+    };
+    let tracks = lib
+        .jellyfin_tracks
+        .iter()
+        .filter(|t| t.album == album.title)
+        .filter_map(|t| server_item_id(&t.path.to_string_lossy()).map(str::to_string))
+        .collect();
+    offline.write().add_collection(OfflineCollection {
+        kind: CollectionKind::Album,
+        id: album_id.to_string(),
+        name: album.title.clone(),
+        tracks,
+        pinned: true,
+    });
+}
+
 #[component]
 pub fn JellyfinAlbum(
     library: Signal<Library>,
@@ -69,13 +102,17 @@ pub fn JellyfinAlbum(
             .collect::<Vec<_>>()
     });
 
+    let mut ctrl = use_context::<hooks::use_player_controller::PlayerController>();
+    let offline = use_context::<Signal<OfflineStore>>();
     let add_all_to_playlist_text = i18n::t("add_all_to_playlist").to_string();
+    let start_radio_text = i18n::t("start_radio").to_string();
+    let available_offline_text = i18n::t("available_offline").to_string();
+    let remove_download_text = i18n::t("remove_download").to_string();
     let remove_from_cache_text = i18n::t("remove_from_cache").to_string();
 
-    let album_menu_actions = vec![
-        MenuAction::new(add_all_to_playlist_text.as_str(), "fa-solid fa-list-music"),
-        MenuAction::new(remove_from_cache_text.as_str(), "fa-solid fa-trash").destructive(),
-    ];
+    // downloads are desktop only
+    let offline_idx = (!cfg!(target_arch = "wasm32")).then_some(2);
+    let remove_idx = if offline_idx.is_some() { 3 } else { 2 };
 
     rsx! {
         div {
@@ -89,6 +126,24 @@ pub fn JellyfinAlbum(
                             let id_for_menu   = album_id_val.clone();
                             let id_for_action = album_id_val.clone();
                             let is_open = open_album_menu.read().as_deref() == Some(&album_id_val);
+                            let mut album_menu_actions = vec![
+                                MenuAction::new(add_all_to_playlist_text.as_str(), "fa-solid fa-list-music"),
+                                MenuAction::new(start_radio_text.as_str(), "fa-solid fa-tower-broadcast"),
+                            ];
+                            if offline_idx.is_some() {
+                                let is_offline = offline
+                                    .read()
+                                    .collection(CollectionKind::Album, &album_id_val)
+                                    .is_some();
+                                album_menu_actions.push(if is_offline {
+                                    MenuAction::new(remove_download_text.as_str(), "fa-solid fa-circle-xmark")
+                                } else {
+                                    MenuAction::new(available_offline_text.as_str(), "fa-solid fa-circle-down")
+                                });
+                            }
+                            album_menu_actions.push(
+                                MenuAction::new(remove_from_cache_text.as_str(), "fa-solid fa-trash").destructive(),
+                            );
                             rsx! {
                                 div {
                                     key: "{album_id_val}",
@@ -123,7 +178,7 @@ pub fn JellyfinAlbum(
                                     div {
                                         class: "absolute bottom-3 right-3",
                                         DotsMenu {
-                                            actions: album_menu_actions.clone(),
+                                            actions: album_menu_actions,
                                             is_open,
                                             on_open: {
                                                 let id = id_for_menu.clone();
@@ -141,7 +196,11 @@ pub fn JellyfinAlbum(
                                                             pending_album_id_for_playlist.set(Some(id.clone()));
                                                             show_album_playlist_modal.set(true);
                                                         }
-                                                        1 => {
+                                                        1 => ctrl.start_radio(RadioSeed::Album(id.clone())),
+                                                        i if Some(i) == offline_idx => {
+                                                            toggle_album_offline(library, offline, &id);
+                                                        }
+                                                        i if i == remove_idx => {
                                                             let mut lib = library.write();
                                                             let title = lib.jellyfin_albums.iter()
                                                                 .find(|a| a.id == id)
@@ -177,6 +236,7 @@ pub fn JellyfinAlbumDetails(
     on_close: EventHandler<()>,
 ) -> Element {
     let mut ctrl = use_context::<hooks::use_player_controller::PlayerController>();
+    let favorites_store = use_context::<Signal<FavoritesStore>>();
     let mut active_menu_track = use_signal(|| None::<PathBuf>);
     let mut show_playlist_modal = use_signal(|| false);
     let mut selected_track_for_playlist = use_signal(|| None::<PathBuf>);
@@ -243,9 +303,13 @@ pub fn JellyfinAlbumDetails(
 
     let album = album_info();
     let album_title = album.as_ref().map(|a| a.title.clone()).unwrap_or_default();
+    let album_rating = album
+        .as_ref()
+        .map(|a| favorites_store.read().album_rating(&a.id));
     let artist = album.as_ref().map(|a| a.artist.clone()).unwrap_or_default();
 
     let total_seconds: u64 = album_tracks().iter().map(|(t, _)| t.duration).sum();
+    let show_discs = spans_several_discs(album_tracks().iter().map(|(t, _)| t));
     let duration_min = total_seconds / 60;
 
     let songs_text = i18n::t("songs").to_string();
@@ -421,6 +485,20 @@ pub fn JellyfinAlbumDetails(
                     i { class: "fa-solid fa-arrow-left" }
                     "{i18n::t(\"back_to_albums\")}"
                 }
+                if let Some(rating) = album_rating {
+                    div {
+                        title: i18n::t("album_rating"),
+                        StarRating {
+                            rating,
+                            size: "text-base",
+                            on_rate: move |stars: u8| {
+                                if let Some(album) = album_info() {
+                                    rate_album(album.id, stars, favorites_store, config);
+                                }
+                            },
+                        }
+                    }
+                }
             }
 
             div {
@@ -478,6 +556,9 @@ pub fn JellyfinAlbumDetails(
                     for (idx, (track, track_cover_url)) in album_tracks().into_iter().enumerate() {
                         {
                             let track_key = track.path.display().to_string();
+                            let disc_header = (show_discs
+                                && (idx == 0 || album_tracks.read()[idx - 1].0.disc_number != track.disc_number))
+                                .then(|| track.disc_number.unwrap_or(1));
                             let track_menu = track.clone();
                             let track_add  = track.clone();
                             let track_path = track.path.clone();
@@ -486,46 +567,51 @@ pub fn JellyfinAlbumDetails(
                             let album_queue: Vec<reader::models::Track> =
                                 album_tracks().iter().map(|(t, _)| t.clone()).collect();
                             rsx! {
-                                TrackRow {
+                                div {
                                     key: "{track_key}",
-                                    track: track.clone(),
-                                    cover_url: track_cover_url,
-                                    is_menu_open,
-                                    is_selection_mode: is_selection_mode(),
-                                    is_selected: selected_tracks.read().contains(&track_path),
-                                    on_long_press: move |_| {
-                                        is_selection_mode.set(true);
-                                        selected_tracks.write().insert(track_path.clone());
-                                    },
-                                    on_select: move |selected| {
-                                        if selected {
-                                            selected_tracks.write().insert(track_select.clone());
-                                        } else {
-                                            selected_tracks.write().remove(&track_select);
-                                            if selected_tracks.read().is_empty() {
-                                                is_selection_mode.set(false);
+                                    if let Some(disc) = disc_header {
+                                        DiscHeader { disc }
+                                    }
+                                    TrackRow {
+                                        track: track.clone(),
+                                        cover_url: track_cover_url,
+                                        is_menu_open,
+                                        is_selection_mode: is_selection_mode(),
+                                        is_selected: selected_tracks.read().contains(&track_path),
+                                        on_long_press: move |_| {
+                                            is_selection_mode.set(true);
+                                            selected_tracks.write().insert(track_path.clone());
+                                        },
+                                        on_select: move |selected| {
+                                            if selected {
+                                                selected_tracks.write().insert(track_select.clone());
+                                            } else {
+                                                selected_tracks.write().remove(&track_select);
+                                                if selected_tracks.read().is_empty() {
+                                                    is_selection_mode.set(false);
+                                                }
                                             }
-                                        }
-                                    },
-                                    on_click_menu: move |_| {
-                                        if active_menu_track.read().as_ref() == Some(&track_menu.path) {
+                                        },
+                                        on_click_menu: move |_| {
+                                            if active_menu_track.read().as_ref() == Some(&track_menu.path) {
+                                                active_menu_track.set(None);
+                                            } else {
+                                                active_menu_track.set(Some(track_menu.path.clone()));
+                                            }
+                                        },
+                                        on_add_to_playlist: move |_| {
+                                            selected_track_for_playlist.set(Some(track_add.path.clone()));
+                                            show_playlist_modal.set(true);
                                             active_menu_track.set(None);
-                                        } else {
-                                            active_menu_track.set(Some(track_menu.path.clone()));
-                                        }
-                                    },
-                                    on_add_to_playlist: move |_| {
-                                        selected_track_for_playlist.set(Some(track_add.path.clone()));
-                                        show_playlist_modal.set(true);
-                                        active_menu_track.set(None);
-                                    },
-                                    on_close_menu: move |_| active_menu_track.set(None),
-                                    on_play: move |_| {
-                                        queue.set(album_queue.clone());
-                                        ctrl.play_track(idx);
-                                    },
-                                    on_delete: move |_| active_menu_track.set(None),
-                                    hide_delete: true,
+                                        },
+                                        on_close_menu: move |_| active_menu_track.set(None),
+                                        on_play: move |_| {
+                                            queue.set(album_queue.clone());
+                                            ctrl.play_track(idx);
+                                        },
+                                        on_delete: move |_| active_menu_track.set(None),
+                                        hide_delete: true,
+                                    }
                                 }
                             }
                         }
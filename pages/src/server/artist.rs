@@ -1,3 +1,4 @@
+use crate::local::artist::{ArtistRadioButton, FavoriteArtistButton};
 use ::server::jellyfin::JellyfinClient;
 use ::server::subsonic::SubsonicClient;
 use components::dots_menu::{DotsMenu, MenuAction};
@@ -5,8 +6,10 @@ use components::playlist_modal::PlaylistModal;
 use components::selection_bar::SelectionBar;
 use config::{AppConfig, ArtistViewOrder, MusicService};
 use dioxus::prelude::*;
-use reader::{Library, PlaylistStore};
-use std::collections::{HashMap, HashSet};
+use hooks::radio::RadioSeed;
+use reader::models::make_artist_id;
+use reader::{Artist, FavoritesStore, Library, PlaylistStore};
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 #[component]
@@ -15,6 +18,7 @@ pub fn JellyfinArtist(
     config: Signal<AppConfig>,
     artist_name: Signal<String>,
     playlist_store: Signal<PlaylistStore>,
+    mut favorites_store: Signal<FavoritesStore>,
     on_navigate: EventHandler<String>,
     mut queue: Signal<Vec<reader::models::Track>>,
     mut current_queue_index: Signal<usize>,
@@ -41,31 +45,25 @@ pub fn JellyfinArtist(
 
     let jellyfin_artists = use_memo(move || {
         let lib = library.read();
-        let mut artist_map: HashMap<String, Option<PathBuf>> = HashMap::new();
-        for album in &lib.jellyfin_albums {
-            if !artist_map.contains_key(&album.artist) {
-                artist_map.insert(album.artist.clone(), album.cover_path.clone());
-            }
-        }
-        for name in artist_map.keys().cloned().collect::<Vec<_>>() {
-            if let Some(url) = lib.server_artist_images.get(&name) {
-                artist_map.insert(name, Some(PathBuf::from(format!("directurl:{}", url))));
-            }
-        }
-        let mut artists: Vec<_> = artist_map.into_iter().collect();
-        artists.sort_by(|a, b| a.0.to_lowercase().cmp(&b.0.to_lowercase()));
+        let mut artists: Vec<Artist> = lib.jellyfin_artists.clone();
+        artists.sort_by_cached_key(|artist| artist.sort_key());
         artists
     });
 
+    let selected_artist_id = use_memo(move || make_artist_id(&artist_name.read()));
+
     let artist_tracks = use_memo(move || {
         let lib = library.read();
-        let artist = artist_name.read();
-        if artist.is_empty() {
+        if artist_name.read().is_empty() {
             return Vec::new();
         }
+        let artist_id = selected_artist_id();
         lib.jellyfin_tracks
             .iter()
-            .filter(|t| t.artist.to_lowercase() == artist.to_lowercase())
+            .filter(|t| {
+                make_artist_id(&t.artist) == artist_id
+                    || t.artists.iter().any(|a| make_artist_id(a) == artist_id)
+            })
             .cloned()
             .collect::<Vec<_>>()
     });
@@ -73,45 +71,35 @@ pub fn JellyfinArtist(
     let artist_cover = use_memo(move || {
         let lib = library.read();
         let conf = config.read();
-        let artist = artist_name.read();
-        if artist.is_empty() {
+        if artist_name.read().is_empty() {
             return None;
         }
-        if let Some(url) = lib.server_artist_images.get(artist.as_str()) {
-            return Some(url.clone());
-        }
-        lib.jellyfin_albums
+        let artist_id = selected_artist_id();
+        let image = lib
+            .jellyfin_artists
             .iter()
-            .find(|a| a.artist.to_lowercase() == artist.to_lowercase())
-            .and_then(|album| {
-                if let Some(server) = &conf.server {
-                    album.cover_path.as_ref().and_then(|cover_path| {
-                        let path_str = cover_path.to_string_lossy();
-                        utils::jellyfin_image::jellyfin_image_url_from_path(
-                            &path_str,
-                            &server.url,
-                            server.access_token.as_deref(),
-                            512,
-                            90,
-                        )
-                    })
-                } else {
-                    None
-                }
-            })
+            .find(|a| a.id == artist_id)
+            .and_then(|a| a.image_path.clone())?;
+        let server = conf.server.as_ref()?;
+        utils::jellyfin_image::jellyfin_image_url_from_path(
+            &image.to_string_lossy(),
+            &server.url,
+            server.access_token.as_deref(),
+            512,
+            90,
+        )
     });
 
     let artist_albums = use_memo(move || {
         let lib = library.read();
-        let artist = artist_name.read();
-        if artist.is_empty() {
+        if artist_name.read().is_empty() {
             return Vec::new();
         }
-        let artist_lc = artist.to_lowercase();
+        let artist_id = selected_artist_id();
         let mut albums: Vec<_> = lib
             .jellyfin_albums
             .iter()
-            .filter(|a| a.artist.to_lowercase() == artist_lc)
+            .filter(|a| make_artist_id(&a.artist) == artist_id)
             .cloned()
             .collect();
         albums.sort_by(|a, b| {
@@ -125,7 +113,16 @@ pub fn JellyfinArtist(
         albums
     });
 
-    let name = artist_name.read().clone();
+    let name = {
+        let lib = library.read();
+        let name = artist_name.read();
+        lib.artist_by_name(&name)
+            .map(|artist| artist.name.clone())
+            .unwrap_or_else(|| name.clone())
+    };
+    let is_favorite = favorites_store
+        .read()
+        .is_favorite_artist(&selected_artist_id());
 
     let tracks_for_album = |library: &Library, album_id: &str| -> Vec<PathBuf> {
         library
@@ -140,10 +137,10 @@ pub fn JellyfinArtist(
         div {
             if name.is_empty() {
                 div { class: "grid grid-cols-2 sm:grid-cols-3 md:grid-cols-4 lg:grid-cols-5 xl:grid-cols-6 gap-8",
-                    for (artist, cover_path) in jellyfin_artists() {
+                    for artist in jellyfin_artists() {
                         {
                             let cover_url = if let Some(server) = &config.read().server {
-                                if let Some(path) = cover_path {
+                                if let Some(path) = &artist.image_path {
                                     let path_str = path.to_string_lossy();
                                     utils::jellyfin_image::jellyfin_image_url_from_path(
                                         &path_str,
@@ -159,10 +156,10 @@ pub fn JellyfinArtist(
                                 None
                             };
 
-                            let art = artist.clone();
+                            let art = artist.name.clone();
                             rsx! {
                                 div {
-                                    key: "{artist}",
+                                    key: "{artist.id}",
                                     class: "group cursor-pointer flex flex-col items-center",
                                     style: "content-visibility: auto; contain-intrinsic-size: 0 180px;",
                                     onclick: move |_| artist_name.set(art.clone()),
@@ -179,7 +176,7 @@ pub fn JellyfinArtist(
                                             }
                                         }
                                     }
-                                    h3 { class: "text-white font-medium truncate text-center w-full group-hover:text-indigo-400 transition-colors", "{artist}" }
+                                    h3 { class: "text-white font-medium truncate text-center w-full group-hover:text-indigo-400 transition-colors", "{artist.name}" }
                                     p { class: "text-xs text-slate-500 uppercase tracking-wider mt-1", "{i18n::t(\"artist\")}" }
                                 }
                             }
@@ -441,7 +438,18 @@ pub fn JellyfinArtist(
                             }
                         }
 
-                        SortOrderToggle { sort_order }
+                        div { class: "flex items-center justify-end gap-3",
+                            FavoriteArtistButton {
+                                is_favorite,
+                                on_toggle: move |_| {
+                                    favorites_store.write().toggle_artist(selected_artist_id());
+                                },
+                            }
+                            ArtistRadioButton {
+                                on_start: move |_| ctrl.start_radio(RadioSeed::Artist(artist_name())),
+                            }
+                            SortOrderToggle { sort_order }
+                        }
 
                         if artist_albums().is_empty() {
                             p { class: "text-slate-500", "{i18n::t(\"no_albums_found\")}" }
@@ -595,7 +603,18 @@ pub fn JellyfinArtist(
                                 },
                                 on_delete_track: move |_| active_menu_track.set(None),
                                 actions: Some(rsx! {
-                                    SortOrderToggle { sort_order }
+                                    div { class: "flex items-center gap-3",
+                                        FavoriteArtistButton {
+                                            is_favorite,
+                                            on_toggle: move |_| {
+                                                favorites_store.write().toggle_artist(selected_artist_id());
+                                            },
+                                        }
+                                        ArtistRadioButton {
+                                            on_start: move |_| ctrl.start_radio(RadioSeed::Artist(artist_name())),
+                                        }
+                                        SortOrderToggle { sort_order }
+                                    }
                                 }),
                             }
                         }
@@ -639,6 +658,7 @@ pub fn ServerArtist(
     config: Signal<AppConfig>,
     artist_name: Signal<String>,
     playlist_store: Signal<PlaylistStore>,
+    favorites_store: Signal<FavoritesStore>,
     on_navigate: EventHandler<String>,
     queue: Signal<Vec<reader::models::Track>>,
     current_queue_index: Signal<usize>,
@@ -655,6 +675,7 @@ pub fn ServerArtist(
                 config,
                 artist_name,
                 playlist_store,
+                favorites_store,
                 on_navigate,
                 queue,
                 current_queue_index,
@@ -666,6 +687,7 @@ pub fn ServerArtist(
                 config,
                 artist_name,
                 playlist_store,
+                favorites_store,
                 on_navigate,
                 queue,
                 current_queue_index,
@@ -677,6 +699,7 @@ pub fn ServerArtist(
                 config,
                 artist_name,
                 playlist_store,
+                favorites_store,
                 on_navigate,
                 queue,
                 current_queue_index,
@@ -691,6 +714,7 @@ pub fn SubsonicArtist(
     config: Signal<AppConfig>,
     artist_name: Signal<String>,
     playlist_store: Signal<PlaylistStore>,
+    favorites_store: Signal<FavoritesStore>,
     on_navigate: EventHandler<String>,
     queue: Signal<Vec<reader::models::Track>>,
     current_queue_index: Signal<usize>,
@@ -701,6 +725,7 @@ pub fn SubsonicArtist(
             config,
             artist_name,
             playlist_store,
+            favorites_store,
             on_navigate,
             queue,
             current_queue_index,
@@ -714,6 +739,7 @@ pub fn CustomArtist(
     config: Signal<AppConfig>,
     artist_name: Signal<String>,
     playlist_store: Signal<PlaylistStore>,
+    favorites_store: Signal<FavoritesStore>,
     on_navigate: EventHandler<String>,
     queue: Signal<Vec<reader::models::Track>>,
     current_queue_index: Signal<usize>,
@@ -724,6 +750,7 @@ pub fn CustomArtist(
             config,
             artist_name,
             playlist_store,
+            favorites_store,
             on_navigate,
             queue,
             current_queue_index,
@@ -1,13 +1,15 @@
 use ::server::jellyfin::JellyfinClient;
 use ::server::subsonic::SubsonicClient;
+use components::library_toolbar::LibraryToolbar;
 use components::playlist_modal::PlaylistModal;
 use components::selection_bar::SelectionBar;
 use components::stat_card::StatCard;
 use components::track_row::TrackRow;
 use config::{AppConfig, MusicService};
 use dioxus::prelude::*;
+use hooks::use_library_items::sort_tracks;
 use hooks::use_player_controller::PlayerController;
-use reader::Library;
+use reader::{Library, TrackFilter};
 use std::collections::HashSet;
 use std::path::PathBuf;
 
@@ -23,7 +25,8 @@ pub fn JellyfinLibrary(
     let mut is_loading = use_signal(|| false);
     let mut has_fetched = use_signal(|| false);
     let mut fetch_generation = use_signal(|| 0usize);
-    let mut sort_order = use_signal(|| config.peek().sort_order.clone());
+    let sort_order = use_signal(|| config.peek().sort_order.clone());
+    let filter_query = use_signal(String::new);
     let mut scroll_stat = use_signal(|| 0.0);
     use_effect(move || {
         let curr = sort_order.read().clone();
@@ -49,6 +52,7 @@ pub fn JellyfinLibrary(
             let mut lib_write = library.write();
             lib_write.jellyfin_tracks.clear();
             lib_write.jellyfin_albums.clear();
+            lib_write.jellyfin_artists.clear();
         }
         spawn(async move {
             if *fetch_generation.read() == current_gen {
@@ -72,35 +76,15 @@ pub fn JellyfinLibrary(
     });
 
     let displayed_tracks = use_memo(move || {
-        let mut tracks = library.read().jellyfin_tracks.clone();
-        match *sort_order.read() {
-            config::SortOrder::Title => tracks.sort_by_cached_key(|a| {
-                (
-                    a.title.to_lowercase(),
-                    a.artist.to_lowercase(),
-                    a.album.to_lowercase(),
-                    a.disc_number,
-                    a.track_number,
-                )
-            }),
-            config::SortOrder::Artist => tracks.sort_by_cached_key(|a| {
-                (
-                    a.artist.to_lowercase(),
-                    a.album.to_lowercase(),
-                    a.disc_number,
-                    a.track_number,
-                    a.title.to_lowercase(),
-                )
-            }),
-            config::SortOrder::Album => tracks.sort_by_cached_key(|a| {
-                (
-                    a.album.to_lowercase(),
-                    a.disc_number,
-                    a.track_number,
-                    a.title.to_lowercase(),
-                )
-            }),
-        }
+        let filter = TrackFilter::parse(&filter_query.read());
+        let mut tracks: Vec<_> = library
+            .read()
+            .jellyfin_tracks
+            .iter()
+            .filter(|t| filter.matches(t))
+            .cloned()
+            .collect();
+        sort_tracks(&mut tracks, &sort_order.read(), |t| t);
         let conf = config.read();
         tracks
             .into_iter()
@@ -426,36 +410,7 @@ pub fn JellyfinLibrary(
             div {
                 class: "flex items-center justify-between mb-4",
                 h2 { class: "text-xl font-semibold text-white/80", "{i18n::t(\"tracks\")}" }
-                div {
-                    class: "flex space-x-1 bg-white/5 border border-white/5 p-1 rounded-lg",
-                    button {
-                        class: if *sort_order.read() == config::SortOrder::Title {
-                            "px-3 py-1 text-xs rounded-md bg-white/10 text-white font-medium transition-all"
-                        } else {
-                            "px-3 py-1 text-xs rounded-md text-white/40 hover:text-white/80 transition-all"
-                        },
-                        onclick: move |_| sort_order.set(config::SortOrder::Title),
-                        "Title"
-                    }
-                    button {
-                        class: if *sort_order.read() == config::SortOrder::Artist {
-                            "px-3 py-1 text-xs rounded-md bg-white/10 text-white font-medium transition-all"
-                        } else {
-                            "px-3 py-1 text-xs rounded-md text-white/40 hover:text-white/80 transition-all"
-                        },
-                        onclick: move |_| sort_order.set(config::SortOrder::Artist),
-                        "Artist"
-                    }
-                    button {
-                        class: if *sort_order.read() == config::SortOrder::Album {
-                            "px-3 py-1 text-xs rounded-md bg-white/10 text-white font-medium transition-all"
-                        } else {
-                            "px-3 py-1 text-xs rounded-md text-white/40 hover:text-white/80 transition-all"
-                        },
-                        onclick: move |_| sort_order.set(config::SortOrder::Album),
-                        "Album"
-                    }
-                }
+                LibraryToolbar { sort_order, filter_query }
             }
 
             div {
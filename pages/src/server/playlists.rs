@@ -76,6 +76,8 @@ pub fn JellyfinPlaylists(
                                     tracks,
                                     image_tag,
                                     cover_path: None,
+                                    collage_path: None,
+                                    uploaded_collage: None,
                                 });
                             } else {
                                 server_playlists.push(reader::models::JellyfinPlaylist {
@@ -84,6 +86,8 @@ pub fn JellyfinPlaylists(
                                     tracks: vec![],
                                     image_tag,
                                     cover_path: None,
+                                    collage_path: None,
+                                    uploaded_collage: None,
                                 });
                             }
                         }
@@ -106,6 +110,8 @@ pub fn JellyfinPlaylists(
                                 tracks,
                                 image_tag: None,
                                 cover_path: None,
+                                collage_path: None,
+                                uploaded_collage: None,
                             });
                         }
                     }
@@ -117,10 +123,12 @@ pub fn JellyfinPlaylists(
             }
 
             let mut store_write = playlist_store.write();
-            // Preserve any locally-set cover_path when replacing server data
+            // Preserve any locally-set cover_path and generated collage when replacing server data
             for p in &mut server_playlists {
                 if let Some(existing) = store_write.jellyfin_playlists.iter().find(|e| e.id == p.id) {
                     p.cover_path = existing.cover_path.clone();
+                    p.collage_path = existing.collage_path.clone();
+                    p.uploaded_collage = existing.uploaded_collage.clone();
                 }
             }
             store_write.jellyfin_playlists = server_playlists;
@@ -142,8 +150,8 @@ pub fn JellyfinPlaylists(
                         let cover_url = {
                             let conf = config.peek();
                             if let Some(server) = &conf.server {
-                                if let Some(path) = &playlist.cover_path {
-                                    utils::format_artwork_url(Some(path))
+                                if let Some(path) = playlist.cover_path.as_ref().or(playlist.collage_path.as_ref()) {
+                                    utils::format_artwork_url_sized(Some(path), 320)
                                 } else if let Some(tag) = &playlist.image_tag {
                                     Some(utils::jellyfin_image::jellyfin_image_url(
                                         &server.url,
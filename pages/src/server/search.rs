@@ -26,6 +26,7 @@ pub fn JellyfinSearch(
     current_song_progress: Signal<u64>,
     queue: Signal<Vec<reader::models::Track>>,
     current_queue_index: Signal<usize>,
+    on_select_artist: EventHandler<String>,
 ) -> Element {
     let data = use_search_data(library, search_query, config);
     let mut selected_genre = use_signal(|| None::<String>);
@@ -202,11 +203,12 @@ pub fn JellyfinSearch(
             } else {
                 SearchBar { search_query: data.search_query }
 
-                if let Some((tracks, albums)) = (data.search_results)() {
+                if let Some((tracks, albums, artists)) = (data.search_results)() {
                     SearchResults {
                         search_query: data.search_query.read().clone(),
                         tracks: tracks.clone(),
                         albums: albums.clone(),
+                        artists: artists.clone(),
                         library,
                         playlist_store,
                         player,
@@ -221,6 +223,7 @@ pub fn JellyfinSearch(
                         active_menu_track,
                         show_playlist_modal,
                         selected_track_for_playlist,
+                        on_select_artist,
                     }
                 } else {
                     SearchGenres {
@@ -3,7 +3,7 @@ use ::server::subsonic::SubsonicClient;
 use config::{AppConfig, MusicService};
 use dioxus::prelude::*;
 use reader::Library;
-use reader::models::{Album, Track};
+use reader::models::{Album, Track, is_various_artists};
 use std::collections::HashSet;
 use std::path::PathBuf;
 
@@ -73,13 +73,16 @@ pub async fn sync_server_library(
                             Some(PathBuf::from(format!("jellyfin:{}", album_item.id)))
                         };
 
+                        let artist = album_item
+                            .album_artist
+                            .or_else(|| album_item.artists.as_ref().map(|a| a.join(", ")))
+                            .unwrap_or_default();
+
                         out_albums.push(Album {
                             id: format!("jellyfin:{}", album_item.id),
                             title: album_item.name,
-                            artist: album_item
-                                .album_artist
-                                .or_else(|| album_item.artists.as_ref().map(|a| a.join(", ")))
-                                .unwrap_or_default(),
+                            compilation: is_various_artists(&artist),
+                            artist,
                             genre: album_item
                                 .genres
                                 .as_ref()
@@ -116,9 +119,7 @@ pub async fn sync_server_library(
                             }
                         }
 
-                        let bitrate_kbps = item.bitrate.unwrap_or(0) / 1000;
-                        let bitrate_u8 = bitrate_kbps.min(255) as u8;
-
+                        let rating = item.star_rating();
                         out_tracks.push(Track {
                             path: PathBuf::from(path_str),
                             album_id: item
@@ -126,6 +127,7 @@ pub async fn sync_server_library(
                                 .map(|id| format!("jellyfin:{}", id))
                                 .unwrap_or_default(),
                             title: item.name,
+                            album_artist: item.album_artist.clone(),
                             artist: item
                                 .album_artist
                                 .clone()
@@ -134,7 +136,7 @@ pub async fn sync_server_library(
                             album: item.album.unwrap_or_default(),
                             duration: item.run_time_ticks.unwrap_or(0) / 10_000_000,
                             khz: item.sample_rate.unwrap_or(0),
-                            bitrate: bitrate_u8,
+                            bitrate: item.bitrate.unwrap_or(0) / 1000,
                             track_number: item.index_number,
                             disc_number: item.parent_index_number,
                             musicbrainz_release_id: None,
@@ -142,6 +144,11 @@ pub async fn sync_server_library(
                             artists: item.artists.unwrap_or_else(|| {
                                 item.album_artist.into_iter().collect()
                             }),
+                            date: item.production_year.map(|y| y.to_string()),
+                            genres: item.genres.unwrap_or_default(),
+                            codec: item.container.map(|c| c.to_uppercase()),
+                            rating,
+                            ..Default::default()
                         });
                     }
 
@@ -178,6 +185,7 @@ pub async fn sync_server_library(
             if !out_genres.is_empty() {
                 lib_write.jellyfin_genres = out_genres;
             }
+            lib_write.rebuild_server_artists();
         }
         MusicService::Subsonic | MusicService::Custom => {
             let data = fetch_subsonic_library(service, &server_url, &user_id, &token).await?;
@@ -186,6 +194,7 @@ pub async fn sync_server_library(
             lib_write.jellyfin_tracks = data.tracks;
             lib_write.jellyfin_genres = data.genres;
             lib_write.server_artist_images = data.artist_images;
+            lib_write.rebuild_server_artists();
         }
     }
 
@@ -259,6 +268,7 @@ pub async fn fetch_subsonic_library(
                 genre: album_genre,
                 year: album.year.unwrap_or(0),
                 cover_path: Some(PathBuf::from(album_id_prefixed.clone())),
+                compilation: is_various_artists(&album_artist),
             });
 
             let songs = remote.get_album_songs(&album.id).await.map_err(|e| {
@@ -276,8 +286,6 @@ pub async fn fetch_subsonic_library(
                     }
                 }
 
-                let bitrate_u8 = song.bit_rate.unwrap_or(0).min(255) as u8;
-
                 let song_cover_tag = song
                     .cover_art
                     .as_ref()
@@ -298,12 +306,22 @@ pub async fn fetch_subsonic_library(
                     album: song.album.unwrap_or_else(|| album_name.clone()),
                     duration: song.duration.unwrap_or(0),
                     khz: song.sampling_rate.unwrap_or(0),
-                    bitrate: bitrate_u8,
+                    bitrate: song.bit_rate.unwrap_or(0),
+                    bit_depth: song.bit_depth,
                     track_number: song.track,
                     disc_number: song.disc_number,
                     musicbrainz_release_id: None,
                     playlist_item_id: None,
                     artists: vec![song.artist.unwrap_or_else(|| album_artist.clone())],
+                    album_artist: Some(album_artist.clone()).filter(|a| !a.is_empty()),
+                    date: song.year.map(|y| y.to_string()),
+                    genres: song.genre.into_iter().filter(|g| !g.is_empty()).collect(),
+                    bpm: song.bpm.filter(|bpm| *bpm > 0),
+                    comment: song.comment.filter(|c| !c.is_empty()),
+                    file_size: song.size,
+                    codec: song.suffix.map(|s| s.to_uppercase()),
+                    rating: song.user_rating.filter(|r| *r > 0),
+                    ..Default::default()
                 });
             }
         }
@@ -2,17 +2,28 @@ use crate::theme_editor::ThemeEditorPage;
 use ::server::provider::ProviderClient;
 use components::settings_items::{
     BackBehaviorSelector, DiscordPresenceSettings, EqualizerPanel, LanguageSelector,
-    MultiDirectoryPicker, MusicBrainzSettings, ServerSettings, SettingItem, ThemeSelector,
-    ToggleSetting,
+    LyricsSourceOrder, MultiDirectoryPicker, MusicBrainzSettings, ScanExcludes, ServerSettings,
+    SettingItem, ThemeSelector, ToggleSetting,
 };
+use components::offline_storage::OfflineStorage;
 use components::settings_popups::{AddServerPopup, LoginPopup};
-use config::{AppConfig, MusicService};
+use config::{AppConfig, MusicService, ShuffleMode};
 use dioxus::prelude::*;
 use hooks::use_player_controller::PlayerController;
+use reader::relocate::Relocation;
+use reader::{AlbumGrouping, CoverSource, PlaylistCollage};
+use std::path::{Path, PathBuf};
 
 #[component]
-pub fn Settings(config: Signal<AppConfig>) -> Element {
+pub fn Settings(
+    config: Signal<AppConfig>,
+    playlist_store: Signal<reader::PlaylistStore>,
+    favorites_store: Signal<reader::FavoritesStore>,
+) -> Element {
     let mut ctrl = use_context::<PlayerController>();
+    let mut history = use_context::<Signal<reader::HistoryStore>>();
+    let mut saved_queues = use_context::<Signal<reader::SavedQueueStore>>();
+    let mut relocated = use_signal(|| None::<usize>);
     let mut show_add_server = use_signal(|| false);
     let mut show_login = use_signal(|| false);
 
@@ -100,6 +111,74 @@ pub fn Settings(config: Signal<AppConfig>) -> Element {
         }
     };
 
+    // Moves every reference under a music folder to the folder's new location in
+    // one pass, so the rescan that follows finds favorites, playlists and play
+    // counts already pointing at the new files.
+    let relocate_root = move |(index, new_root): (usize, PathBuf)| {
+        let Some(old_root) = config.peek().music_directory.get(index).cloned() else {
+            return;
+        };
+        if old_root == new_root {
+            return;
+        }
+        let relocation = {
+            let cfg = config.peek();
+            let mut paths = reader::relocate::referenced_paths(
+                &favorites_store.peek(),
+                &playlist_store.peek(),
+                &history.peek(),
+            );
+            paths.extend(ctrl.library.peek().tracks.iter().map(|t| t.path.clone()));
+            paths.extend(ctrl.bookmarks.peek().bookmarks.keys().map(PathBuf::from));
+            paths.extend(
+                saved_queues
+                    .peek()
+                    .queues
+                    .iter()
+                    .flat_map(|q| q.tracks().map(|t| t.path.clone())),
+            );
+            paths.extend(cfg.scan_rules.hidden_files.iter().cloned());
+            Relocation::from_prefix(paths.iter().map(PathBuf::as_path), &old_root, &new_root)
+        };
+
+        let mut cfg = config.write();
+        let changed = relocation.apply(
+            &mut favorites_store.write(),
+            &mut playlist_store.write(),
+            &mut history.write(),
+            &mut ctrl.bookmarks.write(),
+            &mut saved_queues.write(),
+        );
+        cfg.music_directory[index] = new_root.clone();
+        if let Some(patterns) = cfg.scan_rules.excludes.remove(&old_root) {
+            cfg.scan_rules.excludes.insert(new_root.clone(), patterns);
+        }
+        cfg.scan_rules.hidden_files = std::mem::take(&mut cfg.scan_rules.hidden_files)
+            .into_iter()
+            .map(|p| relocation.get(&p).map(Path::to_path_buf).unwrap_or(p))
+            .collect();
+        drop(cfg);
+
+        ctrl.library.with_mut(|lib| {
+            relocation.apply_library(lib);
+            for root in &mut lib.root_paths {
+                if *root == old_root {
+                    *root = new_root.clone();
+                }
+            }
+        });
+        for mut queue in [ctrl.queue, ctrl.up_next] {
+            queue.with_mut(|queue| {
+                for track in queue.iter_mut() {
+                    if let Some(new) = relocation.get(&track.path) {
+                        track.path = new.to_path_buf();
+                    }
+                }
+            });
+        }
+        relocated.set(Some(changed));
+    };
+
     rsx! {
         div { class: "p-8 max-w-4xl",
             h1 { class: "text-3xl font-bold text-white mb-6", "{i18n::t(\"settings\")}" }
@@ -140,21 +219,186 @@ pub fn Settings(config: Signal<AppConfig>) -> Element {
                         if !cfg!(target_arch = "wasm32") {
                             SettingItem {
                                 title: i18n::t("music_directory").to_string(),
+                                control: rsx! {
+                                    div { class: "flex flex-col w-full",
+                                        MultiDirectoryPicker {
+                                            current_paths: config.read().music_directory.clone(),
+                                            on_add: move |path| {
+                                                let mut config = config.write();
+                                                if !config.music_directory.contains(&path) {
+                                                    config.music_directory.push(path);
+                                                }
+                                            },
+                                            on_remove: move |index| {
+                                                let mut config = config.write();
+                                                if index < config.music_directory.len() {
+                                                    config.music_directory.remove(index);
+                                                }
+                                            },
+                                            on_relocate: relocate_root,
+                                        }
+                                        if let Some(count) = relocated() {
+                                            p {
+                                                class: "text-xs text-green-400 mt-2",
+                                                "{i18n::t_with(\"relocated_references\", &[(\"count\", count.to_string())])}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        if !cfg!(target_arch = "wasm32") {
+                            SettingItem {
+                                title: i18n::t("album_grouping").to_string(),
+                                control: rsx! {
+                                    select {
+                                        class: "bg-stone-800 text-white rounded-lg px-3 py-2 text-sm border border-white/10 focus:outline-none focus:border-indigo-500",
+                                        value: match config.read().album_grouping {
+                                            AlbumGrouping::Title => "title",
+                                            AlbumGrouping::AlbumArtist => "album-artist",
+                                            AlbumGrouping::Folder => "folder",
+                                        },
+                                        onchange: move |evt| {
+                                            config.write().album_grouping = match evt.value().as_str() {
+                                                "title" => AlbumGrouping::Title,
+                                                "folder" => AlbumGrouping::Folder,
+                                                _ => AlbumGrouping::AlbumArtist,
+                                            };
+                                        },
+                                        option { value: "album-artist", "{i18n::t(\"album_grouping_album_artist\")}" }
+                                        option { value: "folder", "{i18n::t(\"album_grouping_folder\")}" }
+                                        option { value: "title", "{i18n::t(\"album_grouping_title\")}" }
+                                    }
+                                }
+                            }
+                            SettingItem {
+                                title: i18n::t("cover_preference").to_string(),
+                                control: rsx! {
+                                    select {
+                                        class: "bg-stone-800 text-white rounded-lg px-3 py-2 text-sm border border-white/10 focus:outline-none focus:border-indigo-500",
+                                        value: match config.read().cover_options.prefer {
+                                            CoverSource::Embedded => "embedded",
+                                            CoverSource::Folder => "folder",
+                                        },
+                                        onchange: move |evt| {
+                                            config.write().cover_options.prefer = match evt.value().as_str() {
+                                                "folder" => CoverSource::Folder,
+                                                _ => CoverSource::Embedded,
+                                            };
+                                        },
+                                        option { value: "embedded", "{i18n::t(\"cover_preference_embedded\")}" }
+                                        option { value: "folder", "{i18n::t(\"cover_preference_folder\")}" }
+                                    }
+                                }
+                            }
+                            SettingItem {
+                                title: i18n::t("cover_file_names").to_string(),
+                                control: rsx! {
+                                    input {
+                                        class: "w-72 bg-stone-800 text-white rounded-lg px-3 py-2 text-sm border border-white/10 focus:outline-none focus:border-indigo-500",
+                                        r#type: "text",
+                                        title: "{i18n::t(\"cover_file_names_hint\")}",
+                                        value: "{config.read().cover_options.folder_names.join(\", \")}",
+                                        // onchange fires on blur, so a rescan only starts once editing is done
+                                        onchange: move |evt| {
+                                            let names: Vec<String> = evt
+                                                .value()
+                                                .split(',')
+                                                .map(|n| n.trim().to_string())
+                                                .filter(|n| !n.is_empty())
+                                                .collect();
+                                            config.write().cover_options.folder_names = if names.is_empty() {
+                                                reader::models::default_cover_names()
+                                            } else {
+                                                names
+                                            };
+                                        },
+                                    }
+                                }
+                            }
+                            SettingItem {
+                                title: i18n::t("playlist_collage").to_string(),
+                                control: rsx! {
+                                    select {
+                                        class: "bg-stone-800 text-white rounded-lg px-3 py-2 text-sm border border-white/10 focus:outline-none focus:border-indigo-500",
+                                        value: match config.read().playlist_collage {
+                                            PlaylistCollage::Off => "off",
+                                            PlaylistCollage::Grid2 => "grid2",
+                                            PlaylistCollage::Grid3 => "grid3",
+                                        },
+                                        onchange: move |evt| {
+                                            config.write().playlist_collage = match evt.value().as_str() {
+                                                "off" => PlaylistCollage::Off,
+                                                "grid3" => PlaylistCollage::Grid3,
+                                                _ => PlaylistCollage::Grid2,
+                                            };
+                                        },
+                                        option { value: "grid2", "{i18n::t(\"playlist_collage_grid2\")}" }
+                                        option { value: "grid3", "{i18n::t(\"playlist_collage_grid3\")}" }
+                                        option { value: "off", "{i18n::t(\"playlist_collage_off\")}" }
+                                    }
+                                }
+                            }
+                            if config.read().server.as_ref().is_some_and(|s| s.service == MusicService::Jellyfin) {
+                                SettingItem {
+                                    title: i18n::t("upload_playlist_collages").to_string(),
                                     control: rsx! {
-                                    MultiDirectoryPicker {
-                                        current_paths: config.read().music_directory.clone(),
-                                        on_add: move |path| {
+                                        ToggleSetting {
+                                            enabled: config.read().upload_playlist_collages,
+                                            on_change: move |val| config.write().upload_playlist_collages = val,
+                                        }
+                                    }
+                                }
+                            }
+                            SettingItem {
+                                title: i18n::t("write_ratings_to_tags").to_string(),
+                                control: rsx! {
+                                    ToggleSetting {
+                                        enabled: config.read().write_ratings_to_tags,
+                                        on_change: move |val| config.write().write_ratings_to_tags = val,
+                                    }
+                                }
+                            }
+                            SettingItem {
+                                title: i18n::t("scan_excludes").to_string(),
+                                control: rsx! {
+                                    ScanExcludes {
+                                        roots: config.read().music_directory.clone(),
+                                        excludes: config.read().scan_rules.excludes.clone(),
+                                        on_change: move |(root, patterns): (PathBuf, Vec<String>)| {
                                             let mut config = config.write();
-                                            if !config.music_directory.contains(&path) {
-                                                config.music_directory.push(path);
+                                            if patterns.is_empty() {
+                                                config.scan_rules.excludes.remove(&root);
+                                            } else {
+                                                config.scan_rules.excludes.insert(root, patterns);
                                             }
                                         },
-                                        on_remove: move |index| {
-                                            let mut config = config.write();
-                                            if index < config.music_directory.len() {
-                                                config.music_directory.remove(index);
+                                    }
+                                }
+                            }
+                            SettingItem {
+                                title: i18n::t("skip_hidden_files").to_string(),
+                                control: rsx! {
+                                    ToggleSetting {
+                                        enabled: config.read().scan_rules.skip_hidden,
+                                        on_change: move |val| config.write().scan_rules.skip_hidden = val,
+                                    }
+                                }
+                            }
+                            SettingItem {
+                                title: i18n::t("min_track_duration").to_string(),
+                                control: rsx! {
+                                    input {
+                                        class: "w-24 bg-stone-800 text-white rounded-lg px-3 py-2 text-sm border border-white/10 focus:outline-none focus:border-indigo-500",
+                                        r#type: "number",
+                                        min: "0",
+                                        value: "{config.read().scan_rules.min_duration}",
+                                        onchange: move |evt| {
+                                            if let Ok(seconds) = evt.value().trim().parse::<u64>() {
+                                                config.write().scan_rules.min_duration = seconds;
                                             }
-                                        }
+                                        },
                                     }
                                 }
                             }
@@ -236,6 +480,93 @@ pub fn Settings(config: Signal<AppConfig>) -> Element {
                                 }
                             }
                         }
+                        SettingItem {
+                            title: i18n::t("shuffle_mode").to_string(),
+                            control: rsx! {
+                                select {
+                                    class: "bg-stone-800 text-white rounded-lg px-3 py-2 text-sm border border-white/10 focus:outline-none focus:border-indigo-500",
+                                    value: match config.read().shuffle_mode {
+                                        ShuffleMode::Random => "random",
+                                        ShuffleMode::Smart => "smart",
+                                        ShuffleMode::Albums => "albums",
+                                    },
+                                    onchange: move |evt| {
+                                        config.write().shuffle_mode = match evt.value().as_str() {
+                                            "smart" => ShuffleMode::Smart,
+                                            "albums" => ShuffleMode::Albums,
+                                            _ => ShuffleMode::Random,
+                                        };
+                                        if *ctrl.shuffle.peek() {
+                                            ctrl.rebuild_shuffle_order();
+                                        }
+                                    },
+                                    option { value: "random", "{i18n::t(\"shuffle_mode_random\")}" }
+                                    option { value: "smart", "{i18n::t(\"shuffle_mode_smart\")}" }
+                                    option { value: "albums", "{i18n::t(\"shuffle_mode_albums\")}" }
+                                }
+                            }
+                        }
+                        if config.read().shuffle_mode == ShuffleMode::Smart {
+                            SettingItem {
+                                title: i18n::t("shuffle_by_rating").to_string(),
+                                control: rsx! {
+                                    ToggleSetting {
+                                        enabled: config.read().shuffle_by_rating,
+                                        on_change: move |val| config.write().shuffle_by_rating = val,
+                                    }
+                                }
+                            }
+                            SettingItem {
+                                title: i18n::t("shuffle_by_plays").to_string(),
+                                control: rsx! {
+                                    ToggleSetting {
+                                        enabled: config.read().shuffle_by_plays,
+                                        on_change: move |val| config.write().shuffle_by_plays = val,
+                                    }
+                                }
+                            }
+                            SettingItem {
+                                title: i18n::t("shuffle_avoid_recent_hours").to_string(),
+                                control: rsx! {
+                                    input {
+                                        class: "w-24 bg-stone-800 text-white rounded-lg px-3 py-2 text-sm border border-white/10 focus:outline-none focus:border-indigo-500",
+                                        r#type: "number",
+                                        min: "0",
+                                        value: "{config.read().shuffle_avoid_recent_hours}",
+                                        onchange: move |evt| {
+                                            if let Ok(hours) = evt.value().trim().parse::<u32>() {
+                                                config.write().shuffle_avoid_recent_hours = hours;
+                                            }
+                                        },
+                                    }
+                                }
+                            }
+                        }
+                        SettingItem {
+                            title: i18n::t("autoplay").to_string(),
+                            control: rsx! {
+                                ToggleSetting {
+                                    enabled: config.read().autoplay,
+                                    on_change: move |val| config.write().autoplay = val,
+                                }
+                            }
+                        }
+                        SettingItem {
+                            title: i18n::t("bookmark_min_minutes").to_string(),
+                            control: rsx! {
+                                input {
+                                    class: "w-24 bg-stone-800 text-white rounded-lg px-3 py-2 text-sm border border-white/10 focus:outline-none focus:border-indigo-500",
+                                    r#type: "number",
+                                    min: "0",
+                                    value: "{config.read().bookmark_min_minutes}",
+                                    onchange: move |evt| {
+                                        if let Ok(minutes) = evt.value().trim().parse::<u32>() {
+                                            config.write().bookmark_min_minutes = minutes;
+                                        }
+                                    },
+                                }
+                            }
+                        }
                         SettingItem {
                             title: i18n::t("listenbrainz").to_string(),
                             control: rsx! {
@@ -282,6 +613,62 @@ pub fn Settings(config: Signal<AppConfig>) -> Element {
                                 }
                             }
                         }
+                        SettingItem {
+                            title: i18n::t("lyrics_online_lookup").to_string(),
+                            control: rsx! {
+                                ToggleSetting {
+                                    enabled: config.read().lyrics_online,
+                                    on_change: move |val| config.write().lyrics_online = val,
+                                }
+                            }
+                        }
+                        SettingItem {
+                            title: i18n::t("lyrics_sources").to_string(),
+                            control: rsx! {
+                                LyricsSourceOrder {
+                                    sources: config.read().lyrics_source_order(),
+                                    online_enabled: config.read().lyrics_online,
+                                    on_change: move |sources| config.write().lyrics_sources = sources,
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if !cfg!(target_arch = "wasm32") {
+                    section {
+                        h2 {
+                            class: "text-lg font-semibold text-white/80 mb-4 border-b border-white/5 pb-2",
+                            "{i18n::t(\"offline_downloads\")}"
+                        }
+                        div { class: "space-y-4",
+                            SettingItem {
+                                title: i18n::t("offline_cache_limit_mb").to_string(),
+                                control: rsx! {
+                                    input {
+                                        class: "w-24 bg-stone-800 text-white rounded-lg px-3 py-2 text-sm border border-white/10 focus:outline-none focus:border-indigo-500",
+                                        r#type: "number",
+                                        min: "0",
+                                        value: "{config.read().offline_cache_limit_mb}",
+                                        onchange: move |evt| {
+                                            if let Ok(mb) = evt.value().trim().parse::<u64>() {
+                                                config.write().offline_cache_limit_mb = mb;
+                                            }
+                                        },
+                                    }
+                                }
+                            }
+                            SettingItem {
+                                title: i18n::t("offline_transcode").to_string(),
+                                control: rsx! {
+                                    ToggleSetting {
+                                        enabled: config.read().offline_transcode,
+                                        on_change: move |val| config.write().offline_transcode = val,
+                                    }
+                                }
+                            }
+                            OfflineStorage {}
+                        }
                     }
                 }
 
@@ -0,0 +1,163 @@
+use crate::decoder;
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, Decoder, DecoderOptions};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+
+const FRAME_MS: u64 = 100;
+const MAX_SECONDS: u64 = 60;
+/// How far two envelopes may be shifted against each other, to absorb encoder
+/// delay and differently trimmed silence.
+const MAX_SHIFT: usize = 10;
+/// Fewer overlapping frames than this says nothing either way.
+const MIN_OVERLAP: usize = 50;
+
+/// Loudness envelope of the first minute of a file: one log-energy value per
+/// 100 ms. Coarse, but it survives transcoding between lossy and lossless
+/// formats, which is what duplicate detection needs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fingerprint(pub Vec<f32>);
+
+impl Fingerprint {
+    /// Correlation of the two envelopes at their best alignment, from -1 to 1.
+    /// Copies of the same recording score above 0.9.
+    pub fn similarity(&self, other: &Fingerprint) -> f32 {
+        (0..=MAX_SHIFT)
+            .flat_map(|shift| {
+                [
+                    correlation(self.0.get(shift..), Some(&other.0)),
+                    correlation(Some(&self.0), other.0.get(shift..)),
+                ]
+            })
+            .flatten()
+            .fold(-1.0, f32::max)
+    }
+}
+
+fn correlation(a: Option<&[f32]>, b: Option<&[f32]>) -> Option<f32> {
+    let (a, b) = (a?, b?);
+    let len = a.len().min(b.len());
+    if len < MIN_OVERLAP {
+        return None;
+    }
+    let (a, b) = (&a[..len], &b[..len]);
+    let mean_a = a.iter().sum::<f32>() / len as f32;
+    let mean_b = b.iter().sum::<f32>() / len as f32;
+
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        let (dx, dy) = (x - mean_a, y - mean_b);
+        cov += dx * dy;
+        var_a += dx * dx;
+        var_b += dy * dy;
+    }
+    if var_a == 0.0 || var_b == 0.0 {
+        return None;
+    }
+    Some(cov / (var_a * var_b).sqrt())
+}
+
+/// Decodes up to the first minute of `path` and returns its envelope, or `None`
+/// if the file cannot be decoded.
+pub fn fingerprint(path: &Path) -> Option<Fingerprint> {
+    let (source, hint) = decoder::open_file(path).ok()?;
+    let mss = MediaSourceStream::new(source, Default::default());
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .ok()?
+        .format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)?;
+    let track_id = track.id;
+    let sample_rate = u64::from(track.codec_params.sample_rate?);
+    let mut decoder = match symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+    {
+        Ok(d) => d,
+        Err(_) => Box::new(
+            symphonia_adapter_libopus::OpusDecoder::try_new(
+                &track.codec_params,
+                &DecoderOptions::default(),
+            )
+            .ok()?,
+        ),
+    };
+
+    let frame_len = (sample_rate * FRAME_MS / 1000).max(1);
+    let max_frames = (MAX_SECONDS * 1000 / FRAME_MS) as usize;
+    let mut envelope = Vec::with_capacity(max_frames);
+    let (mut energy, mut filled) = (0.0f64, 0u64);
+    let mut samples: Option<SampleBuffer<f32>> = None;
+
+    while envelope.len() < max_frames {
+        let Ok(packet) = format.next_packet() else {
+            break;
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let Ok(decoded) = decoder.decode(&packet) else {
+            continue;
+        };
+        let channels = decoded.spec().channels.count().max(1);
+        let buf = samples
+            .get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, *decoded.spec()));
+        if buf.capacity() < decoded.capacity() * channels {
+            *buf = SampleBuffer::new(decoded.capacity() as u64, *decoded.spec());
+        }
+        buf.copy_interleaved_ref(decoded);
+
+        for frame in buf.samples().chunks(channels) {
+            let mono = frame.iter().sum::<f32>() / channels as f32;
+            energy += f64::from(mono * mono);
+            filled += 1;
+            if filled == frame_len {
+                envelope.push((energy / frame_len as f64 + 1e-10).ln() as f32);
+                energy = 0.0;
+                filled = 0;
+            }
+        }
+    }
+
+    (envelope.len() >= MIN_OVERLAP).then_some(Fingerprint(envelope))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fingerprint;
+
+    fn envelope(len: usize, phase: f32) -> Vec<f32> {
+        (0..len)
+            .map(|i| ((i as f32 + phase) * 0.37).sin() * 3.0 + (i as f32 * 0.05).cos())
+            .collect()
+    }
+
+    #[test]
+    fn same_envelope_matches_across_gain_and_offset() {
+        let original = envelope(300, 0.0);
+        // quieter copy with a few hundred milliseconds of extra lead-in
+        let mut copy = vec![-20.0; 3];
+        copy.extend(original.iter().map(|v| v - 1.5));
+
+        let a = Fingerprint(original);
+        let b = Fingerprint(copy);
+        assert!(a.similarity(&b) > 0.99);
+    }
+
+    #[test]
+    fn different_envelopes_do_not_match() {
+        let a = Fingerprint(envelope(300, 0.0));
+        let b = Fingerprint((0..300).map(|i| ((i * 7919) % 13) as f32).collect());
+        assert!(a.similarity(&b) < 0.5);
+    }
+}
@@ -2,6 +2,8 @@
 pub mod decoder;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod eq;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod fingerprint;
 pub mod player;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod systemint;
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Positions closer than this to either end aren't worth keeping: the track
+/// has barely started or is as good as finished.
+pub const BOOKMARK_MARGIN_SECS: u64 = 30;
+
+/// Where playback of a long track was left off.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Bookmark {
+    pub position_secs: u64,
+    /// Unix seconds, so the newer side wins when merging with a server.
+    pub updated_at: u64,
+}
+
+/// Resume positions of long tracks, keyed by `rating_key`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct BookmarkStore {
+    #[serde(default)]
+    pub bookmarks: HashMap<String, Bookmark>,
+}
+
+impl BookmarkStore {
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = fs::read_to_string(path)?;
+        let store = serde_json::from_str(&data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        Ok(store)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let data = serde_json::to_string(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        utils::artwork::write_atomically(path, data.as_bytes())
+    }
+
+    pub fn position(&self, key: &str) -> Option<u64> {
+        self.bookmarks.get(key).map(|b| b.position_secs)
+    }
+
+    /// Share of the track already heard, for progress bars in lists.
+    pub fn progress(&self, key: &str, duration_secs: u64) -> Option<f64> {
+        let position = self.position(key)?;
+        (duration_secs > 0).then(|| (position as f64 / duration_secs as f64).min(1.0))
+    }
+
+    /// Remembers `position_secs` and returns whether anything changed. Near
+    /// the end the track counts as finished and its bookmark goes; near the
+    /// start the old bookmark stays, since playback may not have reached it yet.
+    pub fn set(&mut self, key: &str, position_secs: u64, duration_secs: u64, now: u64) -> bool {
+        if position_secs + BOOKMARK_MARGIN_SECS >= duration_secs {
+            return self.clear(key);
+        }
+        if position_secs < BOOKMARK_MARGIN_SECS {
+            return false;
+        }
+        let bookmark = Bookmark {
+            position_secs,
+            updated_at: now,
+        };
+        self.bookmarks.insert(key.to_string(), bookmark) != Some(bookmark)
+    }
+
+    pub fn clear(&mut self, key: &str) -> bool {
+        self.bookmarks.remove(key).is_some()
+    }
+
+    /// Carries a bookmark over to a track's new key; when both keys have one,
+    /// the newer position wins.
+    pub fn move_track(&mut self, old: &str, new: &str) -> bool {
+        if old == new {
+            return false;
+        }
+        let Some(bookmark) = self.bookmarks.remove(old) else {
+            return false;
+        };
+        self.merge([(new.to_string(), bookmark)]);
+        true
+    }
+
+    /// Takes bookmarks fetched from elsewhere where they are newer than ours.
+    pub fn merge(&mut self, other: impl IntoIterator<Item = (String, Bookmark)>) -> bool {
+        let mut changed = false;
+        for (key, bookmark) in other {
+            match self.bookmarks.get(&key) {
+                Some(ours) if ours.updated_at >= bookmark.updated_at => {}
+                _ => {
+                    self.bookmarks.insert(key, bookmark);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bookmark, BookmarkStore};
+
+    #[test]
+    fn only_positions_near_the_end_clear_the_bookmark() {
+        let mut store = BookmarkStore::default();
+        assert!(store.set("/book", 600, 3_600, 1));
+        assert_eq!(store.position("/book"), Some(600));
+        assert_eq!(store.progress("/book", 3_600), Some(600.0 / 3_600.0));
+
+        assert!(store.set("/book", 3_590, 3_600, 2));
+        assert_eq!(store.position("/book"), None);
+
+        store.set("/book", 600, 3_600, 3);
+        assert!(!store.set("/book", 10, 3_600, 4));
+        assert_eq!(store.position("/book"), Some(600));
+    }
+
+    #[test]
+    fn merge_keeps_the_newer_bookmark() {
+        let mut store = BookmarkStore::default();
+        store.set("a", 100, 1_000, 50);
+        store.set("b", 100, 1_000, 50);
+        let changed = store.merge([
+            (
+                "a".to_string(),
+                Bookmark {
+                    position_secs: 300,
+                    updated_at: 40,
+                },
+            ),
+            (
+                "b".to_string(),
+                Bookmark {
+                    position_secs: 300,
+                    updated_at: 60,
+                },
+            ),
+        ]);
+        assert!(changed);
+        assert_eq!(store.position("a"), Some(100));
+        assert_eq!(store.position("b"), Some(300));
+    }
+
+    #[test]
+    fn moving_keeps_the_newer_bookmark() {
+        let mut store = BookmarkStore::default();
+        store.set("/old/a.m4b", 600, 3_600, 1);
+        store.set("/old/b.m4b", 900, 3_600, 5);
+        store.set("/new/b.m4b", 300, 3_600, 2);
+
+        assert!(store.move_track("/old/a.m4b", "/new/a.m4b"));
+        assert!(store.move_track("/old/b.m4b", "/new/b.m4b"));
+        assert!(!store.move_track("/old/a.m4b", "/new/a.m4b"));
+
+        assert_eq!(store.position("/old/a.m4b"), None);
+        assert_eq!(store.position("/new/a.m4b"), Some(600));
+        assert_eq!(store.position("/new/b.m4b"), Some(900));
+    }
+}
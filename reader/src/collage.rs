@@ -0,0 +1,112 @@
+use crate::models::{PlaylistCollage, Track, stable_hash};
+use std::collections::HashSet;
+
+/// Covers for a playlist collage: one per distinct album, in playlist order,
+/// trimmed to fill a full grid. Albums without a cover are skipped. Returns the
+/// grid side and the covers, or `None` when there are too few albums for even a
+/// 2×2 grid, in which case the first album's cover is shown as before.
+pub fn pick_covers<'a, T>(
+    tracks: impl IntoIterator<Item = &'a Track>,
+    style: PlaylistCollage,
+    mut cover_of: impl FnMut(&Track) -> Option<T>,
+) -> Option<(u32, Vec<T>)> {
+    let max_side = style.max_side()?;
+    let wanted = (max_side * max_side) as usize;
+
+    let mut seen = HashSet::new();
+    let mut covers = Vec::new();
+    for track in tracks {
+        if covers.len() == wanted {
+            break;
+        }
+        // untagged singles have no album, so each counts as its own
+        let key = if track.album_id.is_empty() {
+            track.path.to_string_lossy().into_owned()
+        } else {
+            track.album_id.clone()
+        };
+        if !seen.insert(key) {
+            continue;
+        }
+        if let Some(cover) = cover_of(track) {
+            covers.push(cover);
+        }
+    }
+
+    let side = (2..=max_side)
+        .rev()
+        .find(|side| covers.len() >= (side * side) as usize)?;
+    covers.truncate((side * side) as usize);
+    Some((side, covers))
+}
+
+/// Cache file name for a collage of `sources`. Any change to the covers or
+/// their order gives a new name, so a stale collage is never reused.
+pub fn file_name(side: u32, sources: &[impl AsRef<str>]) -> String {
+    let key = sources
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("collage_{side}_{:016x}.jpg", stable_hash(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_name, pick_covers};
+    use crate::models::{PlaylistCollage, Track};
+    use std::path::PathBuf;
+
+    fn track(path: &str, album_id: &str) -> Track {
+        Track {
+            path: PathBuf::from(path),
+            album_id: album_id.into(),
+            ..Default::default()
+        }
+    }
+
+    fn cover(track: &Track) -> Option<String> {
+        (track.album_id != "no-cover").then(|| track.album_id.clone())
+    }
+
+    #[test]
+    fn takes_one_cover_per_album_in_playlist_order() {
+        let tracks = [
+            track("/m/1", "a"),
+            track("/m/2", "a"),
+            track("/m/3", "no-cover"),
+            track("/m/4", "b"),
+            track("/m/5", "c"),
+            track("/m/6", "b"),
+            track("/m/7", "d"),
+            track("/m/8", "e"),
+        ];
+
+        let (side, covers) = pick_covers(&tracks, PlaylistCollage::Grid2, cover).unwrap();
+
+        assert_eq!(side, 2);
+        assert_eq!(covers, ["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn falls_back_to_smaller_grid_or_none() {
+        let five: Vec<_> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .enumerate()
+            .map(|(i, id)| track(&format!("/m/{i}"), id))
+            .collect();
+
+        let (side, covers) = pick_covers(&five, PlaylistCollage::Grid3, cover).unwrap();
+        assert_eq!(side, 2);
+        assert_eq!(covers.len(), 4);
+
+        assert!(pick_covers(&five[..3], PlaylistCollage::Grid2, cover).is_none());
+        assert!(pick_covers(&five, PlaylistCollage::Off, cover).is_none());
+    }
+
+    #[test]
+    fn file_name_follows_cover_order() {
+        assert_eq!(file_name(2, &["a", "b"]), file_name(2, &["a", "b"]));
+        assert_ne!(file_name(2, &["a", "b"]), file_name(2, &["b", "a"]));
+    }
+}
@@ -0,0 +1,293 @@
+use crate::bookmarks::BookmarkStore;
+use crate::history::HistoryStore;
+use crate::models::{FavoritesStore, PlaylistStore, Track, normalize_name, rating_key};
+use crate::saved_queues::SavedQueueStore;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Copies whose lengths differ by more than this many seconds are different edits.
+const DURATION_TOLERANCE: u64 = 2;
+
+/// Tracks that look like copies of the same recording, best quality first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub tracks: Vec<Track>,
+}
+
+impl DuplicateGroup {
+    pub fn best(&self) -> &Track {
+        &self.tracks[0]
+    }
+}
+
+/// Groups tracks with the same normalised title and artist whose durations are
+/// within a couple of seconds of each other.
+pub fn find_duplicates(tracks: &[Track]) -> Vec<DuplicateGroup> {
+    let mut by_tags: HashMap<(String, String), Vec<&Track>> = HashMap::new();
+    for track in tracks {
+        let title = match_key(&track.title);
+        if title.is_empty() {
+            continue;
+        }
+        by_tags
+            .entry((title, match_key(&track.artist)))
+            .or_default()
+            .push(track);
+    }
+
+    let mut groups = Vec::new();
+    for mut candidates in by_tags.into_values().filter(|c| c.len() > 1) {
+        candidates.sort_by_key(|t| t.duration);
+        let mut run: Vec<&Track> = Vec::new();
+        for track in candidates {
+            // measured from the run's first track, so small steps don't chain
+            if let Some(first) = run.first()
+                && track.duration - first.duration > DURATION_TOLERANCE
+            {
+                push_group(&mut groups, std::mem::take(&mut run));
+            }
+            run.push(track);
+        }
+        push_group(&mut groups, run);
+    }
+
+    groups.sort_by_cached_key(|g| {
+        (
+            g.best().artist.to_lowercase(),
+            g.best().title.to_lowercase(),
+        )
+    });
+    groups
+}
+
+fn push_group(groups: &mut Vec<DuplicateGroup>, run: Vec<&Track>) {
+    if run.len() < 2 {
+        return;
+    }
+    let mut tracks: Vec<Track> = run.into_iter().cloned().collect();
+    tracks.sort_by(|a, b| compare_quality(b, a));
+    groups.push(DuplicateGroup { tracks });
+}
+
+/// Lowercase letters and digits only, so `Don't Stop`, `dont stop` and
+/// `Don’t  Stop` compare equal.
+pub(crate) fn match_key(value: &str) -> String {
+    let kept: String = value
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect();
+    normalize_name(&kept)
+}
+
+/// Lossy codecs report no bit depth, so its presence marks a lossless file.
+pub fn is_lossless(track: &Track) -> bool {
+    track.bit_depth.is_some()
+}
+
+/// Orders copies by lossless first, then bit depth, sample rate, bitrate and
+/// finally file size.
+pub fn compare_quality(a: &Track, b: &Track) -> Ordering {
+    is_lossless(a)
+        .cmp(&is_lossless(b))
+        .then_with(|| a.bit_depth.cmp(&b.bit_depth))
+        .then_with(|| a.khz.cmp(&b.khz))
+        .then_with(|| a.bitrate.cmp(&b.bitrate))
+        .then_with(|| a.file_size.cmp(&b.file_size))
+}
+
+/// Points favorites, playlist entries and listening history for `others` at `keep`.
+/// A playlist that already had `keep` drops the other copies instead of listing
+/// the song twice, and `keep` takes the highest rating among the copies.
+pub fn merge_references(
+    keep: &Path,
+    others: &[PathBuf],
+    favorites: &mut FavoritesStore,
+    playlists: &mut PlaylistStore,
+    history: &mut HistoryStore,
+    bookmarks: &mut BookmarkStore,
+    saved_queues: &mut SavedQueueStore,
+) {
+    let others: HashSet<&Path> = others
+        .iter()
+        .map(PathBuf::as_path)
+        .filter(|p| *p != keep)
+        .collect();
+    if others.is_empty() {
+        return;
+    }
+
+    let was_favorite = favorites
+        .local_favorites
+        .iter()
+        .any(|p| p == keep || others.contains(p.as_path()));
+    favorites
+        .local_favorites
+        .retain(|p| !others.contains(p.as_path()));
+    if was_favorite && !favorites.is_local_favorite(keep) {
+        favorites.local_favorites.push(keep.to_path_buf());
+    }
+
+    for playlist in &mut playlists.playlists {
+        let mut has_keep = playlist.tracks.iter().any(|p| p == keep);
+        playlist.tracks.retain_mut(|path| {
+            if !others.contains(path.as_path()) {
+                return true;
+            }
+            if has_keep {
+                return false;
+            }
+            *path = keep.to_path_buf();
+            has_keep = true;
+            true
+        });
+    }
+
+    let best_rating = others
+        .iter()
+        .filter_map(|p| favorites.track_ratings.remove(&rating_key(p)))
+        .max();
+    if let Some(stars) = best_rating {
+        let kept = favorites.track_ratings.entry(rating_key(keep)).or_insert(0);
+        *kept = (*kept).max(stars);
+    }
+    for other in &others {
+        bookmarks.move_track(&rating_key(other), &rating_key(keep));
+        saved_queues.move_track(other, keep);
+    }
+
+    let keep = keep.to_string_lossy();
+    for other in others {
+        history.move_track(&other.to_string_lossy(), &keep);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_duplicates, merge_references};
+    use crate::bookmarks::BookmarkStore;
+    use crate::history::HistoryStore;
+    use crate::models::{FavoritesStore, Playlist, PlaylistStore, Track};
+    use crate::saved_queues::{SavedQueue, SavedQueueStore};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn track(path: &str, title: &str, duration: u64, bit_depth: Option<u8>) -> Track {
+        Track {
+            path: PathBuf::from(path),
+            title: title.into(),
+            artist: "Fleetwood Mac".into(),
+            duration,
+            bit_depth,
+            bitrate: if bit_depth.is_some() { 900 } else { 320 },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn groups_copies_and_ranks_lossless_first() {
+        let tracks = vec![
+            track("/a/dreams.mp3", "Dreams", 257, None),
+            track("/b/dreams.flac", "dreams", 258, Some(16)),
+            track("/c/dreams-live.flac", "Dreams", 301, Some(24)),
+            track("/d/dont-stop.mp3", "Don't Stop", 193, None),
+        ];
+
+        let groups = find_duplicates(&tracks);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].tracks.len(), 2);
+        assert_eq!(groups[0].best().path, PathBuf::from("/b/dreams.flac"));
+    }
+
+    #[test]
+    fn close_lengths_do_not_chain_into_one_group() {
+        let tracks = vec![
+            track("/a/dreams.mp3", "Dreams", 200, None),
+            track("/b/dreams.mp3", "Dreams", 202, None),
+            track("/c/dreams.mp3", "Dreams", 204, None),
+            track("/d/dreams.mp3", "Dreams", 206, None),
+        ];
+
+        let groups = find_duplicates(&tracks);
+
+        assert_eq!(groups.len(), 2);
+        assert!(groups.iter().all(|g| g.tracks.len() == 2));
+    }
+
+    #[test]
+    fn merges_references_onto_kept_copy() {
+        let keep = PathBuf::from("/b/dreams.flac");
+        let other = PathBuf::from("/a/dreams.mp3");
+        let mut favorites = FavoritesStore {
+            local_favorites: vec![other.clone()],
+            ..Default::default()
+        };
+        let mut playlists = PlaylistStore {
+            playlists: vec![
+                Playlist {
+                    id: "1".into(),
+                    name: "Mix".into(),
+                    tracks: vec![other.clone(), PathBuf::from("/x.mp3")],
+                    cover_path: None,
+                    collage_path: None,
+                },
+                Playlist {
+                    id: "2".into(),
+                    name: "Both".into(),
+                    tracks: vec![keep.clone(), other.clone()],
+                    cover_path: None,
+                    collage_path: None,
+                },
+            ],
+            ..Default::default()
+        };
+        let mut history = HistoryStore {
+            listen_counts: HashMap::from([
+                ("/a/dreams.mp3".to_string(), 3),
+                ("/b/dreams.flac".to_string(), 2),
+            ]),
+            ..Default::default()
+        };
+        let mut bookmarks = BookmarkStore::default();
+        bookmarks.set("/a/dreams.mp3", 900, 3_600, 1);
+        let mut saved_queues = SavedQueueStore {
+            queues: vec![SavedQueue {
+                name: "Work".into(),
+                saved_at: 1,
+                queue: vec![Track {
+                    path: other.clone(),
+                    ..Default::default()
+                }],
+                up_next: Vec::new(),
+                current_queue_index: 0,
+                from_up_next: false,
+                progress_secs: 0,
+                shuffle: false,
+                loop_mode: Default::default(),
+            }],
+        };
+
+        merge_references(
+            &keep,
+            &[other],
+            &mut favorites,
+            &mut playlists,
+            &mut history,
+            &mut bookmarks,
+            &mut saved_queues,
+        );
+
+        assert_eq!(favorites.local_favorites, vec![keep.clone()]);
+        assert_eq!(
+            playlists.playlists[0].tracks,
+            vec![keep.clone(), PathBuf::from("/x.mp3")]
+        );
+        assert_eq!(playlists.playlists[1].tracks, vec![keep.clone()]);
+        assert_eq!(history.listen_counts.len(), 1);
+        assert_eq!(history.plays("/b/dreams.flac"), 5);
+        assert_eq!(bookmarks.position("/a/dreams.mp3"), None);
+        assert_eq!(bookmarks.position("/b/dreams.flac"), Some(900));
+        assert_eq!(saved_queues.queues[0].queue[0].path, keep);
+    }
+}
@@ -0,0 +1,61 @@
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Per-folder ignore file, in gitignore syntax.
+pub const IGNORE_FILE: &str = ".kopuzignore";
+
+/// The ignore rules in effect inside one directory: the music folder's configured
+/// patterns plus every `.kopuzignore` between the folder and that directory.
+/// Deeper files win, like nested `.gitignore`s.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreStack(Vec<Arc<Gitignore>>);
+
+impl IgnoreStack {
+    pub fn for_root(root: &Path, patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in patterns {
+            // a typo in one pattern should not disable the others
+            let _ = builder.add_line(None, pattern);
+        }
+        Self::default().push(builder.build())
+    }
+
+    /// The rules for `dir`, a subdirectory of the one these rules belong to.
+    pub fn enter(&self, dir: &Path) -> Self {
+        let file = dir.join(IGNORE_FILE);
+        if !file.is_file() {
+            return self.clone();
+        }
+        let mut builder = GitignoreBuilder::new(dir);
+        let _ = builder.add(file);
+        self.clone().push(builder.build())
+    }
+
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        for rules in self.0.iter().rev() {
+            match rules.matched(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+
+    fn push(mut self, rules: Result<Gitignore, ignore::Error>) -> Self {
+        if let Ok(rules) = rules
+            && !rules.is_empty()
+        {
+            self.0.push(Arc::new(rules));
+        }
+        self
+    }
+}
+
+/// Dot-files and dot-folders, e.g. `.Trash` or macOS `._track.mp3` leftovers.
+pub fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
@@ -0,0 +1,271 @@
+use super::models::Track;
+
+/// Parsed library filter. Plain words must all appear in the track's text
+/// fields; `field:value` terms narrow a single field, and numeric fields accept
+/// `N`, `>N`, `>=N`, `<N`, `<=N` or `A-B`. Values with spaces can be quoted:
+/// `genre:"hip hop" year:1990-1999 bpm:>120 rating:>=4`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackFilter {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    Any(String),
+    Text(TextField, String),
+    Number(NumberField, Range),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TextField {
+    Title,
+    Artist,
+    Album,
+    Genre,
+    Composer,
+    Label,
+    Codec,
+    Isrc,
+    Comment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NumberField {
+    Year,
+    Bpm,
+    Bitrate,
+    BitDepth,
+    Rating,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Range {
+    min: Option<u64>,
+    max: Option<u64>,
+}
+
+impl Range {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let num = |s: &str| s.trim().parse::<u64>().ok();
+        if let Some(rest) = value.strip_prefix(">=") {
+            return Some(Self {
+                min: num(rest),
+                max: None,
+            })
+            .filter(|r| r.min.is_some());
+        }
+        if let Some(rest) = value.strip_prefix("<=") {
+            return Some(Self {
+                min: None,
+                max: num(rest),
+            })
+            .filter(|r| r.max.is_some());
+        }
+        if let Some(rest) = value.strip_prefix('>') {
+            let n = num(rest)?;
+            return Some(Self {
+                min: Some(n + 1),
+                max: None,
+            });
+        }
+        if let Some(rest) = value.strip_prefix('<') {
+            let n = num(rest)?;
+            return Some(Self {
+                min: None,
+                max: Some(n.checked_sub(1)?),
+            });
+        }
+        if let Some((lo, hi)) = value.split_once('-') {
+            return Some(Self {
+                min: Some(num(lo)?),
+                max: Some(num(hi)?),
+            });
+        }
+        let n = num(value)?;
+        Some(Self {
+            min: Some(n),
+            max: Some(n),
+        })
+    }
+
+    fn contains(&self, value: Option<u64>) -> bool {
+        let Some(value) = value else {
+            return false;
+        };
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+impl TrackFilter {
+    pub fn parse(query: &str) -> Self {
+        let terms = tokenize(query)
+            .into_iter()
+            .map(|token| parse_term(&token))
+            .collect();
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, track: &Track) -> bool {
+        self.terms.iter().all(|term| match term {
+            Term::Any(needle) => text_fields(track).any(|v| contains(v, needle)),
+            Term::Text(field, needle) => field_values(track, *field).any(|v| contains(v, needle)),
+            Term::Number(field, range) => range.contains(number_value(track, *field)),
+        })
+    }
+}
+
+fn tokenize(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn parse_term(token: &str) -> Term {
+    let any = || Term::Any(token.to_lowercase());
+    let Some((key, value)) = token.split_once(':') else {
+        return any();
+    };
+    if value.is_empty() {
+        return any();
+    }
+    let text = |field| Term::Text(field, value.to_lowercase());
+    let number = |field| Range::parse(value).map(|range| Term::Number(field, range));
+    match key.to_lowercase().as_str() {
+        "title" => text(TextField::Title),
+        "artist" => text(TextField::Artist),
+        "album" => text(TextField::Album),
+        "genre" => text(TextField::Genre),
+        "composer" => text(TextField::Composer),
+        "label" => text(TextField::Label),
+        "codec" | "format" => text(TextField::Codec),
+        "isrc" => text(TextField::Isrc),
+        "comment" => text(TextField::Comment),
+        "year" => number(NumberField::Year).unwrap_or_else(any),
+        "bpm" => number(NumberField::Bpm).unwrap_or_else(any),
+        "bitrate" | "kbps" => number(NumberField::Bitrate).unwrap_or_else(any),
+        "bits" | "bitdepth" => number(NumberField::BitDepth).unwrap_or_else(any),
+        "rating" | "stars" => number(NumberField::Rating).unwrap_or_else(any),
+        _ => any(),
+    }
+}
+
+fn contains(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(needle)
+}
+
+fn text_fields(track: &Track) -> impl Iterator<Item = &str> {
+    [
+        TextField::Title,
+        TextField::Artist,
+        TextField::Album,
+        TextField::Genre,
+        TextField::Composer,
+        TextField::Label,
+    ]
+    .into_iter()
+    .flat_map(|field| field_values(track, field))
+}
+
+fn field_values(track: &Track, field: TextField) -> Box<dyn Iterator<Item = &str> + '_> {
+    match field {
+        TextField::Title => Box::new(std::iter::once(track.title.as_str())),
+        TextField::Artist => Box::new(
+            std::iter::once(track.artist.as_str())
+                .chain(track.artists.iter().map(String::as_str))
+                .chain(track.album_artist.as_deref()),
+        ),
+        TextField::Album => Box::new(std::iter::once(track.album.as_str())),
+        TextField::Genre => Box::new(track.genres.iter().map(String::as_str)),
+        TextField::Composer => Box::new(track.composer.as_deref().into_iter()),
+        TextField::Label => Box::new(track.label.as_deref().into_iter()),
+        TextField::Codec => Box::new(track.codec.as_deref().into_iter()),
+        TextField::Isrc => Box::new(track.isrc.as_deref().into_iter()),
+        TextField::Comment => Box::new(track.comment.as_deref().into_iter()),
+    }
+}
+
+fn number_value(track: &Track, field: NumberField) -> Option<u64> {
+    match field {
+        NumberField::Year => track.year().map(u64::from),
+        NumberField::Bpm => track.bpm.map(u64::from),
+        NumberField::Bitrate => Some(u64::from(track.bitrate)).filter(|b| *b > 0),
+        NumberField::BitDepth => track.bit_depth.map(u64::from),
+        // unrated counts as zero so `rating:<3` finds tracks nobody rated yet
+        NumberField::Rating => Some(u64::from(track.rating.unwrap_or(0))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrackFilter;
+    use crate::models::Track;
+
+    fn track() -> Track {
+        Track {
+            title: "So What".into(),
+            artist: "Miles Davis".into(),
+            album: "Kind of Blue".into(),
+            genres: vec!["Jazz".into(), "Modal Jazz".into()],
+            date: Some("1959-08-17".into()),
+            bpm: Some(136),
+            bitrate: 1411,
+            bit_depth: Some(16),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plain_words_search_every_text_field() {
+        assert!(TrackFilter::parse("miles blue").matches(&track()));
+        assert!(TrackFilter::parse("modal").matches(&track()));
+        assert!(!TrackFilter::parse("coltrane").matches(&track()));
+    }
+
+    #[test]
+    fn field_terms_only_look_at_their_field() {
+        assert!(TrackFilter::parse("genre:jazz").matches(&track()));
+        assert!(!TrackFilter::parse("title:jazz").matches(&track()));
+        assert!(TrackFilter::parse("genre:\"modal jazz\"").matches(&track()));
+    }
+
+    #[test]
+    fn numeric_ranges() {
+        assert!(TrackFilter::parse("year:1950-1959").matches(&track()));
+        assert!(TrackFilter::parse("year:1959").matches(&track()));
+        assert!(!TrackFilter::parse("year:>1959").matches(&track()));
+        assert!(TrackFilter::parse("bpm:>=136 bits:16").matches(&track()));
+        assert!(!TrackFilter::parse("bpm:<136").matches(&track()));
+        assert!(!TrackFilter::parse("bpm:>100").matches(&Track::default()));
+    }
+
+    #[test]
+    fn unrated_tracks_count_as_zero_stars() {
+        let rated = Track {
+            rating: Some(4),
+            ..track()
+        };
+        assert!(TrackFilter::parse("rating:>=4").matches(&rated));
+        assert!(!TrackFilter::parse("rating:>=4").matches(&track()));
+        assert!(TrackFilter::parse("stars:<3").matches(&track()));
+    }
+}
@@ -0,0 +1,314 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Listens kept in full. Older ones are dropped; their plays stay in the
+/// per-track totals.
+pub const MAX_LISTENS: usize = 5_000;
+
+/// Where a listened track was played from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ListenSource {
+    #[default]
+    Local,
+    Jellyfin,
+    Subsonic,
+    Custom,
+}
+
+impl ListenSource {
+    pub fn is_server(self) -> bool {
+        self != Self::Local
+    }
+}
+
+/// One time a track was played, whether to the end or not.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Listen {
+    /// Track path, the key listen counts use.
+    pub track: String,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub source: ListenSource,
+    /// Unix seconds.
+    pub started_at: u64,
+    pub listened_secs: u64,
+    pub duration_secs: u64,
+    /// Played to the end. Anything else counts as a skip.
+    pub completed: bool,
+    /// `device_id` of the app that played it.
+    pub device: String,
+}
+
+/// Totals for the listens that started within a period.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ListenStats {
+    pub plays: u64,
+    pub skips: u64,
+    pub listened_secs: u64,
+    /// Most completed plays first.
+    pub top_artists: Vec<(String, u64)>,
+}
+
+impl ListenStats {
+    pub fn skip_rate(&self) -> f64 {
+        let total = self.plays + self.skips;
+        if total == 0 {
+            0.0
+        } else {
+            self.skips as f64 / total as f64
+        }
+    }
+}
+
+/// The latest [`MAX_LISTENS`] listens, plus per-track play counts and last play
+/// times kept up to date alongside for smart playlists, shuffle and sorting.
+/// Counts carried over from before listens were recorded, and those of dropped
+/// listens, only exist in the totals.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct HistoryStore {
+    #[serde(default)]
+    pub listens: Vec<Listen>,
+    /// Completed plays per track.
+    #[serde(default)]
+    pub listen_counts: HashMap<String, u64>,
+    /// Unix seconds of each track's latest completed play.
+    #[serde(default)]
+    pub last_played: HashMap<String, u64>,
+}
+
+impl HistoryStore {
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = fs::read_to_string(path)?;
+        let store = serde_json::from_str(&data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        Ok(store)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let data = serde_json::to_string(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        utils::artwork::write_atomically(path, data.as_bytes())
+    }
+
+    pub fn record(&mut self, listen: Listen) {
+        if listen.completed {
+            *self.listen_counts.entry(listen.track.clone()).or_insert(0) += 1;
+            let last = self.last_played.entry(listen.track.clone()).or_insert(0);
+            *last = (*last).max(listen.started_at + listen.listened_secs);
+        }
+        self.listens.push(listen);
+        if self.listens.len() > MAX_LISTENS {
+            let excess = self.listens.len() - MAX_LISTENS;
+            self.listens.drain(..excess);
+        }
+    }
+
+    /// Folds in the counts that used to live in the config. Returns whether
+    /// there was anything to move.
+    pub fn migrate_counts(
+        &mut self,
+        listen_counts: HashMap<String, u64>,
+        last_played: HashMap<String, u64>,
+    ) -> bool {
+        let moved = !listen_counts.is_empty() || !last_played.is_empty();
+        for (track, count) in listen_counts {
+            *self.listen_counts.entry(track).or_insert(0) += count;
+        }
+        for (track, at) in last_played {
+            let last = self.last_played.entry(track).or_insert(0);
+            *last = (*last).max(at);
+        }
+        moved
+    }
+
+    pub fn plays(&self, track: &str) -> u64 {
+        self.listen_counts.get(track).copied().unwrap_or(0)
+    }
+
+    /// The latest listen of each track that matches `filter`, newest first.
+    pub fn recently_played(&self, limit: usize, filter: impl Fn(&Listen) -> bool) -> Vec<&Listen> {
+        let mut seen = HashSet::new();
+        self.listens
+            .iter()
+            .rev()
+            .filter(|l| filter(l) && seen.insert(l.track.as_str()))
+            .take(limit)
+            .collect()
+    }
+
+    /// Stats for listens started in `since..until` (Unix seconds) that match `filter`.
+    pub fn stats(&self, since: u64, until: u64, filter: impl Fn(&Listen) -> bool) -> ListenStats {
+        let mut stats = ListenStats::default();
+        let mut artists: HashMap<&str, u64> = HashMap::new();
+        for listen in self
+            .listens
+            .iter()
+            .filter(|l| l.started_at >= since && l.started_at < until && filter(l))
+        {
+            stats.listened_secs += listen.listened_secs;
+            if listen.completed {
+                stats.plays += 1;
+                *artists.entry(listen.artist.as_str()).or_insert(0) += 1;
+            } else {
+                stats.skips += 1;
+            }
+        }
+        let mut top: Vec<(String, u64)> = artists
+            .into_iter()
+            .filter(|(artist, _)| !artist.is_empty())
+            .map(|(artist, plays)| (artist.to_string(), plays))
+            .collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        stats.top_artists = top;
+        stats
+    }
+
+    /// Share of recorded listens of `track` that were skipped, if it has any.
+    pub fn skip_rate(&self, track: &str) -> Option<f64> {
+        let (total, skips) = self
+            .listens
+            .iter()
+            .filter(|l| l.track == track)
+            .fold((0u64, 0u64), |(total, skips), l| {
+                (total + 1, skips + u64::from(!l.completed))
+            });
+        (total > 0).then(|| skips as f64 / total as f64)
+    }
+
+    /// Points everything recorded for `old` at `new`, adding up the counts.
+    pub fn move_track(&mut self, old: &str, new: &str) -> bool {
+        if old == new {
+            return false;
+        }
+        let mut changed = false;
+        if let Some(count) = self.listen_counts.remove(old) {
+            *self.listen_counts.entry(new.to_string()).or_insert(0) += count;
+            changed = true;
+        }
+        if let Some(at) = self.last_played.remove(old) {
+            let last = self.last_played.entry(new.to_string()).or_insert(0);
+            *last = (*last).max(at);
+            changed = true;
+        }
+        for listen in self.listens.iter_mut().filter(|l| l.track == old) {
+            listen.track = new.to_string();
+            changed = true;
+        }
+        changed
+    }
+
+    /// Every track with a count or a recorded listen.
+    pub fn tracks(&self) -> impl Iterator<Item = &str> {
+        self.listen_counts
+            .keys()
+            .chain(self.listens.iter().map(|l| &l.track))
+            .map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HistoryStore, Listen, ListenSource, MAX_LISTENS};
+    use std::collections::HashMap;
+
+    fn listen(track: &str, artist: &str, started_at: u64, completed: bool) -> Listen {
+        Listen {
+            track: track.into(),
+            title: track.into(),
+            artist: artist.into(),
+            album: String::new(),
+            source: ListenSource::Local,
+            started_at,
+            listened_secs: if completed { 200 } else { 20 },
+            duration_secs: 200,
+            completed,
+            device: "dev".into(),
+        }
+    }
+
+    #[test]
+    fn only_completed_listens_count_as_plays() {
+        let mut store = HistoryStore::default();
+        store.record(listen("/a", "A", 100, true));
+        store.record(listen("/a", "A", 400, false));
+        assert_eq!(store.plays("/a"), 1);
+        assert_eq!(store.last_played.get("/a"), Some(&300));
+        assert_eq!(store.skip_rate("/a"), Some(0.5));
+        assert_eq!(store.skip_rate("/b"), None);
+    }
+
+    #[test]
+    fn migrated_counts_add_to_recorded_ones() {
+        let mut store = HistoryStore::default();
+        store.record(listen("/a", "A", 100, true));
+        let moved = store.migrate_counts(
+            HashMap::from([("/a".to_string(), 4), ("/b".to_string(), 2)]),
+            HashMap::from([("/a".to_string(), 50)]),
+        );
+        assert!(moved);
+        assert_eq!(store.plays("/a"), 5);
+        assert_eq!(store.plays("/b"), 2);
+        assert_eq!(store.last_played.get("/a"), Some(&300));
+        assert!(!store.migrate_counts(HashMap::new(), HashMap::new()));
+    }
+
+    #[test]
+    fn recently_played_lists_each_track_once() {
+        let mut store = HistoryStore::default();
+        store.record(listen("/a", "A", 100, true));
+        store.record(listen("/b", "B", 400, true));
+        store.record(listen("/a", "A", 700, false));
+        let recent: Vec<_> = store
+            .recently_played(10, |_| true)
+            .iter()
+            .map(|l| l.started_at)
+            .collect();
+        assert_eq!(recent, [700, 400]);
+    }
+
+    #[test]
+    fn stats_cover_only_the_period() {
+        let mut store = HistoryStore::default();
+        store.record(listen("/a", "A", 100, true));
+        store.record(listen("/b", "B", 1_000, true));
+        store.record(listen("/c", "B", 1_500, true));
+        store.record(listen("/d", "C", 1_800, false));
+        let stats = store.stats(1_000, 2_000, |_| true);
+        assert_eq!(stats.plays, 2);
+        assert_eq!(stats.skips, 1);
+        assert_eq!(stats.listened_secs, 420);
+        assert_eq!(stats.top_artists, [("B".to_string(), 2)]);
+        assert!((stats.skip_rate() - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn old_listens_are_dropped_but_still_counted() {
+        let mut store = HistoryStore::default();
+        for i in 0..MAX_LISTENS as u64 + 10 {
+            store.record(listen("/a", "A", i, true));
+        }
+        assert_eq!(store.listens.len(), MAX_LISTENS);
+        assert_eq!(store.listens[0].started_at, 10);
+        assert_eq!(store.plays("/a"), MAX_LISTENS as u64 + 10);
+    }
+
+    #[test]
+    fn moving_a_track_carries_its_history() {
+        let mut store = HistoryStore::default();
+        store.record(listen("/old", "A", 100, true));
+        store.listen_counts.insert("/new".into(), 2);
+        assert!(store.move_track("/old", "/new"));
+        assert_eq!(store.plays("/new"), 3);
+        assert!(store.listens.iter().all(|l| l.track == "/new"));
+    }
+}
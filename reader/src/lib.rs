@@ -1,13 +1,40 @@
+pub mod bookmarks;
+pub mod collage;
+pub mod duplicates;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod exclude;
+pub mod filter;
+pub mod history;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod lyrics;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod metadata;
 pub mod models;
+pub mod offline;
+pub mod playlist_io;
+pub mod radio;
+pub mod relocate;
+pub mod saved_queues;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod scanner;
+pub mod shuffle;
+pub mod smart;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tag_writer;
+pub mod track_match;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod utils;
 
+pub use bookmarks::BookmarkStore;
+pub use filter::TrackFilter;
+pub use history::HistoryStore;
+pub use offline::OfflineStore;
+pub use saved_queues::SavedQueueStore;
 #[cfg(not(target_arch = "wasm32"))]
 pub use metadata::read;
-pub use models::{Album, FavoritesStore, Library, PlaylistFolder, PlaylistStore, Track};
+pub use models::{
+    Album, AlbumGrouping, Artist, CoverOptions, CoverSource, FavoritesStore, Library,
+    PlaylistCollage, PlaylistFolder, PlaylistStore, ScanRules, Track,
+};
 #[cfg(not(target_arch = "wasm32"))]
-pub use scanner::scan_directory;
+pub use scanner::{ScanCancel, ScanOptions, ScanProgress, scan_directories, scan_directory};
@@ -0,0 +1,205 @@
+use lofty::config::ParseOptions;
+use lofty::file::AudioFile;
+use lofty::id3::v2::Frame;
+use lofty::mpeg::MpegFile;
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::ItemKey;
+use std::path::Path;
+
+/// Lyrics stored in the file's own tags, as raw text. Synced lyrics are always
+/// returned in LRC form so callers only need one parser.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmbeddedLyrics {
+    pub synced: Option<String>,
+    pub plain: Option<String>,
+}
+
+/// Reads `<track>.lrc`, falling back to `<track>.txt`.
+pub fn read_sidecar(track_path: &Path) -> Option<String> {
+    ["lrc", "LRC", "txt", "TXT"].iter().find_map(|ext| {
+        let candidate = track_path.with_extension(ext);
+        let bytes = std::fs::read(candidate).ok()?;
+        let text = String::from_utf8_lossy(&bytes).trim().to_string();
+        (!text.is_empty()).then_some(text)
+    })
+}
+
+pub fn read_embedded(track_path: &Path) -> EmbeddedLyrics {
+    let mut lyrics = EmbeddedLyrics::default();
+
+    if let Ok(tagged_file) = Probe::open(track_path).and_then(|p| p.read()) {
+        for tag in tagged_file.tags() {
+            for text in tag.get_strings(&ItemKey::Lyrics) {
+                let text = text.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                // plenty of taggers store LRC text in USLT / LYRICS
+                let slot = if has_lrc_timestamps(text) {
+                    &mut lyrics.synced
+                } else {
+                    &mut lyrics.plain
+                };
+                slot.get_or_insert_with(|| text.to_string());
+            }
+        }
+    }
+
+    if lyrics.synced.is_none() {
+        lyrics.synced = read_sylt(track_path);
+    }
+
+    lyrics
+}
+
+fn has_lrc_timestamps(text: &str) -> bool {
+    text.lines().any(|line| {
+        let line = line.trim_start();
+        line.starts_with('[')
+            && line[1..].split(']').next().is_some_and(|stamp| {
+                stamp.contains(':') && stamp.starts_with(|c: char| c.is_ascii_digit())
+            })
+    })
+}
+
+// SYLT only exists in ID3v2, which lofty keeps as an opaque frame.
+fn read_sylt(track_path: &Path) -> Option<String> {
+    let is_mp3 = track_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("mp3"));
+    if !is_mp3 {
+        return None;
+    }
+
+    let mut file = std::fs::File::open(track_path).ok()?;
+    let mpeg = MpegFile::read_from(&mut file, ParseOptions::new().read_properties(false)).ok()?;
+    let id3v2 = mpeg.id3v2()?;
+
+    id3v2.into_iter().find_map(|frame| match frame {
+        Frame::Binary(binary) if frame.id_str() == "SYLT" => parse_sylt(&binary.data),
+        _ => None,
+    })
+}
+
+/// Converts a raw SYLT frame body into LRC text. Only millisecond timestamps are
+/// supported; MPEG-frame timestamps would need the stream's frame rate.
+fn parse_sylt(data: &[u8]) -> Option<String> {
+    const MILLISECONDS: u8 = 2;
+
+    let (&encoding, rest) = data.split_first()?;
+    let rest = rest.get(3..)?; // language
+    let (&timestamp_format, rest) = rest.split_first()?;
+    let (_content_type, rest) = rest.split_first()?;
+    if timestamp_format != MILLISECONDS {
+        return None;
+    }
+
+    let (_descriptor, mut rest) = split_text(encoding, rest)?;
+    let mut lrc = String::new();
+    while !rest.is_empty() {
+        let (text, after_text) = split_text(encoding, rest)?;
+        let stamp = after_text.get(..4)?;
+        let ms = u32::from_be_bytes([stamp[0], stamp[1], stamp[2], stamp[3]]);
+        rest = &after_text[4..];
+
+        let text = text.trim_matches(|c| c == '\n' || c == '\r');
+        lrc.push_str(&format!(
+            "[{:02}:{:02}.{:02}]{}\n",
+            ms / 60_000,
+            (ms / 1000) % 60,
+            (ms % 1000) / 10,
+            text
+        ));
+    }
+
+    (!lrc.is_empty()).then_some(lrc)
+}
+
+/// Splits one terminated string off the front of `data`, decoding it with the
+/// ID3v2 text `encoding`.
+fn split_text(encoding: u8, data: &[u8]) -> Option<(String, &[u8])> {
+    match encoding {
+        // ISO-8859-1 / UTF-8: single NUL terminator
+        0 | 3 => {
+            let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+            let bytes = &data[..end];
+            let text = if encoding == 0 {
+                bytes.iter().map(|&b| b as char).collect()
+            } else {
+                String::from_utf8_lossy(bytes).into_owned()
+            };
+            Some((text, data.get(end + 1..).unwrap_or_default()))
+        }
+        // UTF-16 with BOM / UTF-16BE: double NUL terminator on a code unit boundary
+        1 | 2 => {
+            let end = data
+                .chunks_exact(2)
+                .position(|unit| unit == [0, 0])
+                .map(|i| i * 2)
+                .unwrap_or(data.len() & !1);
+            let mut units = &data[..end];
+            let mut big_endian = encoding == 2;
+            if encoding == 1 && units.len() >= 2 {
+                match [units[0], units[1]] {
+                    [0xFF, 0xFE] => units = &units[2..],
+                    [0xFE, 0xFF] => {
+                        big_endian = true;
+                        units = &units[2..];
+                    }
+                    _ => {}
+                }
+            }
+            let units: Vec<u16> = units
+                .chunks_exact(2)
+                .map(|u| {
+                    if big_endian {
+                        u16::from_be_bytes([u[0], u[1]])
+                    } else {
+                        u16::from_le_bytes([u[0], u[1]])
+                    }
+                })
+                .collect();
+            Some((
+                String::from_utf16_lossy(&units),
+                data.get(end + 2..).unwrap_or_default(),
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{has_lrc_timestamps, parse_sylt};
+
+    #[test]
+    fn sylt_becomes_lrc() {
+        let mut frame = vec![3, b'e', b'n', b'g', 2, 1, 0];
+        frame.extend_from_slice(b"Hello\0");
+        frame.extend_from_slice(&1_500u32.to_be_bytes());
+        frame.extend_from_slice(b"World\0");
+        frame.extend_from_slice(&62_340u32.to_be_bytes());
+
+        assert_eq!(
+            parse_sylt(&frame).as_deref(),
+            Some("[00:01.50]Hello\n[01:02.34]World\n")
+        );
+    }
+
+    #[test]
+    fn sylt_utf16_with_bom() {
+        let mut frame = vec![1, b'e', b'n', b'g', 2, 1, 0xFF, 0xFE, 0, 0];
+        frame.extend_from_slice(&[0xFF, 0xFE, b'H', 0, b'i', 0, 0, 0]);
+        frame.extend_from_slice(&10u32.to_be_bytes());
+
+        assert_eq!(parse_sylt(&frame).as_deref(), Some("[00:00.01]Hi\n"));
+    }
+
+    #[test]
+    fn detects_lrc_text() {
+        assert!(has_lrc_timestamps("[ar:Someone]\n[00:12.34]Line"));
+        assert!(!has_lrc_timestamps("[Chorus]\nJust words"));
+    }
+}
@@ -1,18 +1,68 @@
-use super::models::{Album, Library, Track};
-use super::utils::{find_folder_cover, save_cover};
+use super::models::{
+    Album, AlbumGrouping, Artist, CoverOptions, CoverSource, Library, Track, VARIOUS_ARTISTS,
+    is_various_artists, normalize_name, stable_hash,
+};
+use super::tag_writer::POPM_EMAIL;
+use super::utils::{
+    disc_label_number, find_artist_image, find_folder_cover, save_cover, split_disc_suffix,
+};
+use lofty::config::ParseOptions;
+use lofty::file::{FileType, TaggedFile};
+use lofty::id3::v2::{Frame, Id3v2Tag};
+use lofty::mpeg::MpegFile;
 use lofty::prelude::*;
 use lofty::tag::ItemKey;
 use lofty::{probe::Probe, properties::FileProperties, tag::Tag};
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-pub fn make_album_id(album: &str) -> String {
-    format!(
-        "alb_{}",
-        album
-            .to_lowercase()
-            .replace(' ', "_")
-            .replace(|c: char| !c.is_alphanumeric() && c != '_', "")
-    )
+/// Tag values that decide which album a track belongs to.
+#[derive(Debug, Clone, Copy)]
+pub struct AlbumKey<'a> {
+    pub title: &'a str,
+    pub album_artist: Option<&'a str>,
+    pub release_id: Option<&'a str>,
+    pub folder: Option<&'a Path>,
+}
+
+pub fn make_album_id(key: &AlbumKey<'_>, grouping: AlbumGrouping) -> String {
+    if grouping == AlbumGrouping::Title {
+        return format!(
+            "alb_{}",
+            key.title
+                .to_lowercase()
+                .replace(' ', "_")
+                .replace(|c: char| !c.is_alphanumeric() && c != '_', "")
+        );
+    }
+
+    let folder = key
+        .folder
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let release_id = key.release_id.map(str::trim).filter(|id| !id.is_empty());
+    let album_artist = key
+        .album_artist
+        .map(normalize_name)
+        .filter(|artist| !artist.is_empty());
+
+    let title = normalize_name(key.title);
+    let by_folder =
+        grouping == AlbumGrouping::Folder || (release_id.is_none() && album_artist.is_none());
+
+    let mut identity = match (release_id, album_artist) {
+        (Some(release_id), _) => format!("mb:{}", release_id.to_lowercase()),
+        (None, Some(artist)) => format!("{artist}\u{1f}{title}"),
+        (None, None) => title,
+    };
+    if by_folder {
+        identity.push('\u{1f}');
+        identity.push_str(&folder);
+    }
+
+    format!("alb_{:016x}", stable_hash(&identity))
 }
 
 pub fn extract_embedded_cover(tag: Option<&Tag>) -> Option<Vec<u8>> {
@@ -23,6 +73,7 @@ pub fn extract_metadata(
     tag: Option<&Tag>,
     properties: &FileProperties,
     track_path: &Path,
+    grouping: AlbumGrouping,
 ) -> Track {
     let artist = tag
         .and_then(|t| t.artist().map(|a| a.to_string()))
@@ -49,20 +100,17 @@ pub fn extract_metadata(
         })
         .unwrap_or_else(|| vec![artist.clone()]);
 
-    let album_title = tag
+    let tagged_album = tag
         .and_then(|t| t.album().map(|a| a.to_string()))
         .unwrap_or_else(|| "Unknown Album".to_string());
+    // `Album (Disc 2)` and `Album [CD1]` are one album with several discs
+    let (album_title, title_disc) = split_disc_suffix(&tagged_album);
+    let album_title = album_title.to_string();
 
     let album_artist = tag
         .and_then(|t| t.get_string(&ItemKey::AlbumArtist))
         .map(|s| s.to_string());
 
-    let parent_path = track_path.parent().map(|p| p.to_string_lossy());
-    let _grouping_key = album_artist
-        .as_deref()
-        .or(parent_path.as_deref())
-        .unwrap_or(&artist);
-
     let title = tag
         .and_then(|t| t.title().map(|t| t.to_string()))
         .or_else(|| {
@@ -76,66 +124,490 @@ pub fn extract_metadata(
         .and_then(|t| t.get_string(&ItemKey::MusicBrainzReleaseId))
         .map(|s| s.to_string());
 
+    let tag_string = |key: &ItemKey| tag_text(tag, key);
+
+    let compilation = tag_string(&ItemKey::FlagCompilation)
+        .is_some_and(|flag| matches!(flag.to_lowercase().as_str(), "1" | "true" | "yes"))
+        || album_artist.as_deref().is_some_and(is_various_artists);
+
+    // discs ripped into `CD1/`, `CD2/` group by the folder above them
+    let folder = track_path.parent();
+    let folder_disc = folder
+        .and_then(Path::file_name)
+        .and_then(|name| disc_label_number(&name.to_string_lossy()));
+    let album_folder = match folder_disc {
+        Some(_) => folder.and_then(Path::parent),
+        None => folder,
+    };
+
+    let tag_list = |key: &ItemKey| -> Vec<String> {
+        let mut values: Vec<String> = Vec::new();
+        for value in tag.into_iter().flat_map(|t| t.get_strings(key)) {
+            for part in value.split([';', '\0']) {
+                let part = part.trim();
+                if !part.is_empty() && !values.iter().any(|v| v.eq_ignore_ascii_case(part)) {
+                    values.push(part.to_string());
+                }
+            }
+        }
+        values
+    };
+
+    let date = tag_string(&ItemKey::RecordingDate)
+        .or_else(|| tag_string(&ItemKey::ReleaseDate))
+        .or_else(|| tag_string(&ItemKey::Year))
+        .or_else(|| tag.and_then(|t| t.year()).map(|y| y.to_string()));
+
+    let bpm = tag_string(&ItemKey::IntegerBpm)
+        .or_else(|| tag_string(&ItemKey::Bpm))
+        .and_then(|bpm| bpm.parse::<f32>().ok())
+        .filter(|bpm| *bpm > 0.0)
+        .map(|bpm| bpm.round() as u32);
+    let rating = tag_string(&ItemKey::Unknown("RATING".into()))
+        .and_then(|r| r.parse::<u32>().ok())
+        .and_then(stars_from_rating);
+
+    let album_id = make_album_id(
+        &AlbumKey {
+            title: &album_title,
+            album_artist: if compilation {
+                Some(VARIOUS_ARTISTS)
+            } else {
+                album_artist.as_deref()
+            },
+            release_id: musicbrainz_release_id.as_deref(),
+            folder: album_folder,
+        },
+        grouping,
+    );
+
     Track {
         path: track_path.to_path_buf(),
-        album_id: make_album_id(&album_title),
+        album_id,
         title,
         artist,
         artists,
         album: album_title,
         khz: properties.sample_rate().unwrap_or(0),
-        bitrate: properties.bit_depth().unwrap_or(0),
+        bitrate: properties
+            .audio_bitrate()
+            .or_else(|| properties.overall_bitrate())
+            .unwrap_or(0),
+        bit_depth: properties.bit_depth(),
         duration: properties.duration().as_secs()
             + u64::from(properties.duration().subsec_nanos() > 0),
         track_number: tag.and_then(|t| t.track()),
-        disc_number: tag.and_then(|t| t.disk()),
+        disc_number: tag.and_then(|t| t.disk()).or(title_disc).or(folder_disc),
         musicbrainz_release_id,
         playlist_item_id: None,
+        album_artist,
+        compilation,
+        composer: tag_string(&ItemKey::Composer),
+        date,
+        genres: tag_list(&ItemKey::Genre),
+        bpm,
+        isrc: tag_string(&ItemKey::Isrc),
+        label: tag_string(&ItemKey::Label).or_else(|| tag_string(&ItemKey::Publisher)),
+        comment: tag_string(&ItemKey::Comment),
+        musicbrainz_recording_id: tag_string(&ItemKey::MusicBrainzRecordingId),
+        musicbrainz_artist_ids: tag_list(&ItemKey::MusicBrainzArtistId),
+        file_size: None,
+        codec: None,
+        date_added: None,
+        rating,
     }
 }
 
-pub fn read(track_path: &Path, cover_cache: &Path, library: &mut Library) -> Option<Track> {
-    let tagged_file = Probe::open(track_path).ok()?.read().ok()?;
+/// Stars from a `RATING` field: 1–5 as written by taggers that store stars,
+/// anything above as the 0–100 scale tag_writer::write_rating uses.
+fn stars_from_rating(value: u32) -> Option<u8> {
+    let stars = if value <= 5 {
+        value
+    } else {
+        ((value + 10) / 20).min(5)
+    };
+    (stars > 0).then_some(stars as u8)
+}
+
+/// Stars from a POPM frame, preferring the one tag_writer::write_rating
+/// writes. The 0–255 byte follows the Windows Media Player steps.
+fn popm_stars(tag: &Id3v2Tag) -> Option<u8> {
+    let ratings: Vec<(bool, u8)> = tag
+        .into_iter()
+        .filter_map(|frame| match frame {
+            Frame::Popularimeter(popm) => Some((popm.email == POPM_EMAIL, popm.rating)),
+            _ => None,
+        })
+        .collect();
+    let rating = ratings
+        .iter()
+        .find(|(ours, _)| *ours)
+        .or_else(|| ratings.iter().find(|(_, rating)| *rating > 0))
+        .map(|(_, rating)| *rating)?;
+    match rating {
+        0 => None,
+        1..=31 => Some(1),
+        32..=95 => Some(2),
+        96..=159 => Some(3),
+        160..=223 => Some(4),
+        _ => Some(5),
+    }
+}
+
+fn tag_text(tag: Option<&Tag>, key: &ItemKey) -> Option<String> {
+    tag.and_then(|t| t.get_string(key))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+pub fn codec_name(file_type: FileType) -> Option<&'static str> {
+    Some(match file_type {
+        FileType::Aac => "AAC",
+        FileType::Aiff => "AIFF",
+        FileType::Ape => "APE",
+        FileType::Flac => "FLAC",
+        FileType::Mpeg => "MP3",
+        FileType::Mp4 => "MP4",
+        FileType::Mpc => "Musepack",
+        FileType::Opus => "Opus",
+        FileType::Vorbis => "Vorbis",
+        FileType::Speex => "Speex",
+        FileType::Wav => "WAV",
+        FileType::WavPack => "WavPack",
+        _ => return None,
+    })
+}
+
+/// Artist entities for every track artist plus the album artist. Sort names and
+/// MusicBrainz IDs are only trusted when the tag names a single artist, since the
+/// tag formats do not say which entry of a multi-artist credit they belong to.
+pub fn extract_artists(tag: Option<&Tag>, track: &Track, track_path: &Path) -> Vec<Artist> {
+    let tag_string = |key: &ItemKey| tag_text(tag, key);
+
+    let mut artists: Vec<Artist> = Vec::new();
+    let single_track_artist = track.artists.len() == 1;
+    for name in &track.artists {
+        let mut artist = Artist::new(name);
+        if single_track_artist {
+            artist.sort_name = tag_string(&ItemKey::TrackArtistSortOrder);
+            artist.musicbrainz_id = tag_string(&ItemKey::MusicBrainzArtistId);
+        }
+        artists.push(artist);
+    }
+
+    // "Various Artists" is not a performer, so it gets no artist page
+    if let Some(album_artist) = track
+        .album_artist
+        .as_deref()
+        .filter(|name| !is_various_artists(name))
+    {
+        let mut artist = Artist::new(album_artist);
+        artist.sort_name = tag_string(&ItemKey::AlbumArtistSortOrder);
+        artist.musicbrainz_id = tag_string(&ItemKey::MusicBrainzReleaseArtistId);
+        artist.image_path = track_path.parent().and_then(find_artist_image);
+        match artists.iter_mut().find(|a| a.id == artist.id) {
+            Some(existing) => {
+                existing.sort_name = existing.sort_name.take().or(artist.sort_name);
+                existing.musicbrainz_id = existing.musicbrainz_id.take().or(artist.musicbrainz_id);
+                existing.image_path = artist.image_path;
+            }
+            None => artists.push(artist),
+        }
+    }
+
+    artists
+}
+
+/// Everything a scan worker can extract from a file without touching the library.
+pub struct ScannedTrack {
+    pub track: Track,
+    pub album: Album,
+    pub artists: Vec<Artist>,
+}
+
+/// Covers already resolved in a scan, by folder and album title, so the tracks
+/// of an album share one lookup instead of each reading and hashing the art.
+#[derive(Default)]
+pub struct CoverMemo(Mutex<HashMap<(PathBuf, String), Option<PathBuf>>>);
+
+/// Reads a file's tags and copies its cover, embedded or from the folder, into
+/// `cover_cache`, unless `memo` already has the album's. Covers are stored by
+/// content hash, so this is safe to run from several scan workers at once.
+pub fn probe(
+    track_path: &Path,
+    grouping: AlbumGrouping,
+    covers: &CoverOptions,
+    cover_cache: &Path,
+    memo: &CoverMemo,
+) -> Option<ScannedTrack> {
+    let probed = Probe::open(track_path).ok()?.guess_file_type().ok()?;
+    // the generic tag has no POPM, so MP3s are read as such to get at it
+    let (tagged_file, popm) = if probed.file_type() == Some(FileType::Mpeg) {
+        let mut file = std::fs::File::open(track_path).ok()?;
+        let mpeg = MpegFile::read_from(&mut file, ParseOptions::new()).ok()?;
+        let popm = mpeg.id3v2().and_then(popm_stars);
+        (TaggedFile::from(mpeg), popm)
+    } else {
+        (probed.read().ok()?, None)
+    };
     let properties = tagged_file.properties();
     let tag = tagged_file
         .primary_tag()
         .or_else(|| tagged_file.first_tag());
 
-    let track = extract_metadata(tag, properties, track_path);
-    let album_id = track.album_id.clone();
+    let mut track = extract_metadata(tag, properties, track_path, grouping);
+    if popm.is_some() {
+        track.rating = popm;
+    }
+    track.codec = codec_name(tagged_file.file_type()).map(str::to_string);
+    track.file_size = std::fs::metadata(track_path).ok().map(|m| m.len());
+    track.date_added = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs());
+    let artists = extract_artists(tag, &track, track_path);
 
-    let album_artist = tag
-        .and_then(|t| t.get_string(&ItemKey::AlbumArtist))
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| track.artist.clone());
+    let album_artist = if track.compilation {
+        VARIOUS_ARTISTS.to_string()
+    } else {
+        track
+            .album_artist
+            .clone()
+            .unwrap_or_else(|| track.artist.clone())
+    };
+
+    let memo_key = (
+        track_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+        track.album.clone(),
+    );
+    let known = memo.0.lock().ok().and_then(|m| m.get(&memo_key).cloned());
+    let cover_path = match known {
+        Some(cover_path) => cover_path,
+        None => {
+            let embedded = || extract_embedded_cover(tag);
+            let folder = || {
+                let dir = track_path.parent()?;
+                std::fs::read(find_folder_cover(dir, &track.album, &covers.folder_names)?).ok()
+            };
+            let cover_bytes = match covers.prefer {
+                CoverSource::Embedded => embedded().or_else(folder),
+                CoverSource::Folder => folder().or_else(embedded),
+            };
+            let cover_path = cover_bytes.and_then(|bytes| save_cover(&bytes, cover_cache).ok());
+            if let Ok(mut m) = memo.0.lock() {
+                m.insert(memo_key, cover_path.clone());
+            }
+            cover_path
+        }
+    };
+
+    let genre = tag
+        .and_then(|t| t.genre().map(|g| g.to_string()))
+        .unwrap_or_else(|| "Unknown".to_string());
 
-    let album_exists = library.albums.iter().any(|a| a.id == album_id);
+    let year = tag.and_then(|t| t.year()).unwrap_or(0) as u16;
+
+    let album = Album {
+        id: track.album_id.clone(),
+        title: track.album.clone(),
+        artist: album_artist,
+        genre,
+        year,
+        cover_path,
+        compilation: track.compilation,
+    };
+
+    Some(ScannedTrack {
+        track,
+        album,
+        artists,
+    })
+}
+
+pub fn merge(scanned: ScannedTrack, library: &mut Library) -> Track {
+    let ScannedTrack {
+        track,
+        album,
+        artists,
+    } = scanned;
+
+    for artist in artists {
+        library.add_artist(artist);
+    }
+
+    let album_exists = library.albums.iter().any(|a| a.id == album.id);
 
     if !album_exists {
-        let mut cover = None;
+        library.add_album(album);
+    }
 
-        if let Some(bytes) = extract_embedded_cover(tag) {
-            cover = save_cover(&album_id, &bytes, cover_cache).ok();
-        } else if let Some(folder_cover) = find_folder_cover(track_path.parent()?) {
-            cover = Some(folder_cover);
-        }
+    library.add_track(track.clone());
+    track
+}
+
+pub fn read(track_path: &Path, cover_cache: &Path, library: &mut Library) -> Option<Track> {
+    let scanned = probe(
+        track_path,
+        library.album_grouping.unwrap_or_default(),
+        &library.cover_options.clone().unwrap_or_default(),
+        cover_cache,
+        &CoverMemo::default(),
+    )?;
+    Some(merge(scanned, library))
+}
+
+/// Re-reads a file that is already in the library after its tags changed. The
+/// track keeps its `date_added`, moves to whichever album its new tags group it
+/// under, and albums or artists left without tracks are dropped.
+pub fn reread(track_path: &Path, cover_cache: &Path, library: &mut Library) -> Option<Track> {
+    let scanned = probe(
+        track_path,
+        library.album_grouping.unwrap_or_default(),
+        &library.cover_options.clone().unwrap_or_default(),
+        cover_cache,
+        &CoverMemo::default(),
+    )?;
+    Some(merge_reread(scanned, library))
+}
 
-        let genre = tag
-            .and_then(|t| t.genre().map(|g| g.to_string()))
-            .unwrap_or_else(|| "Unknown".to_string());
+/// The library half of [`reread`], for callers that probe the file off the
+/// thread that owns the library.
+pub fn merge_reread(scanned: ScannedTrack, library: &mut Library) -> Track {
+    let ScannedTrack {
+        mut track,
+        album,
+        artists,
+    } = scanned;
 
-        let year = tag.and_then(|t| t.year()).unwrap_or(0) as u16;
+    if let Some(previous) = library.tracks.iter().find(|t| t.path == track.path) {
+        track.date_added = previous.date_added.or(track.date_added);
+    }
 
-        library.add_album(Album {
-            id: album_id.clone(),
-            title: track.album.clone(),
-            artist: album_artist,
-            genre,
-            year,
-            cover_path: cover,
-        });
+    for artist in artists {
+        library.add_artist(artist);
     }
 
+    // unlike a scan, always replace the album so edited titles and covers show up
+    library.add_album(album);
     library.add_track(track.clone());
-    Some(track)
+    library.prune_albums();
+    library.prune_artists();
+
+    track
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AlbumKey, make_album_id, popm_stars, stars_from_rating};
+    use crate::models::AlbumGrouping;
+    use crate::tag_writer::POPM_EMAIL;
+    use lofty::id3::v2::{Frame, Id3v2Tag, PopularimeterFrame};
+    use std::path::Path;
+
+    fn key<'a>(title: &'a str, album_artist: Option<&'a str>, folder: &'a str) -> AlbumKey<'a> {
+        AlbumKey {
+            title,
+            album_artist,
+            release_id: None,
+            folder: Some(Path::new(folder)),
+        }
+    }
+
+    #[test]
+    fn same_title_from_different_artists_stays_apart() {
+        let a = make_album_id(
+            &key("Greatest Hits", Some("Queen"), "/m/a"),
+            AlbumGrouping::AlbumArtist,
+        );
+        let b = make_album_id(
+            &key("Greatest Hits", Some("ABBA"), "/m/b"),
+            AlbumGrouping::AlbumArtist,
+        );
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn album_artist_grouping_ignores_folder_and_case() {
+        let a = make_album_id(
+            &key("Abbey Road", Some("The Beatles"), "/m/CD1"),
+            AlbumGrouping::AlbumArtist,
+        );
+        let b = make_album_id(
+            &key("abbey  road", Some("the beatles"), "/m/CD2"),
+            AlbumGrouping::AlbumArtist,
+        );
+        assert_eq!(a, b);
+        assert_ne!(
+            make_album_id(
+                &key("Abbey Road", Some("The Beatles"), "/m/CD1"),
+                AlbumGrouping::Folder
+            ),
+            make_album_id(
+                &key("Abbey Road", Some("The Beatles"), "/m/CD2"),
+                AlbumGrouping::Folder
+            ),
+        );
+    }
+
+    #[test]
+    fn untagged_album_artist_falls_back_to_folder() {
+        let a = make_album_id(&key("Untitled", None, "/m/a"), AlbumGrouping::AlbumArtist);
+        let b = make_album_id(&key("Untitled", None, "/m/b"), AlbumGrouping::AlbumArtist);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn release_id_wins_over_tags() {
+        let mut a = key("Album", Some("Artist"), "/m/a");
+        a.release_id = Some("f5093c06-23e3-404f-aeaa-40f72885ee3a");
+        let mut b = key("Album (Deluxe)", Some("Various"), "/m/b");
+        b.release_id = Some("F5093C06-23E3-404F-AEAA-40F72885EE3A");
+        assert_eq!(
+            make_album_id(&a, AlbumGrouping::AlbumArtist),
+            make_album_id(&b, AlbumGrouping::AlbumArtist)
+        );
+    }
+
+    #[test]
+    fn ratings_read_as_stars_or_percent() {
+        assert_eq!(stars_from_rating(0), None);
+        assert_eq!(stars_from_rating(1), Some(1));
+        assert_eq!(stars_from_rating(4), Some(4));
+        assert_eq!(stars_from_rating(5), Some(5));
+        assert_eq!(stars_from_rating(20), Some(1));
+        assert_eq!(stars_from_rating(60), Some(3));
+        assert_eq!(stars_from_rating(100), Some(5));
+    }
+
+    #[test]
+    fn popm_prefers_our_frame() {
+        let mut tag = Id3v2Tag::default();
+        assert_eq!(popm_stars(&tag), None);
+        tag.insert(Frame::Popularimeter(PopularimeterFrame::new(
+            "someone@example.com".to_string(),
+            255,
+            0,
+        )));
+        assert_eq!(popm_stars(&tag), Some(5));
+        tag.insert(Frame::Popularimeter(PopularimeterFrame::new(
+            POPM_EMAIL.to_string(),
+            64,
+            0,
+        )));
+        assert_eq!(popm_stars(&tag), Some(2));
+    }
+
+    #[test]
+    fn title_grouping_keeps_legacy_ids() {
+        assert_eq!(
+            make_album_id(
+                &key("Greatest Hits!", Some("Queen"), "/m"),
+                AlbumGrouping::Title
+            ),
+            "alb_greatest_hits"
+        );
+    }
 }
@@ -1,7 +1,111 @@
 use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// How local tracks are grouped into albums when the library is scanned.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum AlbumGrouping {
+    /// Album title only. This is how albums were grouped before album artists were
+    /// taken into account, and it keeps the old `alb_<title>` IDs.
+    Title,
+    /// MusicBrainz release when tagged, otherwise album artist + title. Tracks without
+    /// an album artist are told apart by their folder.
+    #[default]
+    AlbumArtist,
+    /// Like `AlbumArtist`, but albums in different folders are never merged.
+    Folder,
+}
+
+/// Whether playlists without their own cover get a grid of their albums' covers.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum PlaylistCollage {
+    Off,
+    /// 2×2 grid of the first four albums.
+    #[default]
+    Grid2,
+    /// 3×3 grid, or 2×2 when the playlist spans fewer than nine albums.
+    Grid3,
+}
+
+impl PlaylistCollage {
+    /// Largest grid side this setting allows, `None` when collages are off.
+    pub fn max_side(self) -> Option<u32> {
+        match self {
+            Self::Off => None,
+            Self::Grid2 => Some(2),
+            Self::Grid3 => Some(3),
+        }
+    }
+}
+
+/// Which cover wins when a file has an embedded picture and its folder has an image.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum CoverSource {
+    #[default]
+    Embedded,
+    Folder,
+}
+
+/// How scans pick album art.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CoverOptions {
+    #[serde(default)]
+    pub prefer: CoverSource,
+    /// Folder image names in priority order; see [`crate::utils::find_folder_cover`].
+    #[serde(default = "default_cover_names")]
+    pub folder_names: Vec<String>,
+}
+
+impl Default for CoverOptions {
+    fn default() -> Self {
+        Self {
+            prefer: CoverSource::default(),
+            folder_names: default_cover_names(),
+        }
+    }
+}
+
+pub fn default_cover_names() -> Vec<String> {
+    ["cover", "folder", "front", "{album}", "album", "albumart*"]
+        .map(String::from)
+        .to_vec()
+}
+
+/// Which files under the music folders scans leave out. Every scan applies these
+/// to tracks already in the library too.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScanRules {
+    /// Gitignore-style patterns per music folder, relative to that folder. They
+    /// behave like a `.kopuzignore` at the top of the folder.
+    #[serde(default)]
+    pub excludes: HashMap<PathBuf, Vec<String>>,
+    /// Skips files and folders whose name starts with a dot.
+    #[serde(default = "default_true")]
+    pub skip_hidden: bool,
+    /// Shortest track to keep, in seconds. `0` keeps everything.
+    #[serde(default)]
+    pub min_duration: u64,
+    /// Single files hidden from the library, such as resolved duplicates.
+    #[serde(default)]
+    pub hidden_files: HashSet<PathBuf>,
+}
+
+impl Default for ScanRules {
+    fn default() -> Self {
+        Self {
+            excludes: HashMap::new(),
+            skip_hidden: true,
+            min_duration: 0,
+            hidden_files: HashSet::new(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Album {
     pub id: String,
@@ -10,9 +114,96 @@ pub struct Album {
     pub genre: String,
     pub year: u16,
     pub cover_path: Option<PathBuf>,
+    /// Various-artists release; listed under Compilations rather than an artist.
+    #[serde(default)]
+    pub compilation: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Artist {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub sort_name: Option<String>,
+    #[serde(default)]
+    pub musicbrainz_id: Option<String>,
+    /// Local image file, or `directurl:<url>` for server artists.
+    #[serde(default)]
+    pub image_path: Option<PathBuf>,
+}
+
+impl Artist {
+    pub fn new(name: &str) -> Self {
+        Self {
+            id: make_artist_id(name),
+            name: name.trim().to_string(),
+            sort_name: None,
+            musicbrainz_id: None,
+            image_path: None,
+        }
+    }
+
+    /// Key used to order artist lists, honouring the sort-name tag when present.
+    pub fn sort_key(&self) -> String {
+        self.sort_name
+            .as_deref()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(&self.name)
+            .trim()
+            .to_lowercase()
+    }
+
+    /// Fills in anything `other` knows that this entry does not.
+    fn absorb(&mut self, other: Artist) {
+        if self.sort_name.is_none() {
+            self.sort_name = other.sort_name;
+        }
+        if self.musicbrainz_id.is_none() {
+            self.musicbrainz_id = other.musicbrainz_id;
+        }
+        if self.image_path.is_none() {
+            self.image_path = other.image_path;
+        }
+    }
+}
+
+/// Artist IDs come from the normalised name so the same artist gets the same ID
+/// on every scan, whichever track or album it was first seen on.
+pub fn make_artist_id(name: &str) -> String {
+    format!("art_{:016x}", stable_hash(normalize_name(name)))
+}
+
+/// Album artist shown for compilations that are not tagged with one.
+pub const VARIOUS_ARTISTS: &str = "Various Artists";
+
+/// Whether an album artist is one of the usual spellings of "Various Artists".
+pub fn is_various_artists(name: &str) -> bool {
+    matches!(
+        normalize_name(name).as_str(),
+        "various artists" | "various" | "va" | "v.a." | "v/a"
+    )
+}
+
+/// Collapses whitespace and case so differently typed tags compare equal.
+pub(crate) fn normalize_name(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+// FNV-1a, so generated IDs stay the same across builds and platforms.
+pub(crate) fn stable_hash(value: impl AsRef<[u8]>) -> u64 {
+    value
+        .as_ref()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct Track {
     pub path: PathBuf,
     pub album_id: String,
@@ -21,7 +212,11 @@ pub struct Track {
     pub album: String,
     pub duration: u64,
     pub khz: u32,
-    pub bitrate: u8,
+    /// Average bitrate in kbps.
+    #[serde(default)]
+    pub bitrate: u32,
+    #[serde(default)]
+    pub bit_depth: Option<u8>,
     pub track_number: Option<u32>,
     pub disc_number: Option<u32>,
     #[serde(default)]
@@ -30,6 +225,49 @@ pub struct Track {
     pub playlist_item_id: Option<String>,
     #[serde(default)]
     pub artists: Vec<String>,
+    #[serde(default)]
+    pub album_artist: Option<String>,
+    /// Set by the compilation flag (TCMP, cpil, COMPILATION) or a various-artists
+    /// album artist.
+    #[serde(default)]
+    pub compilation: bool,
+    #[serde(default)]
+    pub composer: Option<String>,
+    /// Release date as tagged, e.g. `1997`, `1997-05` or `1997-05-21`.
+    #[serde(default)]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    #[serde(default)]
+    pub bpm: Option<u32>,
+    #[serde(default)]
+    pub isrc: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub musicbrainz_recording_id: Option<String>,
+    #[serde(default)]
+    pub musicbrainz_artist_ids: Vec<String>,
+    #[serde(default)]
+    pub file_size: Option<u64>,
+    #[serde(default)]
+    pub codec: Option<String>,
+    /// Unix timestamp (seconds) of when the track first entered the library.
+    #[serde(default)]
+    pub date_added: Option<u64>,
+    /// 1–5 stars: the user's own rating, or what the file tags or the server report.
+    /// Kept in step with [`FavoritesStore::track_ratings`].
+    #[serde(default)]
+    pub rating: Option<u8>,
+}
+
+impl Track {
+    pub fn year(&self) -> Option<u16> {
+        let date = self.date.as_deref()?.trim();
+        date.get(..4)?.parse().ok()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -43,15 +281,36 @@ pub struct Library {
     pub tracks: Vec<Track>,
     pub albums: Vec<Album>,
     #[serde(default)]
+    pub artists: Vec<Artist>,
+    #[serde(default)]
     pub jellyfin_tracks: Vec<Track>,
     #[serde(default)]
     pub jellyfin_albums: Vec<Album>,
     #[serde(default)]
+    pub jellyfin_artists: Vec<Artist>,
+    #[serde(default)]
     pub jellyfin_genres: Vec<(String, String)>,
     #[serde(default)]
     pub server_artist_images: std::collections::HashMap<String, String>,
+    /// Grouping the local album IDs were built with. `None` for libraries written
+    /// before grouping was configurable, which forces a full rescan.
+    #[serde(default)]
+    pub album_grouping: Option<AlbumGrouping>,
+    /// Cover options the local albums were built with; like `album_grouping`, a
+    /// mismatch forces a full rescan.
+    #[serde(default)]
+    pub cover_options: Option<CoverOptions>,
+    /// Version of the tag extraction the local tracks were read with. Scans re-read
+    /// every file when this is older than [`METADATA_VERSION`].
+    #[serde(default)]
+    pub metadata_version: u32,
 }
 
+/// Bumped whenever `extract_metadata` starts filling new track fields, or scans
+/// start storing something differently (v2: content-hashed covers with variants,
+/// v3: compilations and disc sets, v4: star ratings from POPM and 1–5 RATING).
+pub const METADATA_VERSION: u32 = 4;
+
 fn deserialize_root_paths<'de, D>(deserializer: D) -> Result<Vec<PathBuf>, D::Error>
 where
     D: Deserializer<'de>,
@@ -92,7 +351,7 @@ impl Library {
         }
         let data = serde_json::to_string(self)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
-        fs::write(path, data)
+        utils::artwork::write_atomically(path, data.as_bytes())
     }
 
     pub fn add_track(&mut self, track: Track) {
@@ -115,6 +374,77 @@ impl Library {
         }
     }
 
+    pub fn add_artist(&mut self, artist: Artist) {
+        if let Some(existing) = self.artists.iter_mut().find(|a| a.id == artist.id) {
+            existing.absorb(artist);
+        } else {
+            self.artists.push(artist);
+        }
+    }
+
+    /// Drops local albums that no track belongs to any more.
+    pub fn prune_albums(&mut self) {
+        let referenced: std::collections::HashSet<&str> =
+            self.tracks.iter().map(|t| t.album_id.as_str()).collect();
+        self.albums.retain(|a| referenced.contains(a.id.as_str()));
+    }
+
+    /// Drops local artists that no track or album refers to any more.
+    pub fn prune_artists(&mut self) {
+        let referenced = referenced_artist_ids(&self.tracks, &self.albums);
+        self.artists.retain(|a| referenced.contains(&a.id));
+    }
+
+    /// Rebuilds server artists from the synced tracks and albums, keeping images
+    /// the server reported in `server_artist_images`.
+    pub fn rebuild_server_artists(&mut self) {
+        let mut artists: Vec<Artist> = Vec::new();
+        let names = self
+            .jellyfin_albums
+            .iter()
+            .map(|a| a.artist.as_str())
+            .chain(
+                self.jellyfin_tracks
+                    .iter()
+                    .flat_map(|t| t.artists.iter().map(String::as_str)),
+            );
+        for name in names {
+            if name.trim().is_empty() {
+                continue;
+            }
+            let artist = Artist::new(name);
+            if artists.iter().any(|a| a.id == artist.id) {
+                continue;
+            }
+            let image_path = self
+                .server_artist_images
+                .get(name)
+                .map(|url| PathBuf::from(format!("directurl:{url}")))
+                .or_else(|| {
+                    self.jellyfin_albums
+                        .iter()
+                        .find(|a| make_artist_id(&a.artist) == artist.id)
+                        .and_then(|a| a.cover_path.clone())
+                });
+            artists.push(Artist {
+                image_path,
+                ..artist
+            });
+        }
+        self.jellyfin_artists = artists;
+    }
+
+    pub fn artist(&self, id: &str) -> Option<&Artist> {
+        self.artists
+            .iter()
+            .chain(self.jellyfin_artists.iter())
+            .find(|a| a.id == id)
+    }
+
+    pub fn artist_by_name(&self, name: &str) -> Option<&Artist> {
+        self.artist(&make_artist_id(name))
+    }
+
     pub fn remove_track(&mut self, path: &Path) {
         self.tracks.retain(|t| t.path != path);
     }
@@ -125,10 +455,19 @@ impl Library {
     }
 }
 
+fn referenced_artist_ids(tracks: &[Track], albums: &[Album]) -> std::collections::HashSet<String> {
+    tracks
+        .iter()
+        .flat_map(|t| t.artists.iter().chain(t.album_artist.iter()))
+        .chain(albums.iter().map(|a| &a.artist))
+        .map(|name| make_artist_id(name))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Library;
-    use std::path::PathBuf;
+    use super::{FavoritesStore, Library, Track, rating_key};
+    use std::path::{Path, PathBuf};
 
     #[test]
     fn library_deserializes_legacy_root_path() {
@@ -142,6 +481,35 @@ mod tests {
 
         assert_eq!(library.root_paths, vec![PathBuf::from("/music")]);
     }
+
+    #[test]
+    fn stored_ratings_override_server_ratings() {
+        assert_eq!(rating_key(Path::new("subsonic:42:none")), "42");
+        assert_eq!(rating_key(Path::new("/music/a.flac")), "/music/a.flac");
+
+        let mut tracks = vec![
+            Track {
+                path: PathBuf::from("jellyfin:abc:tag"),
+                rating: Some(3),
+                ..Default::default()
+            },
+            Track {
+                path: PathBuf::from("/music/a.flac"),
+                ..Default::default()
+            },
+        ];
+        let mut favorites = FavoritesStore::default();
+        assert_eq!(favorites.track_rating(&tracks[0]), 3);
+
+        favorites.set_track_rating(Path::new("jellyfin:abc:other-tag"), 0);
+        favorites.set_track_rating(Path::new("/music/a.flac"), 9);
+
+        assert!(favorites.apply_ratings(&mut tracks));
+        assert_eq!(tracks[0].rating, None);
+        assert_eq!(tracks[1].rating, Some(5));
+        assert!(!favorites.ratings_outdated(&tracks));
+        assert!(!favorites.apply_ratings(&mut tracks));
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -151,6 +519,9 @@ pub struct Playlist {
     pub tracks: Vec<PathBuf>,
     #[serde(default)]
     pub cover_path: Option<PathBuf>,
+    /// Generated collage of the playlist's albums, shown when `cover_path` is unset.
+    #[serde(default)]
+    pub collage_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -162,6 +533,14 @@ pub struct JellyfinPlaylist {
     pub image_tag: Option<String>,
     #[serde(default)]
     pub cover_path: Option<PathBuf>,
+    /// Generated collage of the playlist's albums. Server images set elsewhere win
+    /// over it unless the collage was the one uploaded.
+    #[serde(default)]
+    pub collage_path: Option<PathBuf>,
+    /// File name of the collage last uploaded as the server image, so it isn't
+    /// sent again on every start.
+    #[serde(default)]
+    pub uploaded_collage: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -178,6 +557,8 @@ pub struct PlaylistStore {
     pub jellyfin_playlists: Vec<JellyfinPlaylist>,
     #[serde(default)]
     pub folders: Vec<PlaylistFolder>,
+    #[serde(default)]
+    pub smart_playlists: Vec<crate::smart::SmartPlaylist>,
 }
 
 impl PlaylistStore {
@@ -197,7 +578,7 @@ impl PlaylistStore {
         }
         let data = serde_json::to_string(self)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
-        fs::write(path, data)
+        utils::artwork::write_atomically(path, data.as_bytes())
     }
 }
 
@@ -207,6 +588,35 @@ pub struct FavoritesStore {
     pub local_favorites: Vec<PathBuf>,
     #[serde(default)]
     pub jellyfin_favorites: Vec<String>,
+    #[serde(default)]
+    pub favorite_artists: Vec<String>,
+    /// 0–5 stars keyed by [`rating_key`]. `0` is kept, so clearing a rating the
+    /// server reported sticks.
+    #[serde(default)]
+    pub track_ratings: HashMap<String, u8>,
+    /// 0–5 stars keyed by album ID.
+    #[serde(default)]
+    pub album_ratings: HashMap<String, u8>,
+}
+
+/// Item ID of a server track or album, from paths like `jellyfin:<id>:<image tag>`.
+pub fn server_item_id(path: &str) -> Option<&str> {
+    match path.split_once(':') {
+        Some(("jellyfin" | "subsonic" | "custom", rest)) => {
+            rest.split(':').next().filter(|id| !id.is_empty())
+        }
+        _ => None,
+    }
+}
+
+/// Ratings are stored by server item ID for server tracks and by path for local
+/// ones, the same keys listen counts use for local files.
+pub fn rating_key(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    match server_item_id(&path) {
+        Some(id) => id.to_string(),
+        None => path.into_owned(),
+    }
 }
 
 impl FavoritesStore {
@@ -226,7 +636,7 @@ impl FavoritesStore {
         }
         let data = serde_json::to_string_pretty(self)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
-        fs::write(path, data)
+        utils::artwork::write_atomically(path, data.as_bytes())
     }
 
     pub fn is_local_favorite(&self, path: &Path) -> bool {
@@ -237,6 +647,68 @@ impl FavoritesStore {
         self.jellyfin_favorites.iter().any(|i| i == id)
     }
 
+    /// Works for both kinds of track: server tracks carry their item id in the
+    /// path, as in `jellyfin:<id>:<image tag>`.
+    pub fn contains_track(&self, track: &Track) -> bool {
+        match server_item_id(&track.path.to_string_lossy()) {
+            Some(id) => self.is_jellyfin_favorite(id),
+            None => self.is_local_favorite(&track.path),
+        }
+    }
+
+    /// The user's rating for a track, falling back to what the server reported.
+    pub fn track_rating(&self, track: &Track) -> u8 {
+        self.track_ratings
+            .get(&rating_key(&track.path))
+            .copied()
+            .or(track.rating)
+            .unwrap_or(0)
+    }
+
+    pub fn set_track_rating(&mut self, path: &Path, stars: u8) {
+        self.track_ratings.insert(rating_key(path), stars.min(5));
+    }
+
+    pub fn album_rating(&self, album_id: &str) -> u8 {
+        self.album_ratings.get(album_id).copied().unwrap_or(0)
+    }
+
+    pub fn set_album_rating(&mut self, album_id: String, stars: u8) {
+        if stars == 0 {
+            self.album_ratings.remove(&album_id);
+        } else {
+            self.album_ratings.insert(album_id, stars.min(5));
+        }
+    }
+
+    /// The rating a track should carry, if the store has one for it.
+    fn stored_rating(&self, track: &Track) -> Option<Option<u8>> {
+        let stars = *self.track_ratings.get(&rating_key(&track.path))?;
+        Some(Some(stars).filter(|s| *s > 0))
+    }
+
+    /// Whether [`apply_ratings`](Self::apply_ratings) would change any of `tracks`.
+    pub fn ratings_outdated(&self, tracks: &[Track]) -> bool {
+        tracks
+            .iter()
+            .any(|t| self.stored_rating(t).is_some_and(|r| r != t.rating))
+    }
+
+    /// Copies stored ratings onto `tracks` so sorting and filters see them.
+    /// Returns whether anything changed.
+    pub fn apply_ratings(&self, tracks: &mut [Track]) -> bool {
+        let mut changed = false;
+        for track in tracks {
+            if let Some(rating) = self.stored_rating(track)
+                && track.rating != rating
+            {
+                track.rating = rating;
+                changed = true;
+            }
+        }
+        changed
+    }
+
     pub fn toggle_local(&mut self, path: PathBuf) -> bool {
         if let Some(pos) = self.local_favorites.iter().position(|p| p == &path) {
             self.local_favorites.remove(pos);
@@ -247,6 +719,20 @@ impl FavoritesStore {
         }
     }
 
+    pub fn is_favorite_artist(&self, artist_id: &str) -> bool {
+        self.favorite_artists.iter().any(|i| i == artist_id)
+    }
+
+    pub fn toggle_artist(&mut self, artist_id: String) -> bool {
+        if let Some(pos) = self.favorite_artists.iter().position(|i| i == &artist_id) {
+            self.favorite_artists.remove(pos);
+            false
+        } else {
+            self.favorite_artists.push(artist_id);
+            true
+        }
+    }
+
     pub fn set_jellyfin(&mut self, id: String, is_fav: bool) {
         if is_fav {
             if !self.jellyfin_favorites.contains(&id) {
@@ -0,0 +1,296 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum CollectionKind {
+    Album,
+    Playlist,
+}
+
+/// A server album or playlist marked available offline.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OfflineCollection {
+    pub kind: CollectionKind,
+    pub id: String,
+    pub name: String,
+    /// Server item IDs of its tracks.
+    pub tracks: Vec<String>,
+    /// Pinned tracks are never evicted. Unpinning keeps the files until space
+    /// is needed.
+    #[serde(default = "default_pinned")]
+    pub pinned: bool,
+}
+
+fn default_pinned() -> bool {
+    true
+}
+
+/// A downloaded track on disk.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CachedTrack {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Unix seconds of the last download or play, for LRU eviction.
+    pub last_used: u64,
+}
+
+/// What is downloaded for offline play, what is marked for it and what is
+/// still waiting to download. Tracks are keyed by server item ID.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct OfflineStore {
+    #[serde(default)]
+    pub collections: Vec<OfflineCollection>,
+    #[serde(default)]
+    pub cached: HashMap<String, CachedTrack>,
+    /// Item IDs waiting to download, in order. Kept on disk so downloads carry
+    /// on after a restart.
+    #[serde(default)]
+    pub queue: Vec<String>,
+}
+
+impl OfflineStore {
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = fs::read_to_string(path)?;
+        let store = serde_json::from_str(&data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        Ok(store)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let data = serde_json::to_string(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        utils::artwork::write_atomically(path, data.as_bytes())
+    }
+
+    pub fn collection(&self, kind: CollectionKind, id: &str) -> Option<&OfflineCollection> {
+        self.collections
+            .iter()
+            .find(|c| c.kind == kind && c.id == id)
+    }
+
+    /// Marks a collection available offline, replacing an earlier mark of the
+    /// same one, and queues the tracks not downloaded yet.
+    pub fn add_collection(&mut self, collection: OfflineCollection) {
+        self.collections
+            .retain(|c| !(c.kind == collection.kind && c.id == collection.id));
+        for track in &collection.tracks {
+            if !self.cached.contains_key(track) && !self.queue.contains(track) {
+                self.queue.push(track.clone());
+            }
+        }
+        self.collections.push(collection);
+    }
+
+    /// Drops the offline mark. Its downloads stay but become evictable, and
+    /// queued tracks no other collection wants are dropped.
+    pub fn remove_collection(&mut self, kind: CollectionKind, id: &str) -> bool {
+        let before = self.collections.len();
+        self.collections.retain(|c| !(c.kind == kind && c.id == id));
+        let collections = &self.collections;
+        self.queue
+            .retain(|track| collections.iter().any(|c| c.tracks.contains(track)));
+        self.collections.len() != before
+    }
+
+    pub fn set_pinned(&mut self, kind: CollectionKind, id: &str, pinned: bool) {
+        if let Some(c) = self
+            .collections
+            .iter_mut()
+            .find(|c| c.kind == kind && c.id == id)
+        {
+            c.pinned = pinned;
+        }
+    }
+
+    pub fn is_pinned(&self, track: &str) -> bool {
+        self.collections
+            .iter()
+            .any(|c| c.pinned && c.tracks.iter().any(|t| t == track))
+    }
+
+    pub fn cached_path(&self, track: &str) -> Option<&Path> {
+        self.cached.get(track).map(|c| c.path.as_path())
+    }
+
+    pub fn touch(&mut self, track: &str, now: u64) {
+        if let Some(cached) = self.cached.get_mut(track) {
+            cached.last_used = now;
+        }
+    }
+
+    /// Records a finished download and takes it off the queue.
+    pub fn finish(&mut self, track: &str, path: PathBuf, size: u64, now: u64) {
+        self.queue.retain(|t| t != track);
+        self.cached.insert(
+            track.to_string(),
+            CachedTrack {
+                path,
+                size,
+                last_used: now,
+            },
+        );
+    }
+
+    /// Forgets a download, e.g. when its file has gone missing.
+    pub fn forget(&mut self, track: &str) -> Option<CachedTrack> {
+        self.cached.remove(track)
+    }
+
+    pub fn used_bytes(&self) -> u64 {
+        self.cached.values().map(|c| c.size).sum()
+    }
+
+    pub fn pinned_bytes(&self) -> u64 {
+        self.cached
+            .iter()
+            .filter(|(track, _)| self.is_pinned(track))
+            .map(|(_, c)| c.size)
+            .sum()
+    }
+
+    /// Tracks of `collection` downloaded so far.
+    pub fn downloaded(&self, collection: &OfflineCollection) -> usize {
+        collection
+            .tracks
+            .iter()
+            .filter(|t| self.cached.contains_key(*t))
+            .count()
+    }
+
+    /// Whether `incoming` more bytes fit in `limit` once every unpinned
+    /// download is gone.
+    pub fn has_room(&self, limit: u64, incoming: u64) -> bool {
+        self.pinned_bytes() + incoming <= limit
+    }
+
+    /// Drops the least recently used unpinned downloads until `incoming` more
+    /// bytes fit in `limit`. Returns the files to delete. Drops nothing when
+    /// they wouldn't fit even then.
+    pub fn evict(&mut self, limit: u64, incoming: u64) -> Vec<PathBuf> {
+        let mut used = self.used_bytes();
+        if used + incoming <= limit || !self.has_room(limit, incoming) {
+            return Vec::new();
+        }
+        let mut candidates: Vec<(String, u64)> = self
+            .cached
+            .iter()
+            .filter(|(track, _)| !self.is_pinned(track))
+            .map(|(track, c)| (track.clone(), c.last_used))
+            .collect();
+        candidates.sort_by_key(|(_, last_used)| *last_used);
+
+        let mut removed = Vec::new();
+        for (track, _) in candidates {
+            if used + incoming <= limit {
+                break;
+            }
+            if let Some(cached) = self.cached.remove(&track) {
+                used -= cached.size;
+                removed.push(cached.path);
+            }
+        }
+        removed
+    }
+
+    /// Drops every unpinned download. Returns the files to delete.
+    pub fn clear_unpinned(&mut self) -> Vec<PathBuf> {
+        let unpinned: Vec<String> = self
+            .cached
+            .keys()
+            .filter(|track| !self.is_pinned(track))
+            .cloned()
+            .collect();
+        unpinned
+            .iter()
+            .filter_map(|track| self.cached.remove(track))
+            .map(|c| c.path)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CollectionKind, OfflineCollection, OfflineStore};
+    use std::path::PathBuf;
+
+    fn album(id: &str, tracks: &[&str]) -> OfflineCollection {
+        OfflineCollection {
+            kind: CollectionKind::Album,
+            id: id.into(),
+            name: id.into(),
+            tracks: tracks.iter().map(|t| t.to_string()).collect(),
+            pinned: true,
+        }
+    }
+
+    #[test]
+    fn marking_a_collection_queues_missing_tracks_once() {
+        let mut store = OfflineStore::default();
+        store.finish("a", PathBuf::from("/c/a"), 10, 1);
+        store.add_collection(album("x", &["a", "b", "c"]));
+        store.add_collection(album("y", &["c", "d"]));
+        assert_eq!(store.queue, ["b", "c", "d"]);
+
+        store.remove_collection(CollectionKind::Album, "x");
+        assert_eq!(store.queue, ["c", "d"]);
+        assert!(store.cached_path("a").is_some());
+        assert!(!store.is_pinned("a"));
+    }
+
+    #[test]
+    fn eviction_drops_least_recently_used_unpinned_tracks() {
+        let mut store = OfflineStore::default();
+        store.add_collection(album("x", &["pinned"]));
+        store.finish("pinned", PathBuf::from("/c/pinned"), 50, 1);
+        store.finish("old", PathBuf::from("/c/old"), 30, 2);
+        store.finish("new", PathBuf::from("/c/new"), 30, 3);
+        store.touch("old", 10);
+
+        let removed = store.evict(120, 20);
+        assert_eq!(removed, [PathBuf::from("/c/new")]);
+        assert_eq!(store.used_bytes(), 80);
+        assert_eq!(store.pinned_bytes(), 50);
+
+        let removed = store.evict(70, 20);
+        assert_eq!(removed, [PathBuf::from("/c/old")]);
+        assert!(store.cached_path("pinned").is_some());
+    }
+
+    #[test]
+    fn nothing_is_evicted_for_a_download_that_cannot_fit() {
+        let mut store = OfflineStore::default();
+        store.add_collection(album("x", &["pinned"]));
+        store.finish("pinned", PathBuf::from("/c/pinned"), 50, 1);
+        store.finish("old", PathBuf::from("/c/old"), 30, 2);
+
+        assert!(store.has_room(100, 50));
+        assert!(!store.has_room(100, 51));
+        assert!(store.evict(100, 51).is_empty());
+        assert_eq!(store.used_bytes(), 80);
+    }
+
+    #[test]
+    fn unpinning_makes_a_collection_evictable() {
+        let mut store = OfflineStore::default();
+        store.add_collection(album("x", &["a", "b"]));
+        store.finish("a", PathBuf::from("/c/a"), 10, 1);
+        let x = store
+            .collection(CollectionKind::Album, "x")
+            .unwrap()
+            .clone();
+        assert_eq!(store.downloaded(&x), 1);
+
+        assert!(store.clear_unpinned().is_empty());
+        store.set_pinned(CollectionKind::Album, "x", false);
+        assert_eq!(store.clear_unpinned(), [PathBuf::from("/c/a")]);
+    }
+}
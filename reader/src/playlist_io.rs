@@ -0,0 +1,567 @@
+use crate::duplicates::match_key;
+use crate::models::Track;
+use percent_encoding::{AsciiSet, CONTROLS, percent_decode_str, utf8_percent_encode};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+/// Entries whose lengths differ by more than this many seconds are different edits.
+const DURATION_TOLERANCE: u64 = 2;
+
+/// Characters escaped in XSPF locations; `/` stays so paths remain readable.
+const URI_ESCAPE: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b']')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistFormat {
+    M3u,
+    Pls,
+    Xspf,
+}
+
+impl PlaylistFormat {
+    pub const ALL: [PlaylistFormat; 3] = [
+        PlaylistFormat::M3u,
+        PlaylistFormat::Pls,
+        PlaylistFormat::Xspf,
+    ];
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "m3u" | "m3u8" => Some(PlaylistFormat::M3u),
+            "pls" => Some(PlaylistFormat::Pls),
+            "xspf" => Some(PlaylistFormat::Xspf),
+            _ => None,
+        }
+    }
+
+    /// Extension used when exporting. M3U is always written as UTF-8.
+    pub fn extension(self) -> &'static str {
+        match self {
+            PlaylistFormat::M3u => "m3u8",
+            PlaylistFormat::Pls => "pls",
+            PlaylistFormat::Xspf => "xspf",
+        }
+    }
+}
+
+/// One entry as written in a playlist file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaylistEntry {
+    /// Path, relative path or URL, exactly as in the file.
+    pub location: String,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    /// Seconds.
+    pub duration: Option<u64>,
+}
+
+impl PlaylistEntry {
+    pub fn from_track(track: &Track, location: String) -> Self {
+        Self {
+            location,
+            title: Some(track.title.clone()).filter(|t| !t.is_empty()),
+            artist: Some(track.artist.clone()).filter(|a| !a.is_empty()),
+            duration: Some(track.duration).filter(|d| *d > 0),
+        }
+    }
+
+    /// `Artist - Title` when the file said, otherwise the location.
+    pub fn label(&self) -> String {
+        match (&self.artist, &self.title) {
+            (Some(artist), Some(title)) => format!("{artist} - {title}"),
+            (None, Some(title)) => title.clone(),
+            _ => self.location.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedPlaylist {
+    pub name: Option<String>,
+    pub entries: Vec<PlaylistEntry>,
+}
+
+pub fn parse(format: PlaylistFormat, text: &str) -> Result<ParsedPlaylist, String> {
+    let text = text.trim_start_matches('\u{feff}');
+    match format {
+        PlaylistFormat::M3u => Ok(parse_m3u(text)),
+        PlaylistFormat::Pls => Ok(parse_pls(text)),
+        PlaylistFormat::Xspf => parse_xspf(text),
+    }
+}
+
+/// `Artist - Title` as used by `#EXTINF` and PLS titles.
+fn split_display(display: &str) -> (Option<String>, Option<String>) {
+    let display = display.trim();
+    if display.is_empty() {
+        return (None, None);
+    }
+    match display.split_once(" - ") {
+        Some((artist, title)) => (
+            Some(artist.trim().to_string()),
+            Some(title.trim().to_string()),
+        ),
+        None => (None, Some(display.to_string())),
+    }
+}
+
+/// Negative lengths mean "unknown" in both M3U and PLS.
+fn parse_length(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|n| *n > 0)
+        .map(|n| n as u64)
+}
+
+fn parse_m3u(text: &str) -> ParsedPlaylist {
+    let mut playlist = ParsedPlaylist::default();
+    let mut info: Option<PlaylistEntry> = None;
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some(extinf) = line.strip_prefix("#EXTINF:") {
+            let (length, display) = extinf.split_once(',').unwrap_or((extinf, ""));
+            // the length may be followed by attributes, as in `-1 tvg-id="..."`
+            let length = length.split_whitespace().next().unwrap_or_default();
+            let (artist, title) = split_display(display);
+            info = Some(PlaylistEntry {
+                artist,
+                title,
+                duration: parse_length(length),
+                ..Default::default()
+            });
+        } else if let Some(name) = line.strip_prefix("#PLAYLIST:") {
+            playlist.name = Some(name.trim().to_string()).filter(|n| !n.is_empty());
+        } else if !line.starts_with('#') {
+            let mut entry = info.take().unwrap_or_default();
+            entry.location = line.to_string();
+            playlist.entries.push(entry);
+        }
+    }
+    playlist
+}
+
+fn parse_pls(text: &str) -> ParsedPlaylist {
+    let mut entries: BTreeMap<u32, PlaylistEntry> = BTreeMap::new();
+    for line in text.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let field = ["file", "title", "length"]
+            .into_iter()
+            .find_map(|f| Some((f, key.strip_prefix(f)?.parse::<u32>().ok()?)));
+        let Some((field, index)) = field else {
+            continue;
+        };
+        let entry = entries.entry(index).or_default();
+        match field {
+            "file" => entry.location = value.trim().to_string(),
+            "title" => (entry.artist, entry.title) = split_display(value),
+            _ => entry.duration = parse_length(value),
+        }
+    }
+    ParsedPlaylist {
+        name: None,
+        entries: entries
+            .into_values()
+            .filter(|e| !e.location.is_empty())
+            .collect(),
+    }
+}
+
+fn parse_xspf(text: &str) -> Result<ParsedPlaylist, String> {
+    let doc = roxmltree::Document::parse(text).map_err(|e| e.to_string())?;
+    let child_text = |node: roxmltree::Node, name: &str| {
+        node.children()
+            .find(|c| c.tag_name().name() == name)
+            .and_then(|c| c.text())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+    };
+
+    let root = doc.root_element();
+    let entries = root
+        .descendants()
+        .filter(|n| n.tag_name().name() == "track")
+        .filter_map(|track| {
+            let location = child_text(track, "location")?;
+            // relative locations are URI references, so undo the escaping
+            let location = if location.contains("://") {
+                location
+            } else {
+                percent_decode_str(&location)
+                    .decode_utf8_lossy()
+                    .into_owned()
+            };
+            Some(PlaylistEntry {
+                location,
+                title: child_text(track, "title"),
+                artist: child_text(track, "creator"),
+                duration: child_text(track, "duration")
+                    .and_then(|ms| ms.parse::<u64>().ok())
+                    .map(|ms| ms / 1000)
+                    .filter(|s| *s > 0),
+            })
+        })
+        .collect();
+
+    Ok(ParsedPlaylist {
+        name: child_text(root, "title"),
+        entries,
+    })
+}
+
+/// Resolves `.` and `..` without touching the disk, since the playlist may
+/// point at files that no longer exist.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Where an entry points on disk, if anywhere. File URIs are decoded, relative
+/// paths are taken from the playlist's folder, and web URLs give `None`.
+pub fn entry_path(location: &str, base_dir: &Path) -> Option<PathBuf> {
+    let location = location.trim();
+    if let Some(rest) = location.strip_prefix("file://") {
+        let decoded = percent_decode_str(rest).decode_utf8_lossy();
+        // `file:///C:/Music` keeps a leading slash in front of the drive letter
+        let decoded = match decoded.strip_prefix('/') {
+            Some(path) if path.get(1..2) == Some(":") => path.to_string(),
+            _ => decoded.into_owned(),
+        };
+        return Some(normalize(Path::new(&decoded)));
+    }
+    if location.contains("://") {
+        return None;
+    }
+    // playlists made on Windows use backslashes, which work as `/` everywhere
+    let location = location.replace('\\', "/");
+    let path = Path::new(&location);
+    if path.is_absolute() {
+        Some(normalize(path))
+    } else {
+        Some(normalize(&base_dir.join(path)))
+    }
+}
+
+fn file_stem(location: &str) -> Option<String> {
+    let name = location.trim_end_matches('/').rsplit(['/', '\\']).next()?;
+    let name = percent_decode_str(name).decode_utf8_lossy();
+    let stem = name
+        .rsplit_once('.')
+        .map_or(name.as_ref(), |(stem, _)| stem);
+    Some(stem.to_string()).filter(|s| !s.is_empty())
+}
+
+/// Library lookups for matching playlist entries to tracks.
+pub struct TrackIndex<'a> {
+    by_path: HashMap<&'a Path, &'a Track>,
+    by_title: HashMap<String, Vec<&'a Track>>,
+    by_file_name: HashMap<String, Vec<&'a Track>>,
+}
+
+impl<'a> TrackIndex<'a> {
+    pub fn new(tracks: &'a [Track]) -> Self {
+        let mut index = Self {
+            by_path: HashMap::new(),
+            by_title: HashMap::new(),
+            by_file_name: HashMap::new(),
+        };
+        for track in tracks {
+            index.by_path.insert(track.path.as_path(), track);
+            index
+                .by_title
+                .entry(match_key(&track.title))
+                .or_default()
+                .push(track);
+            if let Some(name) = track.path.file_name() {
+                index
+                    .by_file_name
+                    .entry(name.to_string_lossy().to_lowercase())
+                    .or_default()
+                    .push(track);
+            }
+        }
+        index
+    }
+
+    /// The one track with this title, and artist and length when known. Several
+    /// equally good candidates count as no match.
+    pub fn find_by_tags(
+        &self,
+        title: &str,
+        artist: Option<&str>,
+        duration: Option<u64>,
+    ) -> Option<&'a Track> {
+        let candidates = self.by_title.get(&match_key(title))?;
+        let artist = artist.map(match_key).filter(|a| !a.is_empty());
+        let mut found = candidates.iter().copied().filter(|t| {
+            artist.as_ref().is_none_or(|a| {
+                match_key(&t.artist) == *a || t.artists.iter().any(|x| match_key(x) == *a)
+            }) && duration.is_none_or(|d| t.duration.abs_diff(d) <= DURATION_TOLERANCE)
+        });
+        match (found.next(), found.next()) {
+            (Some(track), None) => Some(track),
+            _ => None,
+        }
+    }
+
+    /// Tries the path first, then the tags the file gave, then the file name,
+    /// then the file name read as a title.
+    pub fn find(&self, entry: &PlaylistEntry, base_dir: &Path) -> Option<&'a Track> {
+        if let Some(path) = entry_path(&entry.location, base_dir)
+            && let Some(track) = self.by_path.get(path.as_path())
+        {
+            return Some(track);
+        }
+        if let Some(title) = &entry.title
+            && let Some(track) = self.find_by_tags(title, entry.artist.as_deref(), entry.duration)
+        {
+            return Some(track);
+        }
+        let name = entry
+            .location
+            .trim_end_matches('/')
+            .rsplit(['/', '\\'])
+            .next()
+            .map(|n| percent_decode_str(n).decode_utf8_lossy().to_lowercase());
+        if let Some(matches) = name.and_then(|n| self.by_file_name.get(&n))
+            && let [track] = matches.as_slice()
+        {
+            return Some(track);
+        }
+        let stem = file_stem(&entry.location)?;
+        self.find_by_tags(&stem, entry.artist.as_deref(), entry.duration)
+    }
+}
+
+/// Library tracks for each entry, in order, plus the entries nothing matched.
+pub fn match_entries(
+    entries: &[PlaylistEntry],
+    base_dir: &Path,
+    library: &[Track],
+) -> (Vec<PathBuf>, Vec<PlaylistEntry>) {
+    let index = TrackIndex::new(library);
+    let mut matched = Vec::new();
+    let mut unmatched = Vec::new();
+    for entry in entries {
+        match index.find(entry, base_dir) {
+            Some(track) => matched.push(track.path.clone()),
+            None => unmatched.push(entry.clone()),
+        }
+    }
+    (matched, unmatched)
+}
+
+/// `path` as seen from `base_dir`, with `/` separators so the file works on
+/// other systems. Falls back to the absolute path across drives.
+pub fn relative_location(path: &Path, base_dir: &Path) -> String {
+    let path_parts: Vec<Component> = path.components().collect();
+    let base_parts: Vec<Component> = base_dir.components().collect();
+    let common = path_parts
+        .iter()
+        .zip(&base_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == 0 {
+        return path.to_string_lossy().into_owned();
+    }
+    let ups = std::iter::repeat_n("..".to_string(), base_parts.len() - common);
+    let rest = path_parts[common..]
+        .iter()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned());
+    ups.chain(rest).collect::<Vec<_>>().join("/")
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xspf_location(location: &str) -> String {
+    let encoded = utf8_percent_encode(location, URI_ESCAPE).to_string();
+    if location.starts_with('/') {
+        format!("file://{encoded}")
+    } else if location.get(1..2) == Some(":") {
+        format!("file:///{encoded}")
+    } else {
+        encoded
+    }
+}
+
+pub fn write(format: PlaylistFormat, name: &str, entries: &[PlaylistEntry]) -> String {
+    let mut out = String::new();
+    match format {
+        PlaylistFormat::M3u => {
+            out.push_str("#EXTM3U\n");
+            out.push_str(&format!("#PLAYLIST:{name}\n"));
+            for entry in entries {
+                let length = entry.duration.map_or(-1, |d| d as i64);
+                out.push_str(&format!("#EXTINF:{length},{}\n", entry.label()));
+                out.push_str(&entry.location);
+                out.push('\n');
+            }
+        }
+        PlaylistFormat::Pls => {
+            out.push_str("[playlist]\n");
+            for (i, entry) in entries.iter().enumerate() {
+                let n = i + 1;
+                out.push_str(&format!("File{n}={}\n", entry.location));
+                out.push_str(&format!("Title{n}={}\n", entry.label()));
+                let length = entry.duration.map_or(-1, |d| d as i64);
+                out.push_str(&format!("Length{n}={length}\n"));
+            }
+            out.push_str(&format!("NumberOfEntries={}\nVersion=2\n", entries.len()));
+        }
+        PlaylistFormat::Xspf => {
+            out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+            out.push_str("<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n");
+            out.push_str(&format!(
+                "  <title>{}</title>\n  <trackList>\n",
+                xml_escape(name)
+            ));
+            for entry in entries {
+                out.push_str("    <track>\n");
+                out.push_str(&format!(
+                    "      <location>{}</location>\n",
+                    xml_escape(&xspf_location(&entry.location))
+                ));
+                if let Some(title) = &entry.title {
+                    out.push_str(&format!("      <title>{}</title>\n", xml_escape(title)));
+                }
+                if let Some(artist) = &entry.artist {
+                    out.push_str(&format!(
+                        "      <creator>{}</creator>\n",
+                        xml_escape(artist)
+                    ));
+                }
+                if let Some(duration) = entry.duration {
+                    out.push_str(&format!("      <duration>{}</duration>\n", duration * 1000));
+                }
+                out.push_str("    </track>\n");
+            }
+            out.push_str("  </trackList>\n</playlist>\n");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlaylistFormat, match_entries, parse, relative_location, write};
+    use crate::models::Track;
+    use std::path::{Path, PathBuf};
+
+    fn track(path: &str, artist: &str, title: &str, duration: u64) -> Track {
+        Track {
+            path: PathBuf::from(path),
+            artist: artist.into(),
+            title: title.into(),
+            duration,
+            ..Default::default()
+        }
+    }
+
+    fn library() -> Vec<Track> {
+        vec![
+            track(
+                "/music/Air/Moon Safari/01 La femme d'argent.flac",
+                "Air",
+                "La femme d'argent",
+                430,
+            ),
+            track(
+                "/music/Air/Moon Safari/02 Sexy Boy.flac",
+                "Air",
+                "Sexy Boy",
+                298,
+            ),
+            track(
+                "/music/Portishead/Dummy/03 Sour Times.mp3",
+                "Portishead",
+                "Sour Times",
+                251,
+            ),
+        ]
+    }
+
+    #[test]
+    fn m3u_entries_match_by_path_tags_and_file_name() {
+        let text = "\u{feff}#EXTM3U\n#PLAYLIST:Trip\n\
+            #EXTINF:430,Air - La femme d'argent\n\
+            ../Air/Moon Safari/01 La femme d'argent.flac\n\
+            #EXTINF:251,Portishead - Sour Times\n\
+            D:\\Old Music\\Portishead\\Sour Times.mp3\n\
+            /elsewhere/02 Sexy Boy.flac\n\
+            https://radio.example/stream\n";
+
+        let parsed = parse(PlaylistFormat::M3u, text).unwrap();
+        assert_eq!(parsed.name.as_deref(), Some("Trip"));
+        assert_eq!(parsed.entries.len(), 4);
+
+        let (tracks, unmatched) =
+            match_entries(&parsed.entries, Path::new("/music/playlists"), &library());
+        assert_eq!(
+            tracks,
+            vec![
+                PathBuf::from("/music/Air/Moon Safari/01 La femme d'argent.flac"),
+                PathBuf::from("/music/Portishead/Dummy/03 Sour Times.mp3"),
+                PathBuf::from("/music/Air/Moon Safari/02 Sexy Boy.flac"),
+            ]
+        );
+        assert_eq!(unmatched.len(), 1);
+        assert_eq!(unmatched[0].location, "https://radio.example/stream");
+    }
+
+    #[test]
+    fn pls_and_xspf_round_trip() {
+        let lib = library();
+        let base = Path::new("/music/playlists");
+        let entries: Vec<_> = lib
+            .iter()
+            .map(|t| super::PlaylistEntry::from_track(t, relative_location(&t.path, base)))
+            .collect();
+        assert_eq!(
+            entries[0].location,
+            "../Air/Moon Safari/01 La femme d'argent.flac"
+        );
+
+        for format in [
+            PlaylistFormat::Pls,
+            PlaylistFormat::Xspf,
+            PlaylistFormat::M3u,
+        ] {
+            let text = write(format, "Trip & more", &entries);
+            let parsed = parse(format, &text).unwrap();
+            assert_eq!(parsed.entries, entries, "{format:?}");
+            let (tracks, unmatched) = match_entries(&parsed.entries, base, &lib);
+            assert_eq!(tracks.len(), 3);
+            assert!(unmatched.is_empty());
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::models::{Playlist, Track};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Below this a candidate only shares an era or a single genre with the seeds,
+/// which is too loose to call it related.
+const MIN_SCORE: f64 = 1.5;
+
+/// Picks up to `limit` library tracks that resemble the seeds, best first.
+///
+/// A candidate scores against each seed by shared artist, shared genres,
+/// release years close together and how many playlists hold both; its best
+/// score over all seeds counts. Tracks in `exclude` are skipped, and no artist
+/// takes more than a quarter of the picks so the radio keeps moving.
+pub fn similar_tracks<'a>(
+    seeds: &[Track],
+    candidates: &'a [Track],
+    playlists: &[Playlist],
+    exclude: &HashSet<&Path>,
+    limit: usize,
+) -> Vec<&'a Track> {
+    if seeds.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+
+    let mut memberships: HashMap<&Path, Vec<usize>> = HashMap::new();
+    for (i, playlist) in playlists.iter().enumerate() {
+        for path in &playlist.tracks {
+            memberships.entry(path.as_path()).or_default().push(i);
+        }
+    }
+    let seed_playlists: Vec<HashSet<usize>> = seeds
+        .iter()
+        .map(|s| {
+            memberships
+                .get(s.path.as_path())
+                .map(|p| p.iter().copied().collect())
+                .unwrap_or_default()
+        })
+        .collect();
+    let seed_paths: HashSet<&Path> = seeds.iter().map(|s| s.path.as_path()).collect();
+
+    let mut scored: Vec<(f64, &Track)> = candidates
+        .iter()
+        .filter(|c| !exclude.contains(c.path.as_path()) && !seed_paths.contains(c.path.as_path()))
+        .filter_map(|c| {
+            let in_playlists = memberships.get(c.path.as_path());
+            let score = seeds
+                .iter()
+                .zip(&seed_playlists)
+                .map(|(seed, seed_playlists)| {
+                    let shared = in_playlists.map_or(0, |p| {
+                        p.iter().filter(|i| seed_playlists.contains(i)).count()
+                    });
+                    similarity(seed, c) + (shared as f64 * 1.5).min(3.0)
+                })
+                .fold(0.0, f64::max);
+            (score >= MIN_SCORE).then_some((score, c))
+        })
+        .collect();
+    scored.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| a.1.artist.cmp(&b.1.artist))
+            .then_with(|| a.1.title.cmp(&b.1.title))
+    });
+
+    let per_artist = (limit / 4).max(2);
+    let mut artist_counts: HashMap<String, usize> = HashMap::new();
+    let mut picked = Vec::new();
+    for (_, track) in scored {
+        let count = artist_counts
+            .entry(track.artist.to_lowercase())
+            .or_default();
+        if *count >= per_artist {
+            continue;
+        }
+        *count += 1;
+        picked.push(track);
+        if picked.len() == limit {
+            break;
+        }
+    }
+    picked
+}
+
+/// Tag-based likeness of two tracks, leaving playlists aside.
+fn similarity(a: &Track, b: &Track) -> f64 {
+    let mut score = 0.0;
+
+    if !a.artist.is_empty() && a.artist.eq_ignore_ascii_case(&b.artist) {
+        score += 3.0;
+    } else if a
+        .artists
+        .iter()
+        .any(|x| b.artists.iter().any(|y| x.eq_ignore_ascii_case(y)))
+    {
+        score += 2.0;
+    }
+
+    let shared_genres = a
+        .genres
+        .iter()
+        .filter(|g| b.genres.iter().any(|h| g.eq_ignore_ascii_case(h)))
+        .count();
+    score += (shared_genres as f64 * 1.5).min(3.0);
+
+    if let (Some(x), Some(y)) = (year(a), year(b)) {
+        match (x - y).abs() {
+            0..=2 => score += 1.0,
+            3..=5 => score += 0.5,
+            _ => {}
+        }
+    }
+
+    score
+}
+
+fn year(track: &Track) -> Option<i32> {
+    track.date.as_deref()?.get(..4)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::similar_tracks;
+    use crate::models::{Playlist, Track};
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+
+    fn track(path: &str, artist: &str, genre: &str, date: &str) -> Track {
+        Track {
+            path: PathBuf::from(path),
+            title: path.into(),
+            artist: artist.into(),
+            genres: vec![genre.into()],
+            date: Some(date.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ranks_shared_artist_and_genre_first() {
+        let seed = track("/seed", "A", "Jazz", "1960");
+        let library = vec![
+            seed.clone(),
+            track("/same-artist", "A", "Jazz", "1961"),
+            track("/same-genre", "B", "Jazz", "1990"),
+            track("/era-only", "C", "Metal", "1960"),
+        ];
+        let picked = similar_tracks(&[seed], &library, &[], &HashSet::new(), 10);
+        let paths: Vec<_> = picked.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(paths, ["/same-artist", "/same-genre"]);
+    }
+
+    #[test]
+    fn playlist_neighbours_count_and_excluded_tracks_are_skipped() {
+        let seed = track("/seed", "A", "Jazz", "1960");
+        let library = vec![
+            track("/mixed-with-seed", "B", "Metal", "2010"),
+            track("/queued", "A", "Jazz", "1960"),
+        ];
+        let playlist = Playlist {
+            id: "p".into(),
+            name: "p".into(),
+            tracks: vec![PathBuf::from("/seed"), PathBuf::from("/mixed-with-seed")],
+            cover_path: None,
+            collage_path: None,
+        };
+        let queued = PathBuf::from("/queued");
+        let exclude = HashSet::from([queued.as_path()]);
+        let picked = similar_tracks(&[seed], &library, &[playlist], &exclude, 10);
+        assert_eq!(picked.len(), 1);
+        assert_eq!(picked[0].title, "/mixed-with-seed");
+    }
+
+    #[test]
+    fn caps_tracks_per_artist() {
+        let seed = track("/seed", "A", "Jazz", "1960");
+        let library: Vec<Track> = (0..10)
+            .map(|i| track(&format!("/a{i}"), "A", "Jazz", "1960"))
+            .chain([track("/b", "B", "Jazz", "1960")])
+            .collect();
+        let picked = similar_tracks(&[seed], &library, &[], &HashSet::new(), 4);
+        assert_eq!(picked.len(), 3);
+        assert!(picked.iter().any(|t| t.artist == "B"));
+    }
+}
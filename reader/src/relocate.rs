@@ -0,0 +1,282 @@
+use crate::bookmarks::BookmarkStore;
+use crate::duplicates::match_key;
+use crate::history::HistoryStore;
+use crate::models::{FavoritesStore, Library, PlaylistStore, Track, rating_key};
+use crate::saved_queues::SavedQueueStore;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Moved files are matched on tags, so allow for a re-encode changing the
+/// reported length a little.
+const DURATION_TOLERANCE: u64 = 2;
+
+/// Old path to new path for every file that moved. Built in full first and
+/// then applied to all stores in one go, so a relocation never leaves
+/// favorites pointing one way and playlists the other.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Relocation {
+    moves: HashMap<PathBuf, PathBuf>,
+}
+
+impl Relocation {
+    /// Moves every path under `from` to the same place under `to`.
+    pub fn from_prefix<'a>(
+        paths: impl IntoIterator<Item = &'a Path>,
+        from: &Path,
+        to: &Path,
+    ) -> Self {
+        let moves = paths
+            .into_iter()
+            .filter_map(|path| {
+                let rest = path.strip_prefix(from).ok()?;
+                Some((path.to_path_buf(), to.join(rest)))
+            })
+            .filter(|(old, new)| old != new)
+            .collect();
+        Self { moves }
+    }
+
+    /// Pairs tracks that disappeared with tracks that appeared, by title, artist,
+    /// album and length. Anything ambiguous in either direction is left alone
+    /// rather than guessed.
+    pub fn match_orphans(missing: &[Track], found: &[Track]) -> Self {
+        let key = |t: &Track| {
+            (
+                match_key(&t.title),
+                match_key(&t.artist),
+                match_key(&t.album),
+            )
+        };
+        let mut candidates: HashMap<_, Vec<&Track>> = HashMap::new();
+        for track in found {
+            candidates.entry(key(track)).or_default().push(track);
+        }
+
+        let mut claimed: HashMap<&Path, usize> = HashMap::new();
+        let mut pairs = Vec::new();
+        for track in missing {
+            if match_key(&track.title).is_empty() {
+                continue;
+            }
+            let Some(options) = candidates.get(&key(track)) else {
+                continue;
+            };
+            let mut close = options
+                .iter()
+                .filter(|t| t.duration.abs_diff(track.duration) <= DURATION_TOLERANCE);
+            if let (Some(only), None) = (close.next(), close.next()) {
+                *claimed.entry(only.path.as_path()).or_default() += 1;
+                pairs.push((track.path.clone(), only.path.clone()));
+            }
+        }
+
+        let moves = pairs
+            .into_iter()
+            .filter(|(_, new)| claimed.get(new.as_path()) == Some(&1))
+            .collect();
+        Self { moves }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&Path> {
+        self.moves.get(path).map(PathBuf::as_path)
+    }
+
+    /// Rewrites favorites, ratings, playlist entries and listening history.
+    /// Returns how many references changed.
+    pub fn apply(
+        &self,
+        favorites: &mut FavoritesStore,
+        playlists: &mut PlaylistStore,
+        history: &mut HistoryStore,
+        bookmarks: &mut BookmarkStore,
+        saved_queues: &mut SavedQueueStore,
+    ) -> usize {
+        let mut changed = 0;
+
+        for path in &mut favorites.local_favorites {
+            if let Some(new) = self.moves.get(path) {
+                *path = new.clone();
+                changed += 1;
+            }
+        }
+        let mut seen = std::collections::HashSet::new();
+        favorites.local_favorites.retain(|p| seen.insert(p.clone()));
+
+        for playlist in &mut playlists.playlists {
+            for path in &mut playlist.tracks {
+                if let Some(new) = self.moves.get(path) {
+                    *path = new.clone();
+                    changed += 1;
+                }
+            }
+        }
+
+        for (old, new) in &self.moves {
+            if history.move_track(&old.to_string_lossy(), &new.to_string_lossy()) {
+                changed += 1;
+            }
+            if let Some(stars) = favorites.track_ratings.remove(&rating_key(old)) {
+                favorites.track_ratings.insert(rating_key(new), stars);
+                changed += 1;
+            }
+            if bookmarks.move_track(&rating_key(old), &rating_key(new)) {
+                changed += 1;
+            }
+            if saved_queues.move_track(old, new) {
+                changed += 1;
+            }
+        }
+
+        changed
+    }
+
+    /// Moves library tracks along too, so the next scan finds them already in
+    /// place instead of re-reading every file.
+    pub fn apply_library(&self, library: &mut Library) {
+        for track in &mut library.tracks {
+            if let Some(new) = self.moves.get(&track.path) {
+                track.path = new.clone();
+            }
+        }
+    }
+}
+
+/// Every local path a favorite, rating, playlist or listening history points at.
+pub fn referenced_paths(
+    favorites: &FavoritesStore,
+    playlists: &PlaylistStore,
+    history: &HistoryStore,
+) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = favorites
+        .local_favorites
+        .iter()
+        .chain(playlists.playlists.iter().flat_map(|p| p.tracks.iter()))
+        .cloned()
+        .chain(history.tracks().map(PathBuf::from))
+        .chain(favorites.track_ratings.keys().map(PathBuf::from))
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Relocation;
+    use crate::bookmarks::BookmarkStore;
+    use crate::history::HistoryStore;
+    use crate::models::{FavoritesStore, Playlist, PlaylistStore, Track};
+    use crate::saved_queues::{SavedQueue, SavedQueueStore};
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    fn track(path: &str, title: &str, duration: u64) -> Track {
+        Track {
+            path: PathBuf::from(path),
+            title: title.into(),
+            artist: "Björk".into(),
+            album: "Homogenic".into(),
+            duration,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn prefix_move_rewrites_every_store() {
+        let old = PathBuf::from("/mnt/old/Music/Björk/Joga.flac");
+        let paths = [old.as_path(), Path::new("/elsewhere/x.mp3")];
+        let relocation =
+            Relocation::from_prefix(paths, Path::new("/mnt/old/Music"), Path::new("/data/Music"));
+        let new = PathBuf::from("/data/Music/Björk/Joga.flac");
+
+        let mut favorites = FavoritesStore {
+            local_favorites: vec![old.clone()],
+            track_ratings: HashMap::from([(old.to_string_lossy().to_string(), 5)]),
+            ..Default::default()
+        };
+        let mut playlists = PlaylistStore {
+            playlists: vec![Playlist {
+                id: "1".into(),
+                name: "Mix".into(),
+                tracks: vec![old.clone(), PathBuf::from("/elsewhere/x.mp3")],
+                cover_path: None,
+                collage_path: None,
+            }],
+            ..Default::default()
+        };
+        let mut history = HistoryStore {
+            listen_counts: HashMap::from([(old.to_string_lossy().to_string(), 4)]),
+            ..Default::default()
+        };
+
+        let mut bookmarks = BookmarkStore::default();
+        bookmarks.set(&old.to_string_lossy(), 600, 3_600, 1);
+        let mut saved_queues = SavedQueueStore {
+            queues: vec![SavedQueue {
+                name: "Work".into(),
+                saved_at: 1,
+                queue: vec![track("/mnt/old/Music/Björk/Joga.flac", "Jóga", 305)],
+                up_next: Vec::new(),
+                current_queue_index: 0,
+                from_up_next: false,
+                progress_secs: 0,
+                shuffle: false,
+                loop_mode: Default::default(),
+            }],
+        };
+
+        let changed = relocation.apply(
+            &mut favorites,
+            &mut playlists,
+            &mut history,
+            &mut bookmarks,
+            &mut saved_queues,
+        );
+
+        assert_eq!(changed, 6);
+        assert_eq!(favorites.local_favorites, vec![new.clone()]);
+        assert_eq!(
+            favorites.track_ratings[&new.to_string_lossy().to_string()],
+            5
+        );
+        assert_eq!(playlists.playlists[0].tracks[0], new);
+        assert_eq!(
+            playlists.playlists[0].tracks[1],
+            PathBuf::from("/elsewhere/x.mp3")
+        );
+        assert_eq!(history.plays(&new.to_string_lossy()), 4);
+        assert_eq!(bookmarks.position(&old.to_string_lossy()), None);
+        assert_eq!(bookmarks.position(&new.to_string_lossy()), Some(600));
+        assert_eq!(saved_queues.queues[0].queue[0].path, new);
+    }
+
+    #[test]
+    fn orphans_match_only_when_unambiguous() {
+        let missing = vec![
+            track("/old/joga.mp3", "Jóga", 305),
+            track("/old/bachelorette.mp3", "Bachelorette", 312),
+        ];
+        let found = vec![
+            track("/new/01 Joga.flac", "Jóga", 306),
+            // two equally good candidates: leave it for the user
+            track("/new/a/bachelorette.flac", "Bachelorette", 312),
+            track("/new/b/bachelorette.flac", "Bachelorette", 311),
+        ];
+
+        let relocation = Relocation::match_orphans(&missing, &found);
+
+        assert_eq!(relocation.len(), 1);
+        assert_eq!(
+            relocation.get(Path::new("/old/joga.mp3")),
+            Some(Path::new("/new/01 Joga.flac"))
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use crate::models::Track;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Repeat setting kept with a saved queue.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueLoop {
+    #[default]
+    Off,
+    Queue,
+    Track,
+}
+
+/// A queue put aside under a name, to be picked up again later.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SavedQueue {
+    pub name: String,
+    /// Unix seconds.
+    pub saved_at: u64,
+    #[serde(default)]
+    pub queue: Vec<Track>,
+    #[serde(default)]
+    pub up_next: Vec<Track>,
+    #[serde(default)]
+    pub current_queue_index: usize,
+    /// The current track was played from Up Next and leaves the queue once
+    /// something else plays.
+    #[serde(default)]
+    pub from_up_next: bool,
+    #[serde(default)]
+    pub progress_secs: u64,
+    #[serde(default)]
+    pub shuffle: bool,
+    #[serde(default)]
+    pub loop_mode: QueueLoop,
+}
+
+impl SavedQueue {
+    /// Everything the queue would play: the hand-queued tracks, then the context.
+    pub fn tracks(&self) -> impl Iterator<Item = &Track> {
+        self.up_next.iter().chain(&self.queue)
+    }
+
+    pub fn duration_secs(&self) -> u64 {
+        self.tracks().map(|t| t.duration).sum()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SavedQueueStore {
+    /// Most recently saved first.
+    #[serde(default)]
+    pub queues: Vec<SavedQueue>,
+}
+
+impl SavedQueueStore {
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = fs::read_to_string(path)?;
+        let store = serde_json::from_str(&data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        Ok(store)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let data = serde_json::to_string(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        utils::artwork::write_atomically(path, data.as_bytes())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SavedQueue> {
+        self.queues
+            .iter()
+            .find(|q| q.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Stores `queue` at the top, replacing one saved under the same name.
+    pub fn put(&mut self, queue: SavedQueue) {
+        self.remove(&queue.name);
+        self.queues.insert(0, queue);
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.queues.len();
+        self.queues.retain(|q| !q.name.eq_ignore_ascii_case(name));
+        self.queues.len() != before
+    }
+
+    /// Points every saved copy of the track at `old` to `new`.
+    pub fn move_track(&mut self, old: &Path, new: &Path) -> bool {
+        if old == new {
+            return false;
+        }
+        let mut changed = false;
+        for queue in &mut self.queues {
+            for track in queue.queue.iter_mut().chain(&mut queue.up_next) {
+                if track.path == old {
+                    track.path = new.to_path_buf();
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SavedQueue, SavedQueueStore};
+    use crate::models::Track;
+    use std::path::{Path, PathBuf};
+
+    fn saved(name: &str, saved_at: u64) -> SavedQueue {
+        SavedQueue {
+            name: name.into(),
+            saved_at,
+            queue: vec![Track {
+                path: PathBuf::from("/a"),
+                duration: 100,
+                ..Default::default()
+            }],
+            up_next: vec![Track {
+                path: PathBuf::from("/b"),
+                duration: 50,
+                ..Default::default()
+            }],
+            current_queue_index: 0,
+            from_up_next: false,
+            progress_secs: 0,
+            shuffle: false,
+            loop_mode: Default::default(),
+        }
+    }
+
+    #[test]
+    fn saving_under_an_existing_name_replaces_it() {
+        let mut store = SavedQueueStore::default();
+        store.put(saved("Work", 1));
+        store.put(saved("Gym", 2));
+        store.put(saved("work", 3));
+        let names: Vec<_> = store.queues.iter().map(|q| q.name.as_str()).collect();
+        assert_eq!(names, ["work", "Gym"]);
+        assert_eq!(store.get("WORK").map(|q| q.saved_at), Some(3));
+        assert!(store.remove("gym"));
+        assert!(!store.remove("gym"));
+    }
+
+    #[test]
+    fn moving_a_track_rewrites_both_lists() {
+        let mut store = SavedQueueStore::default();
+        store.put(saved("Work", 1));
+        let queued = store.queues[0].up_next[0].clone();
+        store.queues[0].queue.push(queued);
+
+        assert!(store.move_track(Path::new("/b"), Path::new("/c")));
+        assert!(!store.move_track(Path::new("/b"), Path::new("/c")));
+
+        let paths: Vec<_> = store.queues[0].tracks().map(|t| t.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                PathBuf::from("/c"),
+                PathBuf::from("/a"),
+                PathBuf::from("/c")
+            ]
+        );
+    }
+
+    #[test]
+    fn up_next_plays_before_the_context() {
+        let queue = saved("Work", 1);
+        let paths: Vec<_> = queue.tracks().map(|t| t.path.clone()).collect();
+        assert_eq!(paths, [PathBuf::from("/b"), PathBuf::from("/a")]);
+        assert_eq!(queue.duration_secs(), 150);
+    }
+}
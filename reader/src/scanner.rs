@@ -1,80 +1,295 @@
-use super::metadata::read;
-use super::models::Library;
-use async_recursion::async_recursion;
-use std::collections::HashSet;
+use super::exclude::{IgnoreStack, is_hidden};
+use super::metadata::{CoverMemo, ScannedTrack, merge, probe};
+use super::models::{AlbumGrouping, CoverOptions, Library, METADATA_VERSION, ScanRules};
+use super::utils::sweep_covers;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::fs;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+const MAX_SCAN_WORKERS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScanProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Shared flag that stops a running scan at the next file boundary.
+#[derive(Debug, Clone, Default)]
+pub struct ScanCancel(Arc<AtomicBool>);
+
+impl ScanCancel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for ScanCancel {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    pub album_grouping: AlbumGrouping,
+    pub covers: CoverOptions,
+    pub rules: ScanRules,
+}
+
+pub type ProgressCallback = Arc<dyn Fn(ScanProgress) + Send + Sync>;
 
 pub async fn scan_directory(
     dir: PathBuf,
     cover_cache: PathBuf,
     library: &mut Library,
-    on_progress: Arc<dyn Fn(String) + Send + Sync>,
+    options: ScanOptions,
+    on_progress: ProgressCallback,
+    cancel: ScanCancel,
 ) -> std::io::Result<()> {
-    let existing_paths: HashSet<PathBuf> = library.tracks.iter().map(|t| t.path.clone()).collect();
-    scan_directory_internal(dir, cover_cache, library, &existing_paths, on_progress).await
+    scan_directories(
+        vec![dir],
+        cover_cache,
+        library,
+        options,
+        on_progress,
+        cancel,
+    )
+    .await
 }
 
-#[async_recursion]
-async fn scan_directory_internal(
-    dir: PathBuf,
+/// Walks every root, reads tags on a bounded worker pool and merges the results
+/// into `library` from a single writer. Files already in the library are skipped,
+/// unless the library was grouped differently, built with other cover options or
+/// read by an older tag extractor, in which case every file is re-read. Tracks the
+/// scan rules now exclude or whose file is gone are dropped; callers prune the
+/// albums and artists left behind. Cached covers no album uses any more are deleted afterwards.
+pub async fn scan_directories(
+    dirs: Vec<PathBuf>,
     cover_cache: PathBuf,
     library: &mut Library,
-    existing_paths: &HashSet<PathBuf>,
-    on_progress: Arc<dyn Fn(String) + Send + Sync>,
+    options: ScanOptions,
+    on_progress: ProgressCallback,
+    cancel: ScanCancel,
 ) -> std::io::Result<()> {
-    let mut entries = match fs::read_dir(&dir).await {
-        Ok(e) => e,
-        Err(_) => return Ok(()),
-    };
-
-    let mut audio_files = Vec::new();
-    let mut sub_dirs = Vec::new();
-
-    while let Ok(Some(entry)) = entries.next_entry().await {
-        let path = entry.path();
-        if path.is_dir() {
-            sub_dirs.push(path);
-        } else if is_audio_file(&path) {
-            if !existing_paths.contains(&path) {
-                audio_files.push(path);
-            }
+    let mut lib = std::mem::take(library);
+    let mut date_added: HashMap<PathBuf, u64> = HashMap::new();
+    if lib.album_grouping != Some(options.album_grouping)
+        || lib.cover_options.as_ref() != Some(&options.covers)
+        || lib.metadata_version < METADATA_VERSION
+    {
+        date_added = lib
+            .tracks
+            .iter()
+            .filter_map(|t| Some((t.path.clone(), t.date_added?)))
+            .collect();
+        lib.tracks.clear();
+        lib.albums.clear();
+        lib.artists.clear();
+        lib.album_grouping = Some(options.album_grouping);
+        lib.cover_options = Some(options.covers.clone());
+        lib.metadata_version = METADATA_VERSION;
+    }
+
+    let (mut lib, result) = tokio::task::spawn_blocking(move || {
+        let mut lib = lib;
+        let result = run_scan(
+            &dirs,
+            &cover_cache,
+            &mut lib,
+            &options,
+            &on_progress,
+            &cancel,
+        );
+        if result.is_ok() {
+            // the sweep only tidies up, so a failed one is no reason to fail the scan
+            let covers = lib.albums.iter().filter_map(|a| a.cover_path.as_deref());
+            let _ = sweep_covers(&cover_cache, covers);
         }
+        (lib, result)
+    })
+    .await
+    .map_err(std::io::Error::other)?;
+
+    for track in &mut lib.tracks {
+        if let Some(&added) = date_added.get(&track.path) {
+            track.date_added = Some(added);
+        }
+    }
+
+    *library = lib;
+    result
+}
+
+fn run_scan(
+    dirs: &[PathBuf],
+    cover_cache: &Path,
+    library: &mut Library,
+    options: &ScanOptions,
+    on_progress: &ProgressCallback,
+    cancel: &ScanCancel,
+) -> std::io::Result<()> {
+    on_progress(ScanProgress::default());
+
+    let mut walk = Walk::default();
+    for dir in dirs {
+        collect_audio_files(dir, &options.rules, cancel, &mut walk);
+    }
+    let Walk {
+        mut files,
+        excluded,
+    } = walk;
+
+    if cancel.is_cancelled() {
+        return Err(cancelled());
+    }
+
+    // the rules apply to what was imported before they changed, too; a file the
+    // walk merely didn't reach, e.g. behind an unreadable folder, stays
+    let min_duration = options.rules.min_duration;
+    library.tracks.retain(|t| {
+        let walked = dirs.iter().any(|d| t.path.starts_with(d));
+        let dropped = options.rules.hidden_files.contains(&t.path)
+            || t.path.ancestors().any(|p| excluded.contains(p))
+            || !t.path.exists();
+        !(walked && dropped) && t.duration >= min_duration
+    });
+
+    let existing_paths: HashSet<PathBuf> = library.tracks.iter().map(|t| t.path.clone()).collect();
+    files.retain(|path| !existing_paths.contains(path));
+
+    let total = files.len();
+    on_progress(ScanProgress { done: 0, total });
+    if total == 0 {
+        return Ok(());
     }
 
-    if !audio_files.is_empty() {
-        let mut lib = std::mem::take(library);
-        let cover_cache_clone = cover_cache.clone();
-        let progress = on_progress.clone();
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(2)
+        .clamp(1, MAX_SCAN_WORKERS)
+        .min(total);
+    let next = AtomicUsize::new(0);
+    let cover_memo = CoverMemo::default();
+    let (tx, rx) = mpsc::sync_channel::<Option<ScannedTrack>>(workers * 4);
 
-        lib = tokio::task::spawn_blocking(move || {
-            for path in audio_files {
-                if let Some(name) = path.file_name() {
-                    progress(name.to_string_lossy().into_owned());
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            let tx = tx.clone();
+            let files = &files;
+            let next = &next;
+            let cover_memo = &cover_memo;
+            scope.spawn(move || {
+                loop {
+                    if cancel.is_cancelled() {
+                        break;
+                    }
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(path) = files.get(idx) else {
+                        break;
+                    };
+                    if tx
+                        .send(probe(
+                            path,
+                            options.album_grouping,
+                            &options.covers,
+                            cover_cache,
+                            cover_memo,
+                        ))
+                        .is_err()
+                    {
+                        break;
+                    }
                 }
-                read(&path, &cover_cache_clone, &mut lib);
+            });
+        }
+        drop(tx);
+
+        for (idx, scanned) in rx.into_iter().enumerate() {
+            // short files never enter the library, so every scan probes them again
+            if let Some(scanned) = scanned.filter(|s| s.track.duration >= min_duration) {
+                merge(scanned, library);
             }
-            lib
-        })
-        .await
-        .unwrap();
+            on_progress(ScanProgress {
+                done: idx + 1,
+                total,
+            });
+        }
+    });
 
-        *library = lib;
+    if cancel.is_cancelled() {
+        Err(cancelled())
+    } else {
+        Ok(())
     }
+}
 
-    for sub_dir in sub_dirs {
-        let _ = scan_directory_internal(
-            sub_dir,
-            cover_cache.clone(),
-            library,
-            existing_paths,
-            on_progress.clone(),
-        )
-        .await;
+/// What a walk of the music folders turned up.
+#[derive(Debug, Default)]
+struct Walk {
+    /// Audio files the scan rules let through.
+    files: Vec<PathBuf>,
+    /// Files and folders the scan rules turned away; nothing below them was walked.
+    excluded: HashSet<PathBuf>,
+}
+
+/// Every audio file under `root` that the scan rules let through.
+fn collect_audio_files(root: &Path, rules: &ScanRules, cancel: &ScanCancel, walk: &mut Walk) {
+    let patterns = rules
+        .excludes
+        .get(root)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut pending = vec![(root.to_path_buf(), IgnoreStack::for_root(root, patterns))];
+    // symlinked folders can point back up the tree; walk each real folder once
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+
+    while let Some((dir, ignores)) = pending.pop() {
+        if cancel.is_cancelled() {
+            return;
+        }
+        let Ok(real_dir) = std::fs::canonicalize(&dir) else {
+            continue;
+        };
+        if !visited.insert(real_dir) {
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let ignores = ignores.enter(&dir);
+        let mut sub_dirs = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_dir = path.is_dir();
+            if (rules.skip_hidden && is_hidden(&path)) || ignores.is_ignored(&path, is_dir) {
+                walk.excluded.insert(path);
+                continue;
+            }
+            if is_dir {
+                sub_dirs.push(path);
+            } else if is_audio_file(&path) && !rules.hidden_files.contains(&path) {
+                walk.files.push(path);
+            }
+        }
+        // keep the walk roughly alphabetical so albums land together in the merge
+        sub_dirs.sort();
+        pending.extend(sub_dirs.into_iter().rev().map(|d| (d, ignores.clone())));
     }
+}
 
-    Ok(())
+fn cancelled() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Interrupted, "scan cancelled")
 }
 
 pub fn is_audio_file(path: &Path) -> bool {
@@ -84,3 +299,152 @@ pub fn is_audio_file(path: &Path) -> bool {
         .map(|s| extensions.contains(&s.to_lowercase().as_str()))
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ProgressCallback, ScanCancel, ScanOptions, Walk, collect_audio_files, run_scan};
+    use crate::exclude::IGNORE_FILE;
+    use crate::models::{Library, ScanRules, Track};
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    fn music_dir(name: &str, files: &[&str]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("kopuz-scan-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for file in files {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"").unwrap();
+        }
+        root
+    }
+
+    fn walk(root: &Path, rules: &ScanRules) -> Walk {
+        let mut walk = Walk::default();
+        collect_audio_files(root, rules, &ScanCancel::new(), &mut walk);
+        walk
+    }
+
+    fn relative(root: &Path, paths: &[PathBuf]) -> Vec<String> {
+        let mut paths: Vec<String> = paths
+            .iter()
+            .map(|p| p.strip_prefix(root).unwrap().to_string_lossy().into_owned())
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn walk_follows_globs_ignore_files_and_hidden_entries() {
+        let root = music_dir(
+            "rules",
+            &[
+                "Album/01.flac",
+                "Album/cover.jpg",
+                "Album/02.MP3",
+                "Demos/take.flac",
+                "Live/keep.flac",
+                "Live/skip.flac",
+                "Live/Bonus/skip.flac",
+                ".Trash/old.flac",
+                "Album/._01.flac",
+            ],
+        );
+        fs::write(root.join("Live").join(IGNORE_FILE), "skip.flac\n").unwrap();
+        fs::write(root.join("Live/Bonus").join(IGNORE_FILE), "!skip.flac\n").unwrap();
+        let mut rules = ScanRules {
+            excludes: HashMap::from([(root.clone(), vec!["Demos/".to_string()])]),
+            ..Default::default()
+        };
+        rules.hidden_files.insert(root.join("Album/02.MP3"));
+
+        let found = walk(&root, &rules);
+
+        assert_eq!(
+            relative(&root, &found.files),
+            ["Album/01.flac", "Live/Bonus/skip.flac", "Live/keep.flac"]
+        );
+        for excluded in ["Demos", "Live/skip.flac", ".Trash", "Album/._01.flac"] {
+            assert!(found.excluded.contains(&root.join(excluded)), "{excluded}");
+        }
+
+        rules.skip_hidden = false;
+        let found = walk(&root, &rules);
+        assert!(found.files.contains(&root.join(".Trash/old.flac")));
+        assert!(found.excluded.contains(&root.join("Demos")));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn walk_visits_each_real_folder_once() {
+        let root = music_dir("loop", &["Artist/Album/01.flac"]);
+        std::os::unix::fs::symlink(&root, root.join("Artist/Album/up")).unwrap();
+        std::os::unix::fs::symlink(root.join("Artist"), root.join("Same")).unwrap();
+
+        let found = walk(&root, &ScanRules::default());
+
+        assert_eq!(relative(&root, &found.files), ["Artist/Album/01.flac"]);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rescan_drops_only_excluded_missing_or_short_tracks() {
+        let root = music_dir(
+            "retain",
+            &["Album/01.flac", "Album/intro.flac", "Demos/take.flac"],
+        );
+        std::os::unix::fs::symlink(root.join("Album"), root.join("Linked")).unwrap();
+        let track = |path: &str, duration: u64| Track {
+            path: root.join(path),
+            duration,
+            ..Default::default()
+        };
+        let mut library = Library {
+            tracks: vec![
+                track("Album/01.flac", 200),
+                track("Album/intro.flac", 5),
+                track("Demos/take.flac", 200),
+                track("Album/gone.flac", 200),
+                // the walk reaches this file under its real folder only
+                track("Linked/01.flac", 200),
+                track("/elsewhere/song.flac", 200),
+            ],
+            ..Default::default()
+        };
+        let options = ScanOptions {
+            rules: ScanRules {
+                excludes: HashMap::from([(root.clone(), vec!["Demos/".to_string()])]),
+                min_duration: 10,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let on_progress: ProgressCallback = Arc::new(|_| {});
+        run_scan(
+            std::slice::from_ref(&root),
+            &root.join("covers"),
+            &mut library,
+            &options,
+            &on_progress,
+            &ScanCancel::new(),
+        )
+        .unwrap();
+
+        let kept: Vec<PathBuf> = library.tracks.iter().map(|t| t.path.clone()).collect();
+        assert_eq!(
+            kept,
+            [
+                root.join("Album/01.flac"),
+                root.join("Linked/01.flac"),
+                PathBuf::from("/elsewhere/song.flac"),
+            ]
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+}
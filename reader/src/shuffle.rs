@@ -0,0 +1,365 @@
+use crate::models::Track;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+
+/// Listen stats and preferences for [`smart_order`], taken from the listening
+/// history the same way as for smart playlists.
+pub struct ShuffleContext<'a> {
+    pub listen_counts: &'a HashMap<String, u64>,
+    pub last_played: &'a HashMap<String, u64>,
+    /// Makes highly rated tracks come up sooner.
+    pub by_rating: bool,
+    /// Makes tracks heard less often come up sooner.
+    pub by_plays: bool,
+    /// Tracks played within this many seconds of `now` go to the end.
+    pub avoid_recent_secs: u64,
+    /// Unix seconds.
+    pub now: u64,
+}
+
+impl ShuffleContext<'_> {
+    fn weight(&self, track: &Track) -> f64 {
+        let key = track.path.to_string_lossy();
+        let mut weight = 1.0;
+        if self.by_rating {
+            // unrated counts as three stars
+            weight *= f64::from(track.rating.unwrap_or(3).max(1)) / 3.0;
+        }
+        if self.by_plays {
+            let plays = self.listen_counts.get(key.as_ref()).copied().unwrap_or(0);
+            weight /= 1.0 + (plays as f64).ln_1p() / 2.0;
+        }
+        weight
+    }
+
+    fn is_recent(&self, track: &Track) -> bool {
+        if self.avoid_recent_secs == 0 {
+            return false;
+        }
+        let key = track.path.to_string_lossy();
+        self.last_played
+            .get(key.as_ref())
+            .is_some_and(|at| self.now.saturating_sub(*at) < self.avoid_recent_secs)
+    }
+}
+
+/// A shuffled play order for `tracks`, leaving out `skip` (the track playing
+/// now). Tracks by one artist, and within that from one album, are spread out
+/// evenly instead of landing wherever chance puts them; weights only decide
+/// which of an artist's tracks come up first. Recently played tracks follow
+/// all the others.
+pub fn smart_order<R: Rng>(
+    tracks: &[Track],
+    skip: Option<usize>,
+    ctx: &ShuffleContext,
+    rng: &mut R,
+) -> Vec<usize> {
+    let (recent, fresh): (Vec<usize>, Vec<usize>) = (0..tracks.len())
+        .filter(|i| Some(*i) != skip)
+        .partition(|i| ctx.is_recent(&tracks[*i]));
+
+    let mut order = spread_artists(tracks, fresh, ctx, rng);
+    order.extend(spread_artists(tracks, recent, ctx, rng));
+    order
+}
+
+/// A play order that shuffles whole albums but keeps each album in disc and
+/// track order. The rest of the album of `current` (the track playing now) comes
+/// first; its tracks before `current` are shuffled in as an album of their own.
+pub fn album_order<R: Rng>(tracks: &[Track], current: Option<usize>, rng: &mut R) -> Vec<usize> {
+    let position = |i: usize| {
+        let t = &tracks[i];
+        (t.disc_number.unwrap_or(1), t.track_number.unwrap_or(0), i)
+    };
+    let items: Vec<usize> = (0..tracks.len()).filter(|i| Some(*i) != current).collect();
+    let mut albums = group_by(&items, |i| album_key(&tracks[i]));
+    for album in &mut albums {
+        album.sort_by_key(|i| position(*i));
+    }
+
+    let mut rest = Vec::new();
+    if let Some(current) = current.filter(|c| *c < tracks.len()) {
+        let key = album_key(&tracks[current]);
+        if let Some(slot) = albums.iter().position(|a| album_key(&tracks[a[0]]) == key) {
+            let (after, before): (Vec<usize>, Vec<usize>) = albums[slot]
+                .iter()
+                .partition(|i| position(**i) > position(current));
+            rest = after;
+            if before.is_empty() {
+                albums.remove(slot);
+            } else {
+                albums[slot] = before;
+            }
+        }
+    }
+
+    albums.shuffle(rng);
+    rest.extend(albums.into_iter().flatten());
+    rest
+}
+
+fn album_key(track: &Track) -> String {
+    // untagged singles have no album, so each counts as its own
+    if track.album_id.is_empty() {
+        track.path.to_string_lossy().into_owned()
+    } else {
+        track.album_id.clone()
+    }
+}
+
+fn spread_artists<R: Rng>(
+    tracks: &[Track],
+    items: Vec<usize>,
+    ctx: &ShuffleContext,
+    rng: &mut R,
+) -> Vec<usize> {
+    // weighted random order: higher weights tend to draw higher keys
+    let mut keyed: Vec<(f64, usize)> = items
+        .into_iter()
+        .map(|i| {
+            (
+                rng.gen_range(0.0..1.0f64)
+                    .powf(1.0 / ctx.weight(&tracks[i])),
+                i,
+            )
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let items: Vec<usize> = keyed.into_iter().map(|(_, i)| i).collect();
+
+    let artists = group_by(&items, |i| {
+        let artist = tracks[i].artist.trim().to_lowercase();
+        // tracks without an artist aren't related to each other
+        if artist.is_empty() {
+            tracks[i].path.to_string_lossy().into_owned()
+        } else {
+            artist
+        }
+    });
+    let artists = artists
+        .into_iter()
+        .map(|group| {
+            let albums = group_by(&group, |i| album_key(&tracks[i]));
+            spread(albums, rng)
+        })
+        .collect();
+    spread(artists, rng)
+}
+
+/// Splits `items` by key, keeping their order within each group.
+fn group_by(items: &[usize], key: impl Fn(usize) -> String) -> Vec<Vec<usize>> {
+    let mut index = HashMap::new();
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for &item in items {
+        let slot = *index.entry(key(item)).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[slot].push(item);
+    }
+    groups
+}
+
+/// Interleaves `groups` by placing the members of each at even steps along the
+/// queue, starting from a random offset.
+fn spread<R: Rng>(groups: Vec<Vec<usize>>, rng: &mut R) -> Vec<usize> {
+    let mut placed: Vec<(f64, usize)> = Vec::new();
+    for group in groups {
+        let step = 1.0 / group.len() as f64;
+        let offset = rng.gen_range(0.0..step);
+        placed.extend(
+            group
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| (offset + i as f64 * step, item)),
+        );
+    }
+    placed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    placed.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ShuffleContext, album_order, smart_order};
+    use crate::models::Track;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn track(path: &str, artist: &str, album_id: &str) -> Track {
+        Track {
+            path: PathBuf::from(path),
+            artist: artist.into(),
+            album_id: album_id.into(),
+            ..Default::default()
+        }
+    }
+
+    fn ctx<'a>(
+        listen_counts: &'a HashMap<String, u64>,
+        last_played: &'a HashMap<String, u64>,
+    ) -> ShuffleContext<'a> {
+        ShuffleContext {
+            listen_counts,
+            last_played,
+            by_rating: false,
+            by_plays: false,
+            avoid_recent_secs: 0,
+            now: 1_000_000,
+        }
+    }
+
+    #[test]
+    fn spreads_artists_and_albums() {
+        let mut tracks = Vec::new();
+        for artist in ["a", "b", "c"] {
+            for n in 0..4 {
+                tracks.push(track(&format!("/m/{artist}{n}"), artist, artist));
+            }
+        }
+        // one artist with two albums
+        for n in 0..6 {
+            let album = if n % 2 == 0 { "x1" } else { "x2" };
+            tracks.push(track(&format!("/m/x{n}"), "x", album));
+        }
+        let empty = HashMap::new();
+
+        for seed in 0..20 {
+            let order = smart_order(
+                &tracks,
+                None,
+                &ctx(&empty, &empty),
+                &mut StdRng::seed_from_u64(seed),
+            );
+
+            let mut sorted = order.clone();
+            sorted.sort();
+            assert_eq!(sorted, (0..tracks.len()).collect::<Vec<_>>());
+
+            let x_albums: Vec<&str> = order
+                .iter()
+                .map(|i| &tracks[*i])
+                .filter(|t| t.artist == "x")
+                .map(|t| t.album_id.as_str())
+                .collect();
+            assert!(x_albums.windows(2).all(|w| w[0] != w[1]), "seed {seed}");
+
+            let abc: Vec<&str> = order
+                .iter()
+                .map(|i| tracks[*i].artist.as_str())
+                .filter(|a| *a != "x")
+                .collect();
+            assert!(abc.windows(2).all(|w| w[0] != w[1]), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_order_and_skips_current() {
+        let tracks: Vec<_> = (0..10)
+            .map(|n| track(&format!("/m/{n}"), &format!("artist {}", n % 3), ""))
+            .collect();
+        let empty = HashMap::new();
+        let ctx = ctx(&empty, &empty);
+
+        let first = smart_order(&tracks, Some(4), &ctx, &mut StdRng::seed_from_u64(7));
+        let second = smart_order(&tracks, Some(4), &ctx, &mut StdRng::seed_from_u64(7));
+
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 9);
+        assert!(!first.contains(&4));
+    }
+
+    #[test]
+    fn recently_played_tracks_come_last() {
+        let tracks: Vec<_> = (0..6)
+            .map(|n| track(&format!("/m/{n}"), &format!("artist {n}"), ""))
+            .collect();
+        let empty = HashMap::new();
+        let last_played = HashMap::from([
+            ("/m/1".to_string(), 1_000_000 - 600),
+            ("/m/3".to_string(), 1_000_000 - 3_600),
+            // long enough ago to count as fresh
+            ("/m/5".to_string(), 1_000_000 - 100_000),
+        ]);
+        let mut ctx = ctx(&empty, &last_played);
+        ctx.avoid_recent_secs = 2 * 3_600;
+
+        for seed in 0..10 {
+            let order = smart_order(&tracks, None, &ctx, &mut StdRng::seed_from_u64(seed));
+            let mut tail = order[4..].to_vec();
+            tail.sort();
+            assert_eq!(tail, [1, 3]);
+        }
+    }
+
+    #[test]
+    fn weights_favor_high_ratings_and_few_plays() {
+        let mut loved = track("/m/loved", "a", "x");
+        loved.rating = Some(5);
+        let mut meh = track("/m/meh", "a", "x");
+        meh.rating = Some(1);
+        let tracks = [
+            loved,
+            meh,
+            track("/m/worn", "a", "x"),
+            track("/m/new", "a", "x"),
+        ];
+        let counts = HashMap::from([("/m/worn".to_string(), 500)]);
+        let empty = HashMap::new();
+
+        let mut ctx = ctx(&counts, &empty);
+        ctx.by_rating = true;
+        let loved_first = (0..200)
+            .filter(|seed| {
+                let order =
+                    smart_order(&tracks[..2], None, &ctx, &mut StdRng::seed_from_u64(*seed));
+                order[0] == 0
+            })
+            .count();
+        assert!(loved_first > 140, "{loved_first}");
+
+        ctx.by_rating = false;
+        ctx.by_plays = true;
+        let new_first = (0..200)
+            .filter(|seed| {
+                let order =
+                    smart_order(&tracks[2..], None, &ctx, &mut StdRng::seed_from_u64(*seed));
+                order[0] == 1
+            })
+            .count();
+        assert!(new_first > 120, "{new_first}");
+    }
+
+    #[test]
+    fn album_order_keeps_albums_together_in_track_order() {
+        let mut tracks = Vec::new();
+        for album in ["a", "b", "c"] {
+            // queued out of order to check the sort
+            for n in [3, 1, 2] {
+                let mut t = track(&format!("/m/{album}{n}"), "artist", album);
+                t.track_number = Some(n);
+                tracks.push(t);
+            }
+        }
+        let label = |i: &usize| tracks[*i].path.to_string_lossy().into_owned();
+
+        for seed in 0..10 {
+            let order = album_order(&tracks, None, &mut StdRng::seed_from_u64(seed));
+            assert_eq!(order.len(), 9);
+            for album in order.chunks(3) {
+                let names: Vec<String> = album.iter().map(label).collect();
+                let prefix = &names[0][..4];
+                assert_eq!(names, [1, 2, 3].map(|n| format!("{prefix}{n}")));
+            }
+        }
+
+        // "/m/b1" is playing: "/m/b2" and "/m/b3" go next, the other albums follow
+        let order = album_order(&tracks, Some(4), &mut StdRng::seed_from_u64(3));
+        let names: Vec<String> = order.iter().map(label).collect();
+        assert_eq!(names[..2], ["/m/b2", "/m/b3"]);
+        assert_eq!(names.len(), 8);
+        assert!(!order.contains(&4));
+    }
+}
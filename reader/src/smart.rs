@@ -0,0 +1,447 @@
+use crate::models::{FavoritesStore, Track};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+const DAY: u64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleField {
+    Title,
+    Artist,
+    Album,
+    Genre,
+    Year,
+    /// Length in seconds.
+    Duration,
+    PlayCount,
+    DateAdded,
+    LastPlayed,
+    Favorite,
+    /// 0–5 stars, unrated being 0.
+    Rating,
+}
+
+pub const RULE_FIELDS: [RuleField; 11] = [
+    RuleField::Title,
+    RuleField::Artist,
+    RuleField::Album,
+    RuleField::Genre,
+    RuleField::Year,
+    RuleField::Duration,
+    RuleField::PlayCount,
+    RuleField::DateAdded,
+    RuleField::LastPlayed,
+    RuleField::Favorite,
+    RuleField::Rating,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleOp {
+    Contains,
+    NotContains,
+    Is,
+    IsNot,
+    LessThan,
+    GreaterThan,
+    /// Within the last `value` days.
+    InLast,
+    /// Not within the last `value` days, including never.
+    NotInLast,
+}
+
+impl RuleField {
+    /// The operators that make sense for this field, the first being the default.
+    pub fn ops(self) -> &'static [RuleOp] {
+        match self {
+            RuleField::Title | RuleField::Artist | RuleField::Album | RuleField::Genre => &[
+                RuleOp::Contains,
+                RuleOp::NotContains,
+                RuleOp::Is,
+                RuleOp::IsNot,
+            ],
+            RuleField::Year | RuleField::Duration | RuleField::PlayCount | RuleField::Rating => &[
+                RuleOp::Is,
+                RuleOp::IsNot,
+                RuleOp::LessThan,
+                RuleOp::GreaterThan,
+            ],
+            RuleField::DateAdded | RuleField::LastPlayed => &[RuleOp::InLast, RuleOp::NotInLast],
+            RuleField::Favorite => &[RuleOp::Is, RuleOp::IsNot],
+        }
+    }
+
+    /// Whether the rule needs a value typed in; favorites are yes or no.
+    pub fn takes_value(self) -> bool {
+        self != RuleField::Favorite
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    pub field: RuleField,
+    pub op: RuleOp,
+    #[serde(default)]
+    pub value: String,
+}
+
+impl Rule {
+    pub fn new(field: RuleField) -> Self {
+        Self {
+            field,
+            op: field.ops()[0],
+            value: String::new(),
+        }
+    }
+
+    /// A rule whose value does not parse matches nothing, so a half-typed
+    /// number empties the playlist instead of filling it with everything.
+    pub fn matches(&self, track: &Track, ctx: &SmartContext) -> bool {
+        match self.field {
+            RuleField::Title => self.matches_text([track.title.as_str()]),
+            RuleField::Artist => self.matches_text(
+                std::iter::once(track.artist.as_str())
+                    .chain(track.artists.iter().map(String::as_str)),
+            ),
+            RuleField::Album => self.matches_text([track.album.as_str()]),
+            RuleField::Genre => self.matches_text(track.genres.iter().map(String::as_str)),
+            RuleField::Year => self.matches_number(track.year().map(u64::from)),
+            RuleField::Duration => self.matches_number(Some(track.duration)),
+            RuleField::PlayCount => self.matches_number(Some(ctx.play_count(track))),
+            RuleField::DateAdded => self.matches_date(track.date_added, ctx.now),
+            RuleField::LastPlayed => self.matches_date(ctx.last_played(track), ctx.now),
+            RuleField::Rating => {
+                self.matches_number(Some(u64::from(ctx.favorites.track_rating(track))))
+            }
+            RuleField::Favorite => {
+                let favorite = ctx.favorites.contains_track(track);
+                if self.op == RuleOp::IsNot {
+                    !favorite
+                } else {
+                    favorite
+                }
+            }
+        }
+    }
+
+    fn matches_text<'a>(&self, values: impl IntoIterator<Item = &'a str>) -> bool {
+        let wanted = self.value.trim().to_lowercase();
+        let mut values = values.into_iter().map(str::to_lowercase);
+        match self.op {
+            RuleOp::Contains => values.any(|v| v.contains(&wanted)),
+            RuleOp::NotContains => !values.any(|v| v.contains(&wanted)),
+            RuleOp::Is => values.any(|v| v == wanted),
+            RuleOp::IsNot => !values.any(|v| v == wanted),
+            _ => false,
+        }
+    }
+
+    fn matches_number(&self, value: Option<u64>) -> bool {
+        let Ok(wanted) = self.value.trim().parse::<u64>() else {
+            return false;
+        };
+        match (self.op, value) {
+            (RuleOp::IsNot, None) => true,
+            (_, None) => false,
+            (RuleOp::Is, Some(v)) => v == wanted,
+            (RuleOp::IsNot, Some(v)) => v != wanted,
+            (RuleOp::LessThan, Some(v)) => v < wanted,
+            (RuleOp::GreaterThan, Some(v)) => v > wanted,
+            _ => false,
+        }
+    }
+
+    fn matches_date(&self, timestamp: Option<u64>, now: u64) -> bool {
+        let Ok(days) = self.value.trim().parse::<u64>() else {
+            return false;
+        };
+        let recent = timestamp.is_some_and(|t| now.saturating_sub(t) <= days * DAY);
+        match self.op {
+            RuleOp::InLast => recent,
+            RuleOp::NotInLast => !recent,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmartSort {
+    #[default]
+    Artist,
+    Title,
+    Album,
+    Year,
+    DateAdded,
+    PlayCount,
+    LastPlayed,
+    Duration,
+    Rating,
+}
+
+pub const SMART_SORTS: [SmartSort; 9] = [
+    SmartSort::Artist,
+    SmartSort::Title,
+    SmartSort::Album,
+    SmartSort::Year,
+    SmartSort::DateAdded,
+    SmartSort::PlayCount,
+    SmartSort::LastPlayed,
+    SmartSort::Duration,
+    SmartSort::Rating,
+];
+
+/// Listen stats and favorites the rules are checked against. Rebuilt from the
+/// live stores on every evaluation, which is what keeps the playlists current.
+pub struct SmartContext<'a> {
+    pub listen_counts: &'a HashMap<String, u64>,
+    pub last_played: &'a HashMap<String, u64>,
+    pub favorites: &'a FavoritesStore,
+    /// Unix seconds.
+    pub now: u64,
+}
+
+impl SmartContext<'_> {
+    fn play_count(&self, track: &Track) -> u64 {
+        let key = track.path.to_string_lossy();
+        self.listen_counts.get(key.as_ref()).copied().unwrap_or(0)
+    }
+
+    fn last_played(&self, track: &Track) -> Option<u64> {
+        let key = track.path.to_string_lossy();
+        self.last_played.get(key.as_ref()).copied()
+    }
+}
+
+/// A playlist defined by rules instead of a track list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmartPlaylist {
+    pub id: String,
+    pub name: String,
+    /// Evaluated against the server library rather than local files.
+    #[serde(default)]
+    pub server: bool,
+    /// All rules must match, otherwise any one of them.
+    #[serde(default = "default_true")]
+    pub match_all: bool,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+    #[serde(default)]
+    pub sort: SmartSort,
+    #[serde(default)]
+    pub descending: bool,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl SmartPlaylist {
+    pub fn new(id: String, name: String, server: bool) -> Self {
+        Self {
+            id,
+            name,
+            server,
+            match_all: true,
+            rules: Vec::new(),
+            sort: SmartSort::default(),
+            descending: false,
+            limit: None,
+        }
+    }
+
+    pub fn matches(&self, track: &Track, ctx: &SmartContext) -> bool {
+        if self.rules.is_empty() {
+            return true;
+        }
+        if self.match_all {
+            self.rules.iter().all(|r| r.matches(track, ctx))
+        } else {
+            self.rules.iter().any(|r| r.matches(track, ctx))
+        }
+    }
+
+    /// The matching tracks, sorted and cut to the limit.
+    pub fn evaluate(&self, tracks: &[Track], ctx: &SmartContext) -> Vec<Track> {
+        let mut matched: Vec<&Track> = tracks.iter().filter(|t| self.matches(t, ctx)).collect();
+        matched.sort_by(|a, b| {
+            let primary = self.compare(a, b, ctx);
+            let primary = if self.descending {
+                primary.reverse()
+            } else {
+                primary
+            };
+            primary.then_with(|| album_order(a, b))
+        });
+        if let Some(limit) = self.limit {
+            matched.truncate(limit);
+        }
+        matched.into_iter().cloned().collect()
+    }
+
+    fn compare(&self, a: &Track, b: &Track, ctx: &SmartContext) -> Ordering {
+        let text = |x: &str, y: &str| x.to_lowercase().cmp(&y.to_lowercase());
+        match self.sort {
+            SmartSort::Artist => text(&a.artist, &b.artist),
+            SmartSort::Title => text(&a.title, &b.title),
+            SmartSort::Album => text(&a.album, &b.album),
+            SmartSort::Year => a.year().cmp(&b.year()),
+            SmartSort::DateAdded => a.date_added.cmp(&b.date_added),
+            SmartSort::PlayCount => ctx.play_count(a).cmp(&ctx.play_count(b)),
+            SmartSort::LastPlayed => ctx.last_played(a).cmp(&ctx.last_played(b)),
+            SmartSort::Duration => a.duration.cmp(&b.duration),
+            SmartSort::Rating => ctx
+                .favorites
+                .track_rating(a)
+                .cmp(&ctx.favorites.track_rating(b)),
+        }
+    }
+}
+
+fn album_order(a: &Track, b: &Track) -> Ordering {
+    a.album
+        .cmp(&b.album)
+        .then(a.disc_number.cmp(&b.disc_number))
+        .then(a.track_number.cmp(&b.track_number))
+        .then_with(|| a.title.cmp(&b.title))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rule, RuleField, RuleOp, SmartContext, SmartPlaylist, SmartSort};
+    use crate::models::{FavoritesStore, Track};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    const NOW: u64 = 1_700_000_000;
+    const DAY: u64 = 86_400;
+
+    fn track(path: &str, genre: &str, year: &str, added_days_ago: u64) -> Track {
+        Track {
+            path: PathBuf::from(path),
+            title: path.trim_start_matches('/').into(),
+            genres: vec![genre.into()],
+            date: Some(year.into()),
+            date_added: Some(NOW - added_days_ago * DAY),
+            ..Default::default()
+        }
+    }
+
+    fn rule(field: RuleField, op: RuleOp, value: &str) -> Rule {
+        Rule {
+            field,
+            op,
+            value: value.into(),
+        }
+    }
+
+    fn playlist(rules: Vec<Rule>) -> SmartPlaylist {
+        SmartPlaylist {
+            rules,
+            sort: SmartSort::Title,
+            ..SmartPlaylist::new("1".into(), "Smart".into(), false)
+        }
+    }
+
+    fn titles(tracks: &[Track]) -> Vec<&str> {
+        tracks.iter().map(|t| t.title.as_str()).collect()
+    }
+
+    #[test]
+    fn old_jazz() {
+        let tracks = vec![
+            track("/kind-of-blue", "Modal Jazz", "1959", 400),
+            track("/bitches-brew", "Jazz Fusion", "1970", 400),
+            track("/blue-train", "Hard Bop", "1958", 400),
+        ];
+        let ctx = SmartContext {
+            listen_counts: &HashMap::new(),
+            last_played: &HashMap::new(),
+            favorites: &FavoritesStore::default(),
+            now: NOW,
+        };
+        let smart = playlist(vec![
+            rule(RuleField::Genre, RuleOp::Contains, "jazz"),
+            rule(RuleField::Year, RuleOp::LessThan, "1970"),
+        ]);
+
+        assert_eq!(titles(&smart.evaluate(&tracks, &ctx)), vec!["kind-of-blue"]);
+    }
+
+    #[test]
+    fn unplayed_recent_additions_and_neglected_favorites() {
+        let tracks = vec![
+            track("/new-unplayed", "Pop", "2023", 3),
+            track("/new-played", "Pop", "2023", 3),
+            track("/old-favorite", "Pop", "2001", 900),
+            track("/fresh-favorite", "Pop", "2001", 900),
+        ];
+        let counts = HashMap::from([
+            ("/new-played".to_string(), 2),
+            ("/fresh-favorite".to_string(), 9),
+        ]);
+        let last_played = HashMap::from([
+            ("/new-played".to_string(), NOW - DAY),
+            ("/old-favorite".to_string(), NOW - 300 * DAY),
+            ("/fresh-favorite".to_string(), NOW - 2 * DAY),
+        ]);
+        let favorites = FavoritesStore {
+            local_favorites: vec![
+                PathBuf::from("/old-favorite"),
+                PathBuf::from("/fresh-favorite"),
+            ],
+            ..Default::default()
+        };
+        let ctx = SmartContext {
+            listen_counts: &counts,
+            last_played: &last_played,
+            favorites: &favorites,
+            now: NOW,
+        };
+
+        let unplayed = playlist(vec![
+            rule(RuleField::PlayCount, RuleOp::Is, "0"),
+            rule(RuleField::DateAdded, RuleOp::InLast, "30"),
+        ]);
+        assert_eq!(
+            titles(&unplayed.evaluate(&tracks, &ctx)),
+            vec!["new-unplayed"]
+        );
+
+        let neglected = playlist(vec![
+            Rule::new(RuleField::Favorite),
+            rule(RuleField::LastPlayed, RuleOp::NotInLast, "180"),
+        ]);
+        assert_eq!(
+            titles(&neglected.evaluate(&tracks, &ctx)),
+            vec!["old-favorite"]
+        );
+    }
+
+    #[test]
+    fn sorts_and_limits() {
+        let tracks = vec![
+            track("/a", "Rock", "1990", 10),
+            track("/b", "Rock", "1975", 20),
+            track("/c", "Rock", "2005", 30),
+        ];
+        let ctx = SmartContext {
+            listen_counts: &HashMap::new(),
+            last_played: &HashMap::new(),
+            favorites: &FavoritesStore::default(),
+            now: NOW,
+        };
+        let smart = SmartPlaylist {
+            sort: SmartSort::Year,
+            descending: true,
+            limit: Some(2),
+            ..playlist(vec![])
+        };
+
+        assert_eq!(titles(&smart.evaluate(&tracks, &ctx)), vec!["c", "a"]);
+    }
+}
@@ -0,0 +1,246 @@
+use lofty::config::{ParseOptions, WriteOptions};
+use lofty::error::Result;
+use lofty::file::FileType;
+use lofty::id3::v2::{Frame, Id3v2Tag, PopularimeterFrame};
+use lofty::mpeg::MpegFile;
+use lofty::picture::{Picture, PictureType};
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::{ItemKey, ItemValue, Tag, TagItem, TagType};
+use std::path::{Path, PathBuf};
+
+/// Fields to write to one or more files. `None` keeps whatever each file already
+/// has; empty text, an empty list or a `Some(None)` number clears the field.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TagEdit {
+    pub title: Option<String>,
+    pub artists: Option<Vec<String>>,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub track_number: Option<Option<u32>>,
+    pub disc_number: Option<Option<u32>>,
+    pub date: Option<String>,
+    pub genres: Option<Vec<String>>,
+    /// Front cover image bytes, or `Some(None)` to remove it.
+    pub cover: Option<Option<Vec<u8>>>,
+    pub lyrics: Option<String>,
+}
+
+impl TagEdit {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The tag a file had before [`write_tags`] touched it, for undo.
+#[derive(Clone)]
+pub struct TagBackup {
+    pub path: PathBuf,
+    tag_type: TagType,
+    previous: Option<Tag>,
+}
+
+/// Applies `edit` to the file's primary tag, creating the tag if the file has
+/// none, and returns what was there before.
+pub fn write_tags(path: &Path, edit: &TagEdit) -> Result<TagBackup> {
+    let tagged_file = Probe::open(path)?.read()?;
+    let tag_type = tagged_file.primary_tag_type();
+    let previous = tagged_file.tag(tag_type).cloned();
+
+    let mut tag = previous.clone().unwrap_or_else(|| Tag::new(tag_type));
+    apply(&mut tag, edit)?;
+    tag.save_to_path(path, WriteOptions::default())?;
+
+    Ok(TagBackup {
+        path: path.to_path_buf(),
+        tag_type,
+        previous,
+    })
+}
+
+/// Puts a file's tag back the way it was before the write that produced `backup`.
+pub fn restore(backup: &TagBackup) -> Result<()> {
+    match &backup.previous {
+        Some(tag) => tag.save_to_path(&backup.path, WriteOptions::default()),
+        None => backup.tag_type.remove_from_path(&backup.path),
+    }
+}
+
+/// Identifies our POPM frame; players keep one frame per email address.
+pub(crate) const POPM_EMAIL: &str = "kopuz";
+
+/// Stores a 0–5 star rating in the file: a POPM frame in MP3s, a 0–100
+/// `RATING` field in every other format. `0` clears the rating.
+pub fn write_rating(path: &Path, stars: u8) -> Result<()> {
+    let stars = stars.min(5);
+    let tagged_file = Probe::open(path)?.read()?;
+    let tag_type = tagged_file.primary_tag_type();
+
+    if tag_type == TagType::Id3v2 && tagged_file.file_type() == FileType::Mpeg {
+        let mut file = std::fs::File::open(path)?;
+        let mpeg = MpegFile::read_from(&mut file, ParseOptions::new())?;
+        let mut tag: Id3v2Tag = mpeg.id3v2().cloned().unwrap_or_default();
+        // the common Windows Media Player mapping of stars onto 0–255
+        let rating = [0, 1, 64, 128, 196, 255][stars as usize];
+        tag.insert(Frame::Popularimeter(PopularimeterFrame::new(
+            POPM_EMAIL.to_string(),
+            rating,
+            0,
+        )));
+        return tag.save_to_path(path, WriteOptions::default());
+    }
+
+    let mut tag = tagged_file
+        .tag(tag_type)
+        .cloned()
+        .unwrap_or_else(|| Tag::new(tag_type));
+    let value = if stars == 0 {
+        String::new()
+    } else {
+        (u32::from(stars) * 20).to_string()
+    };
+    set_text(&mut tag, ItemKey::Unknown("RATING".into()), &value);
+    tag.save_to_path(path, WriteOptions::default())
+}
+
+fn apply(tag: &mut Tag, edit: &TagEdit) -> Result<()> {
+    if let Some(title) = &edit.title {
+        set_text(tag, ItemKey::TrackTitle, title);
+    }
+    if let Some(artists) = &edit.artists {
+        // one joined value reads back the same way in every format; see extract_metadata
+        tag.remove_key(&ItemKey::TrackArtists);
+        set_text(tag, ItemKey::TrackArtist, &artists.join("; "));
+    }
+    if let Some(album) = &edit.album {
+        set_text(tag, ItemKey::AlbumTitle, album);
+    }
+    if let Some(album_artist) = &edit.album_artist {
+        set_text(tag, ItemKey::AlbumArtist, album_artist);
+    }
+    if let Some(track_number) = edit.track_number {
+        match track_number {
+            Some(n) => tag.set_track(n),
+            None => tag.remove_track(),
+        }
+    }
+    if let Some(disc_number) = edit.disc_number {
+        match disc_number {
+            Some(n) => tag.set_disk(n),
+            None => tag.remove_disk(),
+        }
+    }
+    if let Some(date) = &edit.date {
+        tag.remove_key(&ItemKey::Year);
+        tag.remove_key(&ItemKey::ReleaseDate);
+        set_text(tag, ItemKey::RecordingDate, date);
+    }
+    if let Some(genres) = &edit.genres {
+        tag.remove_key(&ItemKey::Genre);
+        for genre in genres.iter().map(|g| g.trim()).filter(|g| !g.is_empty()) {
+            tag.push(TagItem::new(
+                ItemKey::Genre,
+                ItemValue::Text(genre.to_string()),
+            ));
+        }
+    }
+    if let Some(cover) = &edit.cover {
+        tag.remove_picture_type(PictureType::CoverFront);
+        if let Some(bytes) = cover {
+            let mut picture = Picture::from_reader(&mut bytes.as_slice())?;
+            picture.set_pic_type(PictureType::CoverFront);
+            tag.push_picture(picture);
+        }
+    }
+    if let Some(lyrics) = &edit.lyrics {
+        set_text(tag, ItemKey::Lyrics, lyrics);
+    }
+    Ok(())
+}
+
+fn set_text(tag: &mut Tag, key: ItemKey, value: &str) {
+    let value = value.trim();
+    if value.is_empty() {
+        tag.remove_key(&key);
+    } else {
+        tag.insert_text(key, value.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TagEdit, restore, write_tags};
+    use lofty::prelude::*;
+    use lofty::probe::Probe;
+    use std::path::Path;
+
+    /// A tenth of a second of 8 kHz mono silence.
+    fn wav() -> Vec<u8> {
+        let samples = 800u32;
+        let data_len = samples * 2;
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(36 + data_len).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&8000u32.to_le_bytes());
+        out.extend_from_slice(&16000u32.to_le_bytes());
+        out.extend_from_slice(&2u16.to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&data_len.to_le_bytes());
+        out.resize(out.len() + data_len as usize, 0);
+        out
+    }
+
+    fn tags(path: &Path) -> (Option<String>, Option<String>) {
+        let file = Probe::open(path).unwrap().read().unwrap();
+        let tag = file.primary_tag();
+        (
+            tag.and_then(|t| t.title().map(|s| s.to_string())),
+            tag.and_then(|t| t.artist().map(|s| s.to_string())),
+        )
+    }
+
+    #[test]
+    fn restoring_backups_undoes_each_write() {
+        let dir = std::env::temp_dir().join(format!("kopuz-tags-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("track.wav");
+        std::fs::write(&path, wav()).unwrap();
+
+        let first = write_tags(
+            &path,
+            &TagEdit {
+                title: Some("Before".into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let second = write_tags(
+            &path,
+            &TagEdit {
+                title: Some("After".into()),
+                artists: Some(vec!["Earth, Wind & Fire".into(), "Guest".into()]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            tags(&path),
+            (
+                Some("After".into()),
+                Some("Earth, Wind & Fire; Guest".into())
+            )
+        );
+
+        restore(&second).unwrap();
+        assert_eq!(tags(&path), (Some("Before".into()), None));
+        restore(&first).unwrap();
+        assert_eq!(tags(&path), (None, None));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
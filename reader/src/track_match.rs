@@ -0,0 +1,230 @@
+use crate::duplicates::match_key;
+use crate::models::Track;
+use std::collections::HashMap;
+
+/// A candidate needs at least this score to be offered at all, which a title
+/// match alone does not reach.
+const MIN_SCORE: u32 = 50;
+/// The best candidate is taken without asking from this score up, as long as
+/// the runner-up trails it by at least `CONFIDENT_MARGIN`.
+const CONFIDENT_SCORE: u32 = 70;
+const CONFIDENT_MARGIN: u32 = 10;
+/// Candidates kept per track for the review screen.
+const MAX_CANDIDATES: usize = 5;
+
+/// Title without bracketed extras or featured artists, so `Song (2011 Remaster)`
+/// and `Song [feat. X]` find `Song`.
+fn core_title(title: &str) -> String {
+    let mut out = String::new();
+    let mut depth = 0usize;
+    for c in title.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => out.push(c),
+            _ => {}
+        }
+    }
+    let key = match_key(&out);
+    let key = [" feat ", " ft ", " featuring "]
+        .iter()
+        .find_map(|sep| key.split_once(sep).map(|(head, _)| head.to_string()))
+        .unwrap_or(key);
+    if key.is_empty() {
+        match_key(title)
+    } else {
+        key
+    }
+}
+
+fn artist_keys(track: &Track) -> Vec<String> {
+    std::iter::once(&track.artist)
+        .chain(&track.artists)
+        .chain(&track.album_artist)
+        .map(|a| match_key(a))
+        .filter(|a| !a.is_empty())
+        .collect()
+}
+
+/// How alike two tracks look, out of 100: title 40, artist 30, album 20 and
+/// length 10. `None` when the titles differ.
+pub fn score(source: &Track, target: &Track) -> Option<u32> {
+    let title = if match_key(&source.title) == match_key(&target.title) {
+        40
+    } else if core_title(&source.title) == core_title(&target.title) {
+        30
+    } else {
+        return None;
+    };
+
+    let ours = artist_keys(source);
+    let theirs = artist_keys(target);
+    let artist = if ours.is_empty() || theirs.is_empty() {
+        10
+    } else if ours.iter().any(|a| theirs.contains(a)) {
+        30
+    } else if ours.iter().any(|a| {
+        theirs
+            .iter()
+            .any(|b| a.contains(b.as_str()) || b.contains(a.as_str()))
+    }) {
+        20
+    } else {
+        0
+    };
+
+    let album = match (match_key(&source.album), match_key(&target.album)) {
+        (a, b) if !a.is_empty() && a == b => 20,
+        _ => 0,
+    };
+
+    let duration = match source.duration.abs_diff(target.duration) {
+        _ if source.duration == 0 || target.duration == 0 => 5,
+        0..=2 => 10,
+        3..=5 => 5,
+        _ => 0,
+    };
+
+    Some(title + artist + album + duration)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchStatus {
+    Matched,
+    Ambiguous,
+    Missing,
+}
+
+/// The candidates found for one track, best first, and which one is used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackMatch {
+    pub source: Track,
+    pub candidates: Vec<(Track, u32)>,
+    pub chosen: Option<usize>,
+    pub status: MatchStatus,
+}
+
+impl TrackMatch {
+    pub fn chosen_track(&self) -> Option<&Track> {
+        self.chosen
+            .and_then(|i| self.candidates.get(i))
+            .map(|(track, _)| track)
+    }
+}
+
+/// Looks up every source track among `targets`. Ambiguous tracks get their
+/// best guess preselected; missing ones are left out until the user decides.
+pub fn match_tracks(sources: &[Track], targets: &[Track]) -> Vec<TrackMatch> {
+    let mut by_title: HashMap<String, Vec<&Track>> = HashMap::new();
+    for track in targets {
+        by_title
+            .entry(core_title(&track.title))
+            .or_default()
+            .push(track);
+    }
+
+    sources
+        .iter()
+        .map(|source| {
+            let mut candidates: Vec<(Track, u32)> = by_title
+                .get(&core_title(&source.title))
+                .into_iter()
+                .flatten()
+                .filter_map(|t| Some(((*t).clone(), score(source, t)?)))
+                .filter(|(_, s)| *s >= MIN_SCORE)
+                .collect();
+            candidates.sort_by_key(|(_, s)| std::cmp::Reverse(*s));
+            candidates.truncate(MAX_CANDIDATES);
+
+            let status = match candidates.as_slice() {
+                [] => MatchStatus::Missing,
+                [(_, best), rest @ ..]
+                    if *best >= CONFIDENT_SCORE
+                        && rest.first().is_none_or(|r| r.1 + CONFIDENT_MARGIN <= *best) =>
+                {
+                    MatchStatus::Matched
+                }
+                _ => MatchStatus::Ambiguous,
+            };
+            TrackMatch {
+                source: source.clone(),
+                chosen: (!candidates.is_empty()).then_some(0),
+                candidates,
+                status,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MatchStatus, match_tracks};
+    use crate::models::Track;
+    use std::path::PathBuf;
+
+    fn track(path: &str, artist: &str, title: &str, album: &str, duration: u64) -> Track {
+        Track {
+            path: PathBuf::from(path),
+            artist: artist.into(),
+            title: title.into(),
+            album: album.into(),
+            duration,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sorts_sources_into_matched_ambiguous_and_missing() {
+        let local = vec![
+            track(
+                "/m/a.flac",
+                "Radiohead",
+                "Paranoid Android",
+                "OK Computer",
+                387,
+            ),
+            track("/m/b.flac", "Radiohead", "Creep", "Pablo Honey", 238),
+            track(
+                "/m/c.flac",
+                "Boards of Canada",
+                "Roygbiv",
+                "Music Has the Right",
+                151,
+            ),
+        ];
+        let server = vec![
+            track(
+                "jellyfin:1",
+                "Radiohead",
+                "Paranoid Android (Remastered)",
+                "OK Computer OKNOTOK",
+                386,
+            ),
+            // the same song twice, neither on the local album
+            track("jellyfin:2", "Radiohead", "Creep", "Best Of", 238),
+            track("jellyfin:3", "Radiohead", "Creep", "Live at Reading", 241),
+            track(
+                "jellyfin:4",
+                "Someone Else",
+                "Paranoid Android",
+                "Covers",
+                300,
+            ),
+        ];
+
+        let matches = match_tracks(&local, &server);
+
+        assert_eq!(matches[0].status, MatchStatus::Matched);
+        assert_eq!(
+            matches[0].chosen_track().unwrap().path,
+            PathBuf::from("jellyfin:1")
+        );
+        assert_eq!(matches[1].status, MatchStatus::Ambiguous);
+        assert_eq!(
+            matches[1].chosen_track().unwrap().path,
+            PathBuf::from("jellyfin:2")
+        );
+        assert_eq!(matches[2].status, MatchStatus::Missing);
+        assert_eq!(matches[2].chosen, None);
+    }
+}
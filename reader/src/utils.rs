@@ -1,21 +1,294 @@
+use super::models::stable_hash;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
+use utils::artwork::{COVER_SIZES, ImageKind, generate_variants, variant_path, write_atomically};
 
-pub fn find_folder_cover(dir: &Path) -> Option<PathBuf> {
-    let candidates = ["cover.jpg", "cover.png", "folder.jpg", "album.jpg"];
+const IMAGE_EXTENSIONS: [&str; 6] = ["jpg", "jpeg", "png", "webp", "gif", "bmp"];
+const ART_SUBFOLDERS: [&str; 2] = ["scans", "artwork"];
 
-    for name in candidates {
-        let p = dir.join(name);
-        if p.exists() {
-            return Some(p);
+/// Finds the folder image for an album. `names` are tried in priority order
+/// against file names without their extension, ignoring case; `*` matches any
+/// run of characters and `{album}` stands for the album title. Each name is
+/// looked for in `dir`, then in its `Scans/` and `Artwork/` subfolders. Disc
+/// folders such as `CD1/` or `Disc 2/` fall back to their parent's cover.
+pub fn find_folder_cover(dir: &Path, album: &str, names: &[String]) -> Option<PathBuf> {
+    let mut search_dirs = vec![dir.to_path_buf()];
+    search_dirs.extend(art_subfolders(dir));
+    if dir
+        .file_name()
+        .is_some_and(|name| is_disc_folder(&name.to_string_lossy()))
+        && let Some(parent) = dir.parent()
+    {
+        search_dirs.push(parent.to_path_buf());
+        search_dirs.extend(art_subfolders(parent));
+    }
+
+    let images: Vec<Vec<(String, PathBuf)>> = search_dirs.iter().map(|d| image_files(d)).collect();
+    let album = album.to_lowercase();
+
+    names.iter().find_map(|name| {
+        let pattern = name.trim().to_lowercase().replace("{album}", &album);
+        if pattern.is_empty() {
+            return None;
+        }
+        images.iter().find_map(|files| {
+            files
+                .iter()
+                .find(|(stem, _)| matches_name(&pattern, stem))
+                .map(|(_, path)| path.clone())
+        })
+    })
+}
+
+/// Image files in `dir` as (lowercased stem, path), sorted so ties between
+/// `front.jpg` and `front.png` resolve the same way on every platform.
+fn image_files(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+                && path.is_file()
+        })
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_string_lossy().to_lowercase();
+            Some((stem, path))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn art_subfolders(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                && path.file_name().is_some_and(|n| {
+                    ART_SUBFOLDERS.contains(&n.to_string_lossy().to_lowercase().as_str())
+                })
+        })
+        .collect();
+    found.sort();
+    found
+}
+
+/// Glob match where `*` is the only special character. Both sides are lowercase.
+fn matches_name(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// `CD1`, `cd 2`, `Disc 3`, `Disk_4 - Bonus` and the like.
+fn is_disc_folder(name: &str) -> bool {
+    disc_label_number(name).is_some()
+}
+
+/// The number in a disc label such as a `CD2/` folder or the `Disc 1 of 2` in an
+/// album title. Anything after the number is ignored.
+pub(crate) fn disc_label_number(label: &str) -> Option<u32> {
+    let label = label.trim().to_lowercase();
+    ["cd", "disc", "disk"].iter().find_map(|prefix| {
+        let rest = label
+            .strip_prefix(prefix)?
+            .trim_start_matches([' ', '_', '-', '.']);
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .map_or(rest, |end| &rest[..end]);
+        digits.parse().ok()
+    })
+}
+
+/// Splits a disc suffix off an album title, so `Album (Disc 2)`, `Album [CD 2]`
+/// and `Album - Disc 2` all become `Album` with disc 2.
+pub(crate) fn split_disc_suffix(title: &str) -> (&str, Option<u32>) {
+    let trimmed = title.trim_end();
+    let split = match trimmed.chars().last() {
+        Some(close @ (')' | ']')) => {
+            let open = if close == ')' { '(' } else { '[' };
+            trimmed[..trimmed.len() - 1]
+                .rfind(open)
+                .map(|i| (&trimmed[..i], &trimmed[i + 1..trimmed.len() - 1]))
+        }
+        // without brackets the label must be the end of the title: `Album CD2`
+        _ => {
+            let lower = trimmed.to_ascii_lowercase();
+            [" cd", " disc", " disk"]
+                .iter()
+                .filter_map(|word| lower.rfind(word))
+                .max()
+                .map(|i| (&trimmed[..i], &trimmed[i + 1..]))
+                .filter(|(_, label)| {
+                    label
+                        .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+                        .split([' ', '_', '-', '.', '/'])
+                        .filter(|word| !word.is_empty())
+                        .all(|word| word == "of" || word.chars().all(|c| c.is_ascii_digit()))
+                })
+        }
+    };
+
+    match split.and_then(|(head, label)| Some((head, disc_label_number(label)?))) {
+        Some((head, disc)) => {
+            let head = head.trim_end_matches([' ', '-', ',', ':', '–']);
+            if head.is_empty() {
+                (title, None)
+            } else {
+                (head, Some(disc))
+            }
+        }
+        None => (title, None),
+    }
+}
+
+/// Looks for an `artist.jpg`/`artist.png` next to the album folder, or in it for
+/// flat layouts where albums sit directly under the artist.
+pub fn find_artist_image(album_dir: &Path) -> Option<PathBuf> {
+    let candidates = ["artist.jpg", "artist.png"];
+
+    for dir in [album_dir.parent(), Some(album_dir)].into_iter().flatten() {
+        for name in candidates {
+            let p = dir.join(name);
+            if p.exists() {
+                return Some(p);
+            }
         }
     }
     None
 }
 
-pub fn save_cover(album_id: &str, data: &[u8], cache_dir: &Path) -> std::io::Result<PathBuf> {
+/// Copies cover art into the cache under a hash of its content, so albums that
+/// share artwork share one file, and pre-generates the smaller variants the
+/// `artwork://` handler serves to grids and rows.
+pub fn save_cover(data: &[u8], cache_dir: &Path) -> std::io::Result<PathBuf> {
+    let kind = ImageKind::detect(data).ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, "unrecognised image format")
+    })?;
+    let path = cache_dir.join(format!("{:016x}.{}", stable_hash(data), kind.extension()));
+    if path.exists() {
+        return Ok(path);
+    }
+
     fs::create_dir_all(cache_dir)?;
-    let path = cache_dir.join(format!("{album_id}.jpg"));
-    fs::write(&path, data)?;
+    write_atomically(&path, data)?;
+    // variants only save bandwidth, the original is still usable without them
+    let _ = generate_variants(&path);
     Ok(path)
 }
+
+/// Deletes the cached covers, and their variants, that none of `covers` point
+/// to: art replaced by a retag and files from older cache layouts. Returns how
+/// many files went.
+pub fn sweep_covers<'a>(
+    cache_dir: &Path,
+    covers: impl IntoIterator<Item = &'a Path>,
+) -> std::io::Result<usize> {
+    let mut keep = HashSet::new();
+    for cover in covers {
+        keep.extend(COVER_SIZES.iter().map(|size| variant_path(cover, *size)));
+        keep.insert(cover.to_path_buf());
+    }
+
+    let mut removed = 0;
+    for entry in fs::read_dir(cache_dir)?.flatten() {
+        let path = entry.path();
+        if !path.is_file() || keep.contains(&path) {
+            continue;
+        }
+        if fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_disc_folder, matches_name, split_disc_suffix, sweep_covers};
+
+    #[test]
+    fn cover_names_match_with_wildcards() {
+        assert!(matches_name("cover", "cover"));
+        assert!(!matches_name("cover", "cover2"));
+        assert!(matches_name("albumart*", "albumartsmall"));
+        assert!(matches_name("albumart*", "albumart"));
+        assert!(matches_name("*front*", "01 - front cover"));
+        assert!(!matches_name("front", "back"));
+    }
+
+    #[test]
+    fn sweeping_keeps_referenced_covers_and_their_variants() {
+        let dir = std::env::temp_dir().join(format!("kopuz-sweep-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "kept.png",
+            "kept_96.jpg",
+            "old.jpg",
+            "old_320.jpg",
+            "album1.jpg",
+        ] {
+            std::fs::write(dir.join(name), b"x").unwrap();
+        }
+
+        let kept = dir.join("kept.png");
+        let removed = sweep_covers(&dir, [kept.as_path()]).unwrap();
+        assert_eq!(removed, 3);
+        assert!(kept.exists());
+        assert!(dir.join("kept_96.jpg").exists());
+        assert!(!dir.join("old_320.jpg").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn recognises_disc_folders() {
+        assert!(is_disc_folder("CD1"));
+        assert!(is_disc_folder("Disc 2"));
+        assert!(is_disc_folder("disk_3 - Bonus"));
+        assert!(!is_disc_folder("Discovery"));
+        assert!(!is_disc_folder("CDs"));
+    }
+
+    #[test]
+    fn strips_disc_suffix_from_album_titles() {
+        assert_eq!(
+            split_disc_suffix("Mellon Collie (Disc 2)"),
+            ("Mellon Collie", Some(2))
+        );
+        assert_eq!(split_disc_suffix("The Wall [CD 1]"), ("The Wall", Some(1)));
+        assert_eq!(split_disc_suffix("Live - Disc 3 of 4"), ("Live", Some(3)));
+        assert_eq!(
+            split_disc_suffix("Sandinista! CD2"),
+            ("Sandinista!", Some(2))
+        );
+        assert_eq!(split_disc_suffix("Discovery"), ("Discovery", None));
+        assert_eq!(
+            split_disc_suffix("Hits (Remastered)"),
+            ("Hits (Remastered)", None)
+        );
+        assert_eq!(split_disc_suffix("CD1"), ("CD1", None));
+    }
+}
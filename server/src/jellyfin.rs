@@ -124,6 +124,23 @@ pub struct Item {
     pub container: Option<String>,
     pub bitrate: Option<u32>,
     pub sample_rate: Option<u32>,
+    pub user_data: Option<UserItemData>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct UserItemData {
+    /// Jellyfin keeps ratings on a 0–10 scale.
+    pub rating: Option<f64>,
+}
+
+impl Item {
+    /// The user's rating in stars (1–5), if any.
+    pub fn star_rating(&self) -> Option<u8> {
+        let rating = self.user_data.as_ref()?.rating?;
+        let stars = (rating / 2.0).round().clamp(0.0, 5.0) as u8;
+        (stars > 0).then_some(stars)
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -811,6 +828,26 @@ impl JellyfinClient {
         Ok(())
     }
 
+    /// Stores a 0–5 star rating in the user data of `item_id`; `0` clears it.
+    pub async fn set_rating(&self, item_id: &str, stars: u8) -> Result<(), String> {
+        let user_id = self.user_id()?;
+        let rating = (stars > 0).then(|| f64::from(stars.min(5)) * 2.0);
+        let body = serde_json::json!({ "Rating": rating });
+
+        let resp = self
+            .authorized_request(
+                reqwest::Method::POST,
+                &format!("/UserItems/{}/UserData?userId={}", item_id, user_id),
+            )?
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Self::ensure_success(resp, "Failed to set rating").await?;
+        Ok(())
+    }
+
     pub async fn get_favorite_items(&self) -> Result<Vec<Item>, String> {
         let user_id = self.user_id()?;
         let path = format!("/Users/{}/Items", user_id);
@@ -826,4 +863,22 @@ impl JellyfinClient {
         let items_resp: ItemsResponse = self.request_with_query(&path, &query).await?;
         Ok(items_resp.items)
     }
+
+    /// Songs the server considers a good mix with `item_id`, which may be a
+    /// song, album or artist.
+    pub async fn instant_mix(&self, item_id: &str, limit: usize) -> Result<Vec<Item>, String> {
+        let user_id = self.user_id()?;
+        let path = format!("/Items/{}/InstantMix", item_id);
+
+        let fields = "DateCreated,MediaSources,ImageTags,Genres,ParentIndexNumber,IndexNumber,AlbumId,AlbumArtist,ProductionYear,Container".to_string();
+        let limit = limit.to_string();
+        let query = [
+            ("UserId", user_id),
+            ("Limit", limit.as_str()),
+            ("Fields", fields.as_str()),
+        ];
+
+        let items_resp: ItemsResponse = self.request_with_query(&path, &query).await?;
+        Ok(items_resp.items)
+    }
 }
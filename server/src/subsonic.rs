@@ -59,6 +59,14 @@ pub struct SubsonicSong {
     pub disc_number: Option<u32>,
     pub genre: Option<String>,
     pub cover_art: Option<String>,
+    pub year: Option<u16>,
+    pub size: Option<u64>,
+    pub suffix: Option<String>,
+    pub bit_depth: Option<u8>,
+    pub bpm: Option<u32>,
+    pub comment: Option<String>,
+    pub user_rating: Option<u8>,
+    pub artist_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -77,6 +85,24 @@ pub struct SubsonicArtist {
     pub cover_art: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsonicBookmark {
+    /// Milliseconds into the song.
+    pub position: u64,
+    /// ISO 8601 time of the last change.
+    #[serde(default)]
+    pub changed: String,
+    pub entry: SubsonicSong,
+}
+
+impl SubsonicBookmark {
+    /// `changed` as Unix seconds, if the server sent a time we can read.
+    pub fn changed_at(&self) -> Option<u64> {
+        parse_iso8601(&self.changed)
+    }
+}
+
 #[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct EmptyData {}
@@ -179,6 +205,78 @@ struct PlaylistCreationData {
     playlist: Option<SubsonicPlaylist>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimilarSongsContainer {
+    #[serde(default)]
+    song: Vec<SubsonicSong>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetSimilarSongs2Data {
+    #[serde(default)]
+    similar_songs2: Option<SimilarSongsContainer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetSongData {
+    #[serde(default)]
+    song: Option<SubsonicSong>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BookmarksContainer {
+    #[serde(default)]
+    bookmark: Vec<SubsonicBookmark>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetBookmarksData {
+    #[serde(default)]
+    bookmarks: Option<BookmarksContainer>,
+}
+
+/// Reads `YYYY-MM-DDTHH:MM:SS` with an optional fraction and a `Z` or
+/// `+HH:MM` offset into Unix seconds.
+fn parse_iso8601(s: &str) -> Option<u64> {
+    let num = |range: std::ops::Range<usize>| s.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+
+    let rest = s
+        .get(19..)?
+        .trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    let offset = match rest.as_bytes().first() {
+        None | Some(b'Z') => 0,
+        Some(sign @ (b'+' | b'-')) => {
+            let hours: i64 = rest.get(1..3)?.parse().ok()?;
+            let minutes: i64 = rest.get(4..6).and_then(|m| m.parse().ok()).unwrap_or(0);
+            let secs = hours * 3600 + minutes * 60;
+            if *sign == b'+' { secs } else { -secs }
+        }
+        Some(_) => return None,
+    };
+
+    // days since 1970-01-01 in the proleptic Gregorian calendar
+    let (y, m) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * m + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86_400 + hour * 3600 + minute * 60 + second - offset;
+    u64::try_from(secs).ok()
+}
+
 impl SubsonicClient {
     pub fn new(base_url: &str, username: &str, password: &str) -> Self {
         let builder = reqwest::Client::builder();
@@ -347,6 +445,112 @@ impl SubsonicClient {
             .map(|_| ())
     }
 
+    /// Sets the 1–5 star rating of a song or album; `0` removes it.
+    pub async fn set_rating(&self, item_id: &str, stars: u8) -> Result<(), String> {
+        self.call::<EmptyData>(
+            "setRating.view",
+            vec![
+                ("id".to_string(), item_id.to_string()),
+                ("rating".to_string(), stars.min(5).to_string()),
+            ],
+        )
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn get_song(&self, song_id: &str) -> Result<Option<SubsonicSong>, String> {
+        let data = self
+            .call::<GetSongData>(
+                "getSong.view",
+                vec![("id".to_string(), song_id.to_string())],
+            )
+            .await?;
+        Ok(data.song)
+    }
+
+    /// Songs by `artist_id` and similar artists, as picked by the server.
+    pub async fn get_similar_songs2(
+        &self,
+        artist_id: &str,
+        count: usize,
+    ) -> Result<Vec<SubsonicSong>, String> {
+        let data = self
+            .call::<GetSimilarSongs2Data>(
+                "getSimilarSongs2.view",
+                vec![
+                    ("id".to_string(), artist_id.to_string()),
+                    ("count".to_string(), count.to_string()),
+                ],
+            )
+            .await?;
+        Ok(data.similar_songs2.map(|s| s.song).unwrap_or_default())
+    }
+
+    pub async fn get_bookmarks(&self) -> Result<Vec<SubsonicBookmark>, String> {
+        let data = self
+            .call::<GetBookmarksData>("getBookmarks.view", vec![])
+            .await?;
+        Ok(data.bookmarks.map(|b| b.bookmark).unwrap_or_default())
+    }
+
+    pub async fn create_bookmark(&self, item_id: &str, position_ms: u64) -> Result<(), String> {
+        self.call::<EmptyData>(
+            "createBookmark.view",
+            vec![
+                ("id".to_string(), item_id.to_string()),
+                ("position".to_string(), position_ms.to_string()),
+            ],
+        )
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn delete_bookmark(&self, item_id: &str) -> Result<(), String> {
+        self.call::<EmptyData>(
+            "deleteBookmark.view",
+            vec![("id".to_string(), item_id.to_string())],
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// The original file, as uploaded to the server.
+    pub fn download_url(&self, item_id: &str) -> Result<String, String> {
+        self.rest_url("download.view", &[("id", item_id)])
+    }
+
+    /// A stream transcoded to `format` at up to `max_bit_rate` kbps.
+    pub fn transcoded_stream_url(
+        &self,
+        item_id: &str,
+        format: &str,
+        max_bit_rate: u32,
+    ) -> Result<String, String> {
+        self.rest_url(
+            "stream.view",
+            &[
+                ("id", item_id),
+                ("format", format),
+                ("maxBitRate", &max_bit_rate.to_string()),
+            ],
+        )
+    }
+
+    fn rest_url(&self, endpoint: &str, params: &[(&str, &str)]) -> Result<String, String> {
+        let mut url = reqwest::Url::parse(&format!("{}/rest/{}", self.base_url, endpoint))
+            .map_err(|e| format!("Invalid Subsonic base URL '{}': {}", self.base_url, e))?;
+        {
+            let mut pairs = url.query_pairs_mut();
+            for (k, v) in self.auth_params() {
+                pairs.append_pair(&k, &v);
+            }
+            for (k, v) in params {
+                pairs.append_pair(k, v);
+            }
+        }
+        Ok(url.to_string())
+    }
+
     pub fn stream_url(&self, item_id: &str) -> Result<String, String> {
         let mut url = reqwest::Url::parse(&format!("{}/rest/stream.view", self.base_url))
             .map_err(|e| format!("Invalid Subsonic base URL '{}': {}", self.base_url, e))?;
@@ -0,0 +1,193 @@
+use std::path::{Path, PathBuf};
+
+/// Longest edge, in pixels, of the JPEG variants kept next to every cached cover.
+pub const COVER_SIZES: [u32; 3] = [96, 320, 640];
+
+/// Image format sniffed from the data itself, since tags and folder files often
+/// carry PNGs behind a `.jpg` name or no name at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageKind {
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+    Bmp,
+}
+
+impl ImageKind {
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        match data {
+            [0xFF, 0xD8, 0xFF, ..] => Some(Self::Jpeg),
+            [0x89, b'P', b'N', b'G', ..] => Some(Self::Png),
+            [b'G', b'I', b'F', b'8', ..] => Some(Self::Gif),
+            [
+                b'R',
+                b'I',
+                b'F',
+                b'F',
+                _,
+                _,
+                _,
+                _,
+                b'W',
+                b'E',
+                b'B',
+                b'P',
+                ..,
+            ] => Some(Self::Webp),
+            [b'B', b'M', ..] => Some(Self::Bmp),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+            Self::Gif => "gif",
+            Self::Webp => "webp",
+            Self::Bmp => "bmp",
+        }
+    }
+
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+            Self::Gif => "image/gif",
+            Self::Webp => "image/webp",
+            Self::Bmp => "image/bmp",
+        }
+    }
+}
+
+pub fn variant_path(original: &Path, size: u32) -> PathBuf {
+    let stem = original
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    original.with_file_name(format!("{stem}_{size}.jpg"))
+}
+
+/// The smallest pre-generated variant that is at least `size` pixels, or the
+/// original when none is big enough. `0` always means the original.
+pub fn sized_path(original: &Path, size: u32) -> PathBuf {
+    if size == 0 {
+        return original.to_path_buf();
+    }
+    COVER_SIZES
+        .iter()
+        .filter(|s| **s >= size)
+        .map(|s| variant_path(original, *s))
+        .find(|p| p.exists())
+        .unwrap_or_else(|| original.to_path_buf())
+}
+
+/// Writes `data` beside `path` first and renames it into place, so readers and
+/// racing writers never see a half-written file, and a crash or a full disk
+/// halfway through leaves the previous file intact.
+pub fn write_atomically(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static TMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let tmp = path.with_extension(format!(
+        "{}-{}.tmp",
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
+/// Writes the JPEG variants that are smaller than the image itself. Requests for
+/// bigger sizes fall back to the original in [`sized_path`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn generate_variants(original: &Path) -> image::ImageResult<()> {
+    use image::codecs::jpeg::JpegEncoder;
+
+    let img = image::ImageReader::open(original)?
+        .with_guessed_format()?
+        .decode()?;
+    let longest = img.width().max(img.height());
+
+    for size in COVER_SIZES.into_iter().filter(|s| *s < longest) {
+        let out = variant_path(original, size);
+        if out.exists() {
+            continue;
+        }
+        let thumb = img.thumbnail(size, size).to_rgb8();
+        let mut encoded = Vec::new();
+        JpegEncoder::new_with_quality(&mut encoded, 85).encode(
+            thumb.as_raw(),
+            thumb.width(),
+            thumb.height(),
+            image::ExtendedColorType::Rgb8,
+        )?;
+        write_atomically(&out, &encoded)?;
+    }
+    Ok(())
+}
+
+/// Edge length, in pixels, of generated playlist collages.
+pub const COLLAGE_SIZE: u32 = 640;
+
+/// Lays `covers` out row by row in a `side`×`side` grid and encodes it as JPEG.
+/// Covers are cropped to squares, so odd aspect ratios don't leave gaps.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn compose_collage(covers: &[Vec<u8>], side: u32) -> image::ImageResult<Vec<u8>> {
+    use image::codecs::jpeg::JpegEncoder;
+    use image::imageops::{self, FilterType};
+
+    let tile = COLLAGE_SIZE / side.max(1);
+    let mut canvas = image::RgbImage::new(tile * side, tile * side);
+    for (i, data) in covers.iter().take((side * side) as usize).enumerate() {
+        let img = image::load_from_memory(data)?
+            .resize_to_fill(tile, tile, FilterType::Triangle)
+            .to_rgb8();
+        let (col, row) = (i as u32 % side, i as u32 / side);
+        imageops::replace(&mut canvas, &img, (col * tile).into(), (row * tile).into());
+    }
+
+    let mut encoded = Vec::new();
+    JpegEncoder::new_with_quality(&mut encoded, 85).encode(
+        canvas.as_raw(),
+        canvas.width(),
+        canvas.height(),
+        image::ExtendedColorType::Rgb8,
+    )?;
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImageKind, variant_path};
+    use std::path::Path;
+
+    #[test]
+    fn detects_format_from_magic_bytes() {
+        assert_eq!(
+            ImageKind::detect(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A]),
+            Some(ImageKind::Png)
+        );
+        assert_eq!(
+            ImageKind::detect(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(ImageKind::Jpeg)
+        );
+        assert_eq!(
+            ImageKind::detect(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some(ImageKind::Webp)
+        );
+        assert_eq!(ImageKind::detect(b"<svg"), None);
+    }
+
+    #[test]
+    fn variants_sit_next_to_the_original() {
+        assert_eq!(
+            variant_path(Path::new("/cache/covers/abc.png"), 320),
+            Path::new("/cache/covers/abc_320.jpg")
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod artwork;
 pub mod color;
 pub mod jellyfin_image;
 pub mod lyrics;
@@ -20,6 +21,12 @@ pub async fn sleep(duration: std::time::Duration) {
 }
 
 pub fn format_artwork_url(path: Option<&impl AsRef<Path>>) -> Option<String> {
+    format_artwork_url_sized(path, 0)
+}
+
+/// Like [`format_artwork_url`], but asks for the cached variant closest to `size`
+/// pixels so long lists don't decode full-size covers. `0` means full size.
+pub fn format_artwork_url_sized(path: Option<&impl AsRef<Path>>, size: u32) -> Option<String> {
     path.and_then(|p| {
         let p = p.as_ref();
         let p_str = p.to_string_lossy();
@@ -48,17 +55,11 @@ pub fn format_artwork_url(path: Option<&impl AsRef<Path>>) -> Option<String> {
             // TODO: Reduce overhead.
             use std::fs;
 
-            if let Ok(bytes) = fs::read(&*abs_str) {
-                // Determine MIME type, TODO: expand list
-                let mime = if abs_str.ends_with(".png") {
-                    "image/png"
-                } else if abs_str.ends_with(".gif") {
-                    "image/gif"
-                } else if abs_str.ends_with(".webp") {
-                    "image/webp"
-                } else {
-                    "image/jpeg"
-                };
+            let sized = artwork::sized_path(Path::new(&*abs_str), size);
+            if let Ok(bytes) = fs::read(sized) {
+                let mime = artwork::ImageKind::detect(&bytes)
+                    .map(artwork::ImageKind::mime)
+                    .unwrap_or("image/jpeg");
 
                 // Encode to base64
                 use base64::{Engine as _, engine::general_purpose};
@@ -87,10 +88,12 @@ pub fn format_artwork_url(path: Option<&impl AsRef<Path>>) -> Option<String> {
                 .add(b'\\')
                 .add(b':');
 
-            Some(format!(
-                "artwork://local?p={}",
-                percent_encoding::utf8_percent_encode(&abs_str, QUERY_VAL)
-            ))
+            let encoded = percent_encoding::utf8_percent_encode(&abs_str, QUERY_VAL);
+            if size > 0 {
+                Some(format!("artwork://local?p={encoded}&s={size}"))
+            } else {
+                Some(format!("artwork://local?p={encoded}"))
+            }
         }
     })
 }
@@ -79,6 +79,20 @@ pub async fn fetch_lyrics(artist: &str, title: &str, album: &str, duration: u64)
     None
 }
 
+/// Turns lyrics text from a file or tag into [`Lyrics`], synced when it carries
+/// LRC timestamps.
+pub fn parse_lyrics(text: &str) -> Option<Lyrics> {
+    if text.trim().is_empty() {
+        return None;
+    }
+    let lines = parse_lrc(text);
+    if lines.is_empty() {
+        Some(Lyrics::Plain(text.trim().to_string()))
+    } else {
+        Some(Lyrics::Synced(lines))
+    }
+}
+
 fn extract_from_response(data: &LrcResponse) -> Option<Lyrics> {
     if let Some(synced) = &data.synced_lyrics {
         if !synced.trim().is_empty() {